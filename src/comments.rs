@@ -0,0 +1,217 @@
+use crate::de::{Event, Progress};
+use crate::error::Result;
+use crate::libyaml::error::Mark;
+use crate::libyaml::parser::Scalar;
+use crate::loader::Loader;
+use crate::locations::{self, Visitor};
+use crate::path::Path;
+use crate::Value;
+use std::collections::BTreeMap;
+
+/// Comments attached to the keys of YAML maps, keyed by the path of the key.
+///
+/// A path is formed by joining map keys with `.` and appending `[i]` for
+/// sequence elements, like `dependencies.serde` or `servers[0].host`. Each
+/// entry holds the lines of the comment block immediately above the key, with
+/// the leading `#` and one following space removed.
+///
+/// ```
+/// # fn main() -> serde_yaml::Result<()> {
+/// let yaml = "# Name of the service\nname: demo\nports:\n  # Public port\n  http: 80\n";
+/// let (value, comments) = serde_yaml::from_str_with_comments(yaml)?;
+///
+/// assert_eq!(value["ports"]["http"], 80);
+/// assert_eq!(comments.get("name").unwrap(), ["Name of the service"]);
+/// assert_eq!(comments.get("ports.http").unwrap(), ["Public port"]);
+///
+/// assert_eq!(serde_yaml::to_string_with_comments(&value, &comments)?, yaml);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Comments {
+    map: BTreeMap<String, Vec<String>>,
+}
+
+impl Comments {
+    /// Creates an empty set of comments.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the comment lines attached to the key at the given path.
+    #[inline]
+    pub fn get(&self, path: &str) -> Option<&[String]> {
+        self.map.get(path).map(Vec::as_slice)
+    }
+
+    /// Attaches comment lines to the key at the given path, returning the lines
+    /// previously attached to it, if any.
+    #[inline]
+    pub fn insert(&mut self, path: impl Into<String>, lines: Vec<String>) -> Option<Vec<String>> {
+        self.map.insert(path.into(), lines)
+    }
+
+    /// Removes the comment lines attached to the key at the given path.
+    #[inline]
+    pub fn remove(&mut self, path: &str) -> Option<Vec<String>> {
+        self.map.remove(path)
+    }
+
+    /// Returns the number of keys that have comments attached.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if no key has comments attached.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the paths and their comment lines, ordered by
+    /// path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.map
+            .iter()
+            .map(|(path, lines)| (path.as_str(), lines.as_slice()))
+    }
+}
+
+/// Deserialize a `Value` from a string of YAML text, together with the comments
+/// written above the keys of its maps.
+///
+/// Only whole-line comments directly above a map key are kept. Comments that
+/// trail a value on the same line, or that are separated from the next key by
+/// a blank line, are dropped.
+pub fn from_str_with_comments(s: &str) -> Result<(Value, Comments)> {
+    let (value, keys) = crate::de::from_str_with_events(s, key_lines)?;
+    let lines: Vec<&str> = s.lines().collect();
+
+    let mut comments = Comments::new();
+    for key in keys {
+        let mut block = Vec::new();
+        let mut line = key.line;
+        while line > key.floor.map_or(0, |floor| floor + 1) {
+            line -= 1;
+            match lines.get(line).and_then(|line| comment_text(line)) {
+                Some(text) => block.push(text.to_owned()),
+                None => break,
+            }
+        }
+        if !block.is_empty() {
+            block.reverse();
+            comments.insert(key.path, block);
+        }
+    }
+
+    Ok((value, comments))
+}
+
+/// Serialize a `Value` as a String of YAML, writing each comment above the map
+/// key whose path it is attached to, indented as far as the key's line.
+///
+/// Comments whose path does not name a key of `value` are ignored.
+pub fn to_string_with_comments(value: &Value, comments: &Comments) -> Result<String> {
+    let yaml = crate::to_string(value)?;
+    if comments.is_empty() {
+        return Ok(yaml);
+    }
+
+    let mut loader = Loader::new(Progress::Str(&yaml))?;
+    let keys = loader
+        .next_document()
+        .map_or_else(Vec::new, |document| key_lines(&document.events));
+    let mut above = BTreeMap::new();
+    for key in keys {
+        if let Some(lines) = comments.get(&key.path) {
+            above.entry(key.line).or_insert(lines);
+        }
+    }
+
+    let mut out = String::with_capacity(yaml.len());
+    for (i, line) in yaml.lines().enumerate() {
+        if let Some(lines) = above.get(&i) {
+            // A key after `- ` starts further right than its line does.
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for text in *lines {
+                out.push_str(indent);
+                out.push('#');
+                if !text.is_empty() {
+                    out.push(' ');
+                    out.push_str(text);
+                }
+                out.push('\n');
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn comment_text(line: &str) -> Option<&str> {
+    let text = line.trim_start().strip_prefix('#')?;
+    Some(text.strip_prefix(' ').unwrap_or(text).trim_end())
+}
+
+struct KeyLine {
+    path: String,
+    line: usize,
+    /// Last line occupied by the scalar preceding this key, if any. Comment
+    /// lines are only searched for strictly after it.
+    floor: Option<usize>,
+}
+
+fn key_lines(events: &[(Event<'_>, Mark)]) -> Vec<KeyLine> {
+    let mut key_lines = KeyLines {
+        keys: Vec::new(),
+        floor: None,
+    };
+    locations::walk(events, &mut key_lines);
+    key_lines.keys
+}
+
+struct KeyLines {
+    keys: Vec<KeyLine>,
+    floor: Option<usize>,
+}
+
+impl Visitor for KeyLines {
+    fn key(&mut self, path: &Path, scalar: &Scalar, mark: Mark) {
+        self.keys.push(KeyLine {
+            path: key_path(path),
+            line: mark.line() as usize,
+            floor: self.floor,
+        });
+        self.scalar(path, scalar, mark);
+    }
+
+    fn scalar(&mut self, _path: &Path, scalar: &Scalar, mark: Mark) {
+        let newlines = scalar
+            .repr
+            .map_or(0, |repr| repr.iter().filter(|&&b| b == b'\n').count());
+        self.floor = Some(mark.line() as usize + newlines);
+    }
+
+    fn alias(&mut self, _path: &Path, mark: Mark) {
+        self.floor = Some(mark.line() as usize);
+    }
+}
+
+// Formats a path like `servers[0].host`, without the `.` that error messages
+// put before a sequence at the root.
+fn key_path(path: &Path) -> String {
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{}]", key_path(parent), index),
+        Path::Map { parent, key } => match parent {
+            Path::Root => (*key).to_owned(),
+            parent => format!("{}.{}", key_path(parent), key),
+        },
+        Path::Alias { parent } => key_path(parent),
+        Path::Unknown { parent } => format!("{}.?", key_path(parent)),
+    }
+}
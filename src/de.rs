@@ -2,12 +2,19 @@ use crate::error::{self, Error, ErrorImpl};
 use crate::libyaml::error::Mark;
 use crate::libyaml::parser::{MappingStart, Scalar, ScalarStyle, SequenceStart};
 use crate::libyaml::tag::Tag;
-use crate::loader::{Document, Loader};
+use crate::loader::{expand_leading_tabs, read_first_document, read_input, Document, Loader};
+use crate::mapping::Mapping;
 use crate::path::Path;
+use crate::value::binary::{self, BINARY_TAG};
+use crate::value::omap::OMAP_TAG;
+use crate::value::set::SET_TAG;
+use crate::value::Value;
 use serde::de::value::StrDeserializer;
 use serde::de::{
     self, Deserialize, DeserializeOwned, DeserializeSeed, Expected, IgnoredAny, Unexpected, Visitor,
 };
+use serde::forward_to_deserialize_any;
+use std::borrow::Cow;
 use std::fmt;
 use std::io;
 use std::mem;
@@ -57,6 +64,65 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 /// ```
 pub struct Deserializer<'de> {
     progress: Progress<'de>,
+    options: Options<'de>,
+}
+
+type TagResolver<'de> = dyn Fn(&str, Value) -> Result<Value> + 'de;
+
+type ScalarTransform<'de> = dyn Fn(&str) -> Cow<'_, str> + 'de;
+
+#[derive(Clone, Default)]
+struct Options<'de> {
+    tag_resolver: Option<Arc<TagResolver<'de>>>,
+    scalar_transform: Option<Arc<ScalarTransform<'de>>>,
+    yaml_1_1_bools: bool,
+    all_scalars_as_strings: bool,
+    max_aliases: Option<usize>,
+    max_anchor_name_len: Option<usize>,
+    deny_unknown_tags: bool,
+}
+
+impl Options<'_> {
+    fn parse_bool(&self) -> fn(&str) -> Option<bool> {
+        if self.yaml_1_1_bools {
+            parse_bool_yaml_1_1
+        } else {
+            parse_bool
+        }
+    }
+
+    /// Applies the options that rewrite plain scalars before deserializing.
+    fn rewrite_scalars(&self, document: &mut Document<'_>) {
+        if self.scalar_transform.is_none() && !self.all_scalars_as_strings {
+            return;
+        }
+        for (event, _mark) in &mut document.events {
+            let Event::Scalar(scalar) = event else {
+                continue;
+            };
+            if scalar.style != ScalarStyle::Plain {
+                continue;
+            }
+            if let Some(transform) = &self.scalar_transform {
+                transform_scalar(&**transform, scalar);
+            }
+            if self.all_scalars_as_strings && scalar.tag.is_none() {
+                scalar.tag = Some(Tag::new(Tag::STR));
+            }
+        }
+    }
+}
+
+fn transform_scalar(transform: &ScalarTransform<'_>, scalar: &mut Scalar<'_>) {
+    let Ok(value) = str::from_utf8(&scalar.value) else {
+        return;
+    };
+    let transformed = match transform(value) {
+        transformed if transformed == value => return,
+        transformed => transformed.into_owned(),
+    };
+    scalar.value = transformed.into_bytes().into_boxed_slice();
+    scalar.repr = None;
 }
 
 pub(crate) enum Progress<'de> {
@@ -72,13 +138,19 @@ impl<'de> Deserializer<'de> {
     /// Creates a YAML deserializer from a `&str`.
     pub fn from_str(s: &'de str) -> Self {
         let progress = Progress::Str(s);
-        Deserializer { progress }
+        Deserializer {
+            progress,
+            options: Options::default(),
+        }
     }
 
     /// Creates a YAML deserializer from a `&[u8]`.
     pub fn from_slice(v: &'de [u8]) -> Self {
         let progress = Progress::Slice(v);
-        Deserializer { progress }
+        Deserializer {
+            progress,
+            options: Options::default(),
+        }
     }
 
     /// Creates a YAML deserializer from an `io::Read`.
@@ -91,7 +163,230 @@ impl<'de> Deserializer<'de> {
         R: io::Read + 'de,
     {
         let progress = Progress::Read(Box::new(rdr));
-        Deserializer { progress }
+        Deserializer {
+            progress,
+            options: Options::default(),
+        }
+    }
+
+    /// Intercepts tagged nodes such as `!secret value` before they are
+    /// handed to the type being deserialized.
+    ///
+    /// The resolver receives the tag without its leading `!`, along with the
+    /// tagged content as a `Value`, and returns the `Value` to deserialize in
+    /// its place. It applies wherever a tagged node is deserialized without
+    /// a type hint, which includes deserializing into `Value`. Without a
+    /// resolver, such nodes become [`Value::Tagged`].
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "password: !secret db_password\n";
+    /// let de = Deserializer::from_str(yaml).with_tag_resolver(|tag, value| {
+    ///     if tag == "secret" {
+    ///         Ok(Value::from(format!("<{}>", value.as_str().unwrap())))
+    ///     } else {
+    ///         Ok(value)
+    ///     }
+    /// });
+    ///
+    /// let value = Value::deserialize(de).unwrap();
+    /// assert_eq!(value["password"], "<db_password>");
+    /// ```
+    pub fn with_tag_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str, Value) -> Result<Value> + 'de,
+    {
+        self.options.tag_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Rewrites every plain scalar, such as `${PORT}` in `port: ${PORT}`,
+    /// before it is deserialized. This is the place to implement
+    /// interpolation of environment variables or similar placeholders.
+    ///
+    /// The transform runs before the scalar is resolved to a type, so a
+    /// result like `8080` deserializes as a number where one is expected, and
+    /// into `Value` as [`Value::Number`]. Plain mapping keys are rewritten
+    /// too, while quoted and block scalars are left alone. Returning the
+    /// scalar itself keeps it unchanged and borrowed from the input.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    /// use std::borrow::Cow;
+    ///
+    /// let yaml = "host: ${HOST}\nport: ${PORT}\nliteral: '${HOST}'\n";
+    /// let de = Deserializer::from_str(yaml).with_scalar_transform(|scalar| match scalar {
+    ///     "${HOST}" => Cow::Borrowed("localhost"),
+    ///     "${PORT}" => Cow::Borrowed("8080"),
+    ///     _ => Cow::Borrowed(scalar),
+    /// });
+    ///
+    /// let value = Value::deserialize(de).unwrap();
+    /// assert_eq!(value["host"], "localhost");
+    /// assert_eq!(value["port"], 8080);
+    /// assert_eq!(value["literal"], "${HOST}");
+    /// ```
+    pub fn with_scalar_transform<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(&str) -> Cow<'_, str> + 'de,
+    {
+        self.options.scalar_transform = Some(Arc::new(transform));
+        self
+    }
+
+    /// Resolves the YAML 1.1 boolean spellings `yes`, `no`, `on` and `off` to
+    /// booleans, in any combination of upper and lower case, in addition to
+    /// the `true` and `false` of YAML 1.2.
+    ///
+    /// Only plain scalars are affected; `'yes'` and `"off"` remain strings.
+    /// The single letters `y` and `n` are not treated as booleans.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "[yes, Off, 'on', y]";
+    ///
+    /// let value = Value::deserialize(Deserializer::from_str(yaml)).unwrap();
+    /// assert_eq!(value, serde_yaml::from_str::<Value>("[yes, Off, on, y]").unwrap());
+    ///
+    /// let de = Deserializer::from_str(yaml).yaml_1_1_bools();
+    /// let value = Value::deserialize(de).unwrap();
+    /// assert_eq!(value, serde_yaml::from_str::<Value>("[true, false, on, y]").unwrap());
+    /// ```
+    pub fn yaml_1_1_bools(mut self) -> Self {
+        self.options.yaml_1_1_bools = true;
+        self
+    }
+
+    /// Reads every untagged plain scalar as a string, as if it were quoted, so
+    /// that `42`, `true` and `null` become `"42"`, `"true"` and `"null"`, and
+    /// an empty value becomes `""`. Scalars with an explicit tag, like
+    /// `!!int 42`, are still resolved by their tag.
+    ///
+    /// Deserializing such a scalar into a number or boolean type therefore
+    /// fails, as it would for a quoted string.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "[42, true, null, !!int 7]";
+    /// let de = Deserializer::from_str(yaml).all_scalars_as_strings();
+    /// let value = Value::deserialize(de).unwrap();
+    /// assert_eq!(value, serde_yaml::from_str::<Value>("['42', 'true', 'null', 7]").unwrap());
+    /// ```
+    pub fn all_scalars_as_strings(mut self) -> Self {
+        self.options.all_scalars_as_strings = true;
+        self
+    }
+
+    /// Rejects tags other than the standard ones of the YAML core schema, such
+    /// as `!!str` and `!!int`, instead of producing [`Value::Tagged`] or
+    /// ignoring them.
+    ///
+    /// The error names the tag and its location. Tags naming the variant of
+    /// an enum being deserialized, like `!Circle` for a `Shape` enum, are
+    /// still accepted.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "a: !!str 1\nb: !secret hunter2\n";
+    /// assert!(Value::deserialize(Deserializer::from_str(yaml)).is_ok());
+    ///
+    /// let de = Deserializer::from_str(yaml).deny_unknown_tags();
+    /// let error = Value::deserialize(de).unwrap_err();
+    /// assert_eq!(error.to_string(), "b: unknown tag `!secret` at line 2 column 4");
+    /// ```
+    pub fn deny_unknown_tags(mut self) -> Self {
+        self.options.deny_unknown_tags = true;
+        self
+    }
+
+    /// Limits the number of times aliases may be dereferenced while
+    /// deserializing a document, guarding against documents whose aliases
+    /// expand into something enormous.
+    ///
+    /// Every use of an alias counts, including those reached through another
+    /// alias, so nested aliases count once per expansion. Once the limit is
+    /// exceeded, deserialization fails with a "repetition limit exceeded"
+    /// error. By default the limit is 100 times the number of events in the
+    /// document.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "a: &a [1, 2]\nb: [*a, *a, *a]\n";
+    /// assert!(Value::deserialize(Deserializer::from_str(yaml).max_aliases(3)).is_ok());
+    ///
+    /// let error = Value::deserialize(Deserializer::from_str(yaml).max_aliases(2)).unwrap_err();
+    /// assert_eq!(error.to_string(), "repetition limit exceeded");
+    /// ```
+    pub fn max_aliases(mut self, limit: usize) -> Self {
+        self.options.max_aliases = Some(limit);
+        self
+    }
+
+    /// Limits the length in bytes of anchor and alias names, like `base` in
+    /// `&base` and `*base`. A document using a longer name fails with an
+    /// error at that name. By default the limit is 1024 bytes.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "a: &base 1\nb: *base\n";
+    /// assert!(Value::deserialize(Deserializer::from_str(yaml).max_anchor_name_len(4)).is_ok());
+    ///
+    /// let de = Deserializer::from_str(yaml).max_anchor_name_len(3);
+    /// let error = Value::deserialize(de).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "anchor name is longer than 3 bytes at line 1 column 4",
+    /// );
+    /// ```
+    pub fn max_anchor_name_len(mut self, limit: usize) -> Self {
+        self.options.max_anchor_name_len = Some(limit);
+        self
+    }
+
+    /// Replaces tabs in the indentation of each line with `width` spaces
+    /// before parsing, so that input indented with tabs, which YAML does not
+    /// allow, can still be read.
+    ///
+    /// Tabs elsewhere, such as inside quoted scalars or after the
+    /// indentation of a block scalar's content, are left alone. Because the
+    /// input is rewritten, strings are no longer borrowed from it.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_yaml::{Deserializer, Value};
+    ///
+    /// let yaml = "server:\n\thost: localhost\n\tmotd: \"a\tb\"\n";
+    /// assert!(Value::deserialize(Deserializer::from_str(yaml)).is_err());
+    ///
+    /// let value = Value::deserialize(Deserializer::from_str(yaml).expand_tabs(2)).unwrap();
+    /// assert_eq!(value["server"]["host"], "localhost");
+    /// assert_eq!(value["server"]["motd"], "a\tb");
+    /// ```
+    pub fn expand_tabs(mut self, width: usize) -> Self {
+        if let Progress::Str(_) | Progress::Slice(_) | Progress::Read(_) = self.progress {
+            let progress = mem::replace(&mut self.progress, Progress::Str(""));
+            self.progress = match read_input(progress) {
+                Ok(input) => {
+                    let expanded = expand_leading_tabs(&input, width);
+                    Progress::Read(Box::new(io::Cursor::new(expanded)))
+                }
+                Err(err) => Progress::Fail(err.shared()),
+            };
+        }
+        self
     }
 
     fn de<T>(
@@ -100,14 +395,17 @@ impl<'de> Deserializer<'de> {
     ) -> Result<T> {
         let mut pos = 0;
         let mut jumpcount = 0;
+        let options = self.options;
 
         match self.progress {
             Progress::Iterable(_) => return Err(error::new(ErrorImpl::MoreThanOneDocument)),
-            Progress::Document(document) => {
+            Progress::Document(mut document) => {
+                options.rewrite_scalars(&mut document);
                 let t = f(&mut DeserializerFromEvents {
                     document: &document,
                     pos: &mut pos,
                     jumpcount: &mut jumpcount,
+                    options: &options,
                     path: Path::Root,
                     remaining_depth: 128,
                     current_enum: None,
@@ -121,14 +419,19 @@ impl<'de> Deserializer<'de> {
         }
 
         let mut loader = Loader::new(self.progress)?;
-        let document = match loader.next_document() {
+        if let Some(limit) = options.max_anchor_name_len {
+            loader.set_max_anchor_name_len(limit);
+        }
+        let mut document = match loader.next_document() {
             Some(document) => document,
             None => return Err(error::new(ErrorImpl::EndOfStream)),
         };
+        options.rewrite_scalars(&mut document);
         let t = f(&mut DeserializerFromEvents {
             document: &document,
             pos: &mut pos,
             jumpcount: &mut jumpcount,
+            options: &options,
             path: Path::Root,
             remaining_depth: 128,
             current_enum: None,
@@ -153,12 +456,14 @@ impl Iterator for Deserializer<'_> {
                 let document = loader.next_document()?;
                 return Some(Deserializer {
                     progress: Progress::Document(document),
+                    options: self.options.clone(),
                 });
             }
             Progress::Document(_) => return None,
             Progress::Fail(err) => {
                 return Some(Deserializer {
                     progress: Progress::Fail(Arc::clone(err)),
+                    options: self.options.clone(),
                 });
             }
             _ => {}
@@ -167,7 +472,10 @@ impl Iterator for Deserializer<'_> {
         let dummy = Progress::Str("");
         let input = mem::replace(&mut self.progress, dummy);
         match Loader::new(input) {
-            Ok(loader) => {
+            Ok(mut loader) => {
+                if let Some(limit) = self.options.max_anchor_name_len {
+                    loader.set_max_anchor_name_len(limit);
+                }
                 self.progress = Progress::Iterable(loader);
                 self.next()
             }
@@ -176,6 +484,7 @@ impl Iterator for Deserializer<'_> {
                 self.progress = Progress::Fail(Arc::clone(&fail));
                 Some(Deserializer {
                     progress: Progress::Fail(fail),
+                    options: self.options.clone(),
                 })
             }
         }
@@ -433,6 +742,7 @@ struct DeserializerFromEvents<'de, 'document> {
     document: &'document Document<'de>,
     pos: &'document mut usize,
     jumpcount: &'document mut usize,
+    options: &'document Options<'de>,
     path: Path<'document>,
     remaining_depth: u8,
     current_enum: Option<CurrentEnum<'document>>,
@@ -464,11 +774,32 @@ impl<'de, 'document> DeserializerFromEvents<'de, 'document> {
     }
 
     fn next_event_mark(&mut self) -> Result<(&'document Event<'de>, Mark)> {
-        self.peek_event_mark().map(|(event, mark)| {
-            *self.pos += 1;
-            self.current_enum = None;
-            (event, mark)
-        })
+        let (event, mark) = self.peek_event_mark()?;
+        if self.options.deny_unknown_tags {
+            self.check_tag(event, mark)?;
+        }
+        *self.pos += 1;
+        self.current_enum = None;
+        Ok((event, mark))
+    }
+
+    fn check_tag(&self, event: &Event<'de>, mark: Mark) -> Result<()> {
+        let tag = match event {
+            Event::Scalar(scalar) => &scalar.tag,
+            Event::SequenceStart(sequence) => &sequence.tag,
+            Event::MappingStart(mapping) => &mapping.tag,
+            _ => return Ok(()),
+        };
+        let Some(tag) = tag else {
+            return Ok(());
+        };
+        let is_variant = matches!(self.current_enum, Some(CurrentEnum { name: Some(_), .. }));
+        if is_variant || is_core_tag(tag) {
+            return Ok(());
+        }
+        let message = format!("unknown tag `{}`", String::from_utf8_lossy(tag));
+        let err = error::new(ErrorImpl::Message(message, None));
+        Err(error::fix_mark(err, mark, self.path))
     }
 
     fn jump<'anchor>(
@@ -476,7 +807,11 @@ impl<'de, 'document> DeserializerFromEvents<'de, 'document> {
         pos: &'anchor mut usize,
     ) -> Result<DeserializerFromEvents<'de, 'anchor>> {
         *self.jumpcount += 1;
-        if *self.jumpcount > self.document.events.len() * 100 {
+        let limit = match self.options.max_aliases {
+            Some(limit) => limit,
+            None => self.document.events.len() * 100,
+        };
+        if *self.jumpcount > limit {
             return Err(error::new(ErrorImpl::RepetitionLimitExceeded));
         }
         match self.document.aliases.get(pos) {
@@ -486,6 +821,7 @@ impl<'de, 'document> DeserializerFromEvents<'de, 'document> {
                     document: self.document,
                     pos,
                     jumpcount: self.jumpcount,
+                    options: self.options,
                     path: Path::Alias { parent: &self.path },
                     remaining_depth: self.remaining_depth,
                     current_enum: None,
@@ -566,6 +902,80 @@ impl<'de, 'document> DeserializerFromEvents<'de, 'document> {
         Ok(value)
     }
 
+    fn visit_set<V>(&mut self, visitor: V, mark: Mark) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (value, len) = self.recursion_check(mark, |de| {
+            let mut set = SetAccess { de, len: 0 };
+            let value = visitor.visit_seq(&mut set)?;
+            Ok((value, set.len))
+        })?;
+        self.end_mapping(len)?;
+        Ok(value)
+    }
+
+    fn visit_omap<V>(&mut self, visitor: V, mark: Mark) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (value, len) = self.recursion_check(mark, |de| {
+            let mut omap = OmapAccess { de, len: 0 };
+            let value = visitor.visit_seq(&mut omap)?;
+            Ok((value, omap.len))
+        })?;
+        self.end_sequence(len)?;
+        Ok(value)
+    }
+
+    fn visit_omap_map<V>(&mut self, visitor: V, mark: Mark) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (value, len) = self.recursion_check(mark, |de| {
+            let mut omap = OmapAccess { de, len: 0 };
+            let value = visitor.visit_map(&mut omap)?;
+            Ok((value, omap.len))
+        })?;
+        self.end_sequence(len)?;
+        Ok(value)
+    }
+
+    /// Counts the elements of the sequence that the next event starts, or
+    /// returns None if the next event does not start a sequence.
+    fn peek_sequence_len(&self) -> Option<usize> {
+        let events = &self.document.events[*self.pos..];
+        let (Event::SequenceStart(_), _mark) = events.first()? else {
+            return None;
+        };
+        let mut depth = 0;
+        let mut len = 0;
+        for (event, _mark) in &events[1..] {
+            match event {
+                Event::Alias(_) | Event::Scalar(_) => {
+                    if depth == 0 {
+                        len += 1;
+                    }
+                }
+                Event::SequenceStart(_) | Event::MappingStart(_) => {
+                    if depth == 0 {
+                        len += 1;
+                    }
+                    depth += 1;
+                }
+                Event::SequenceEnd | Event::MappingEnd => {
+                    if depth == 0 {
+                        return Some(len);
+                    }
+                    depth -= 1;
+                }
+                Event::Void => return None,
+            }
+        }
+        // The document ended early because of a parse error.
+        None
+    }
+
     fn end_sequence(&mut self, len: usize) -> Result<()> {
         let total = {
             let mut seq = SeqAccess {
@@ -629,6 +1039,33 @@ impl<'de, 'document> DeserializerFromEvents<'de, 'document> {
         }
     }
 
+    fn visit_tagged<V>(&mut self, tag: &'document str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let resolver = match &self.options.tag_resolver {
+            Some(resolver) => resolver,
+            None => {
+                return visitor.visit_enum(EnumAccess {
+                    de: self,
+                    name: None,
+                    tag,
+                });
+            }
+        };
+        let mut content_de = DeserializerFromEvents {
+            document: self.document,
+            pos: self.pos,
+            jumpcount: self.jumpcount,
+            options: self.options,
+            path: self.path,
+            remaining_depth: self.remaining_depth,
+            current_enum: Some(CurrentEnum { name: None, tag }),
+        };
+        let content = Value::deserialize(&mut content_de)?;
+        de::Deserializer::deserialize_any(resolver(tag, content)?, visitor)
+    }
+
     fn recursion_check<F: FnOnce(&mut Self) -> Result<T>, T>(
         &mut self,
         mark: Mark,
@@ -668,6 +1105,7 @@ impl<'de> de::SeqAccess<'de> for SeqAccess<'de, '_, '_> {
                     document: self.de.document,
                     pos: self.de.pos,
                     jumpcount: self.de.jumpcount,
+                    options: self.de.options,
                     path: Path::Seq {
                         parent: &self.de.path,
                         index: self.len,
@@ -682,6 +1120,213 @@ impl<'de> de::SeqAccess<'de> for SeqAccess<'de, '_, '_> {
     }
 }
 
+/// Presents the keys of a `!!set` mapping as a sequence, checking that each
+/// key's value is null.
+struct SetAccess<'de, 'document, 'set> {
+    de: &'set mut DeserializerFromEvents<'de, 'document>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for SetAccess<'de, '_, '_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if let Event::MappingEnd | Event::Void = self.de.peek_event()? {
+            return Ok(None);
+        }
+        let path = Path::Seq {
+            parent: &self.de.path,
+            index: self.len,
+        };
+        let mut element_de = DeserializerFromEvents {
+            document: self.de.document,
+            pos: self.de.pos,
+            jumpcount: self.de.jumpcount,
+            options: self.de.options,
+            path,
+            remaining_depth: self.de.remaining_depth,
+            current_enum: None,
+        };
+        self.len += 1;
+        let element = seed.deserialize(&mut element_de)?;
+        <()>::deserialize(&mut element_de)?;
+        Ok(Some(element))
+    }
+}
+
+/// Presents the elements of an `!!omap` sequence, each a mapping with a single
+/// entry, either as a sequence of key-value pairs or as a map.
+struct OmapAccess<'de, 'document, 'omap> {
+    de: &'omap mut DeserializerFromEvents<'de, 'document>,
+    len: usize,
+}
+
+impl<'de> OmapAccess<'de, '_, '_> {
+    /// Consumes the start of the next element, returning false at the end of
+    /// the sequence.
+    fn start_entry(&mut self) -> Result<bool> {
+        match self.de.peek_event()? {
+            Event::SequenceEnd | Event::Void => return Ok(false),
+            Event::MappingStart(_) => {}
+            _ => return Err(omap_entry_error()),
+        }
+        self.de.next_event()?;
+        if let Event::MappingEnd = self.de.peek_event()? {
+            return Err(omap_entry_error());
+        }
+        self.len += 1;
+        Ok(true)
+    }
+
+    fn end_entry(&mut self) -> Result<()> {
+        match self.de.next_event()? {
+            Event::MappingEnd => Ok(()),
+            _ => Err(omap_entry_error()),
+        }
+    }
+
+    fn entry_de(&mut self) -> DeserializerFromEvents<'de, '_> {
+        DeserializerFromEvents {
+            document: self.de.document,
+            pos: self.de.pos,
+            jumpcount: self.de.jumpcount,
+            options: self.de.options,
+            path: Path::Seq {
+                parent: &self.de.path,
+                index: self.len - 1,
+            },
+            remaining_depth: self.de.remaining_depth,
+            current_enum: None,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for OmapAccess<'de, '_, '_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if !self.start_entry()? {
+            return Ok(None);
+        }
+        let element = seed.deserialize(OmapEntry {
+            de: &mut self.entry_de(),
+            len: 0,
+        })?;
+        self.end_entry()?;
+        Ok(Some(element))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for OmapAccess<'de, '_, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if !self.start_entry()? {
+            return Ok(None);
+        }
+        seed.deserialize(&mut self.entry_de()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(&mut self.entry_de())?;
+        self.end_entry()?;
+        Ok(value)
+    }
+}
+
+fn omap_entry_error() -> Error {
+    de::Error::custom("expected each element of an !!omap to be a mapping with a single entry")
+}
+
+/// Presents the single entry of an `!!omap` element as a key-value pair.
+struct OmapEntry<'de, 'document, 'entry> {
+    de: &'entry mut DeserializerFromEvents<'de, 'document>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for OmapEntry<'de, '_, '_> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.len == 2 {
+            return Ok(None);
+        }
+        self.len += 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(2 - self.len)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for OmapEntry<'de, '_, '_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = visitor.visit_seq(&mut self)?;
+        while de::SeqAccess::next_element::<IgnoredAny>(&mut self)?.is_some() {}
+        Ok(value)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Collects a sequence of integers into bytes for `deserialize_byte_buf`.
+struct ByteBufVisitor {
+    capacity: usize,
+}
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of bytes")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(self.capacity);
+        while let Some(int) = seq.next_element::<i128>()? {
+            match u8::try_from(int) {
+                Ok(byte) => bytes.push(byte),
+                Err(_) => {
+                    return Err(de::Error::custom(format_args!(
+                        "byte at index {} is out of range 0..=255: {}",
+                        bytes.len(),
+                        int,
+                    )));
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
 struct MapAccess<'de, 'document, 'map> {
     empty: bool,
     de: &'map mut DeserializerFromEvents<'de, 'document>,
@@ -722,6 +1367,7 @@ impl<'de> de::MapAccess<'de> for MapAccess<'de, '_, '_> {
             document: self.de.document,
             pos: self.de.pos,
             jumpcount: self.de.jumpcount,
+            options: self.de.options,
             path: if let Some(key) = self.key.and_then(|key| str::from_utf8(key).ok()) {
                 Path::Map {
                     parent: &self.de.path,
@@ -759,6 +1405,7 @@ impl<'de, 'variant> de::EnumAccess<'de> for EnumAccess<'de, '_, 'variant> {
             document: self.de.document,
             pos: self.de.pos,
             jumpcount: self.de.jumpcount,
+            options: self.de.options,
             path: self.de.path,
             remaining_depth: self.de.remaining_depth,
             current_enum: Some(CurrentEnum {
@@ -853,7 +1500,52 @@ impl<'de> de::VariantAccess<'de> for UnitVariantAccess<'de, '_, '_> {
     }
 }
 
-fn visit_scalar<'de, V>(visitor: V, scalar: &Scalar<'de>, tagged_already: bool) -> Result<V::Value>
+/// Presents the single entry of an untagged mapping, as written with
+/// [`EnumStyle::Map`](crate::EnumStyle::Map), as the variant and its content.
+struct MapVariantAccess<'de, 'document, 'variant> {
+    de: &'variant mut DeserializerFromEvents<'de, 'document>,
+}
+
+impl<'de, 'variant> de::EnumAccess<'de> for MapVariantAccess<'de, '_, 'variant> {
+    type Error = Error;
+    type Variant = DeserializerFromEvents<'de, 'variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let key = match self.de.peek_event()? {
+            Event::Scalar(scalar) => str::from_utf8(&scalar.value).ok(),
+            _ => None,
+        };
+        let variant = seed.deserialize(&mut *self.de)?;
+        let visitor = DeserializerFromEvents {
+            document: self.de.document,
+            pos: self.de.pos,
+            jumpcount: self.de.jumpcount,
+            options: self.de.options,
+            path: match key {
+                Some(key) => Path::Map {
+                    parent: &self.de.path,
+                    key,
+                },
+                None => Path::Unknown {
+                    parent: &self.de.path,
+                },
+            },
+            remaining_depth: self.de.remaining_depth,
+            current_enum: None,
+        };
+        Ok((variant, visitor))
+    }
+}
+
+fn visit_scalar<'de, V>(
+    visitor: V,
+    scalar: &Scalar<'de>,
+    tagged_already: bool,
+    parse_bool: fn(&str) -> Option<bool>,
+) -> Result<V::Value>
 where
     V: Visitor<'de>,
 {
@@ -888,10 +1580,10 @@ where
                 None => Err(de::Error::invalid_value(Unexpected::Str(v), &"null")),
             };
         } else if tag.starts_with("!") && scalar.style == ScalarStyle::Plain {
-            return visit_untagged_scalar(visitor, v, scalar.repr, scalar.style);
+            return visit_untagged_scalar(visitor, v, scalar.repr, scalar.style, parse_bool);
         }
-    } else if scalar.style == ScalarStyle::Plain {
-        return visit_untagged_scalar(visitor, v, scalar.repr, scalar.style);
+    } else if scalar.style == ScalarStyle::Plain && !is_binary(scalar) {
+        return visit_untagged_scalar(visitor, v, scalar.repr, scalar.style, parse_bool);
     }
     if let Some(borrowed) = parse_borrowed_str(v, scalar.repr, scalar.style) {
         visitor.visit_borrowed_str(borrowed)
@@ -900,6 +1592,35 @@ where
     }
 }
 
+fn is_core_tag(tag: &Tag) -> bool {
+    [
+        Tag::NULL,
+        Tag::BOOL,
+        Tag::INT,
+        Tag::FLOAT,
+        Tag::STR,
+        Tag::BINARY,
+        Tag::SET,
+        Tag::OMAP,
+        Tag::SEQ,
+        Tag::MAP,
+    ]
+    .iter()
+    .any(|core| tag == *core)
+}
+
+fn is_set(tag: &Option<Tag>) -> bool {
+    tag.as_ref().is_some_and(|tag| tag == Tag::SET)
+}
+
+fn is_omap(tag: &Option<Tag>) -> bool {
+    tag.as_ref().is_some_and(|tag| tag == Tag::OMAP)
+}
+
+fn is_binary(scalar: &Scalar) -> bool {
+    scalar.tag.as_ref().is_some_and(|tag| tag == Tag::BINARY)
+}
+
 fn parse_borrowed_str<'de>(
     utf8_value: &str,
     repr: Option<&'de [u8]>,
@@ -927,7 +1648,7 @@ fn parse_null(scalar: &[u8]) -> Option<()> {
     }
 }
 
-fn parse_bool(scalar: &str) -> Option<bool> {
+pub(crate) fn parse_bool(scalar: &str) -> Option<bool> {
     match scalar {
         "true" | "True" | "TRUE" => Some(true),
         "false" | "False" | "FALSE" => Some(false),
@@ -935,10 +1656,24 @@ fn parse_bool(scalar: &str) -> Option<bool> {
     }
 }
 
+pub(crate) fn parse_bool_yaml_1_1(scalar: &str) -> Option<bool> {
+    if let Some(boolean) = parse_bool(scalar) {
+        return Some(boolean);
+    }
+    if scalar.eq_ignore_ascii_case("yes") || scalar.eq_ignore_ascii_case("on") {
+        Some(true)
+    } else if scalar.eq_ignore_ascii_case("no") || scalar.eq_ignore_ascii_case("off") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 fn parse_unsigned_int<T>(
     scalar: &str,
     from_str_radix: fn(&str, radix: u32) -> Result<T, ParseIntError>,
 ) -> Option<T> {
+    let scalar = &*without_digit_separators(scalar)?;
     let unpositive = scalar.strip_prefix('+').unwrap_or(scalar);
     if let Some(rest) = unpositive.strip_prefix("0x") {
         if rest.starts_with(['+', '-']) {
@@ -977,6 +1712,7 @@ fn parse_signed_int<T>(
     scalar: &str,
     from_str_radix: fn(&str, radix: u32) -> Result<T, ParseIntError>,
 ) -> Option<T> {
+    let scalar = &*without_digit_separators(scalar)?;
     let unpositive = if let Some(unpositive) = scalar.strip_prefix('+') {
         if unpositive.starts_with(['+', '-']) {
             return None;
@@ -1037,6 +1773,7 @@ fn parse_negative_int<T>(
     scalar: &str,
     from_str_radix: fn(&str, radix: u32) -> Result<T, ParseIntError>,
 ) -> Option<T> {
+    let scalar = &*without_digit_separators(scalar)?;
     if let Some(rest) = scalar.strip_prefix("-0x") {
         let negative = format!("-{}", rest);
         if let Ok(int) = from_str_radix(&negative, 16) {
@@ -1061,6 +1798,28 @@ fn parse_negative_int<T>(
     from_str_radix(scalar, 10).ok()
 }
 
+// YAML 1.1 allows digits of an integer to be grouped with underscores, as in
+// `1_000_000` or `0b1010_0101`. Each underscore must sit between two
+// alphanumeric characters; anything else is not a number.
+fn without_digit_separators(scalar: &str) -> Option<Cow<'_, str>> {
+    if !scalar.contains('_') {
+        return Some(Cow::Borrowed(scalar));
+    }
+    let bytes = scalar.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'_' {
+            let before = i.checked_sub(1).map(|i| bytes[i]);
+            let after = bytes.get(i + 1).copied();
+            if !before.is_some_and(|b| b.is_ascii_alphanumeric())
+                || !after.is_some_and(|b| b.is_ascii_alphanumeric())
+            {
+                return None;
+            }
+        }
+    }
+    Some(Cow::Owned(scalar.replace('_', "")))
+}
+
 pub(crate) fn parse_f64(scalar: &str) -> Option<f64> {
     let unpositive = if let Some(unpositive) = scalar.strip_prefix('+') {
         if unpositive.starts_with(['+', '-']) {
@@ -1070,13 +1829,15 @@ pub(crate) fn parse_f64(scalar: &str) -> Option<f64> {
     } else {
         scalar
     };
-    if let ".inf" | ".Inf" | ".INF" = unpositive {
+    // The spec lists `.inf`, `.Inf` and `.INF`, and likewise for `.nan`; any
+    // other casing is accepted too. There is no negative NaN.
+    if unpositive.eq_ignore_ascii_case(".inf") {
         return Some(f64::INFINITY);
     }
-    if let "-.inf" | "-.Inf" | "-.INF" = scalar {
+    if scalar.eq_ignore_ascii_case("-.inf") {
         return Some(f64::NEG_INFINITY);
     }
-    if let ".nan" | ".NaN" | ".NAN" = scalar {
+    if unpositive.eq_ignore_ascii_case(".nan") {
         return Some(f64::NAN.copysign(1.0));
     }
     if let Ok(float) = unpositive.parse::<f64>() {
@@ -1113,11 +1874,26 @@ where
     Err(visitor)
 }
 
+pub(crate) fn visit_char<'de, V>(visitor: V, v: &str) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut chars = v.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => visitor.visit_char(ch),
+        _ => Err(de::Error::invalid_length(
+            v.chars().count(),
+            &"a single character",
+        )),
+    }
+}
+
 pub(crate) fn visit_untagged_scalar<'de, V>(
     visitor: V,
     v: &str,
     repr: Option<&'de [u8]>,
     style: ScalarStyle,
+    parse_bool: fn(&str) -> Option<bool>,
 ) -> Result<V::Value>
 where
     V: Visitor<'de>,
@@ -1150,8 +1926,11 @@ fn is_plain_or_tagged_literal_scalar(
     tagged_already: bool,
 ) -> bool {
     match (scalar.style, &scalar.tag, tagged_already) {
+        // An explicit core schema tag overrides the implicit resolution of
+        // the scalar, whatever its style.
+        (_, Some(tag), false) if tag == expected => true,
+        (_, Some(tag), false) if tag == Tag::STR => false,
         (ScalarStyle::Plain, _, _) => true,
-        (ScalarStyle::Literal, Some(tag), false) => tag == expected,
         _ => false,
     }
 }
@@ -1175,7 +1954,7 @@ fn invalid_type(event: &Event, exp: &dyn Expected) -> Error {
         Event::Alias(_) => unreachable!(),
         Event::Scalar(scalar) => {
             let get_type = InvalidType { exp };
-            match visit_scalar(get_type, scalar, false) {
+            match visit_scalar(get_type, scalar, false, parse_bool) {
                 Ok(void) => match void {},
                 Err(invalid_type) => invalid_type,
             }
@@ -1184,7 +1963,7 @@ fn invalid_type(event: &Event, exp: &dyn Expected) -> Error {
         Event::MappingStart(_) => de::Error::invalid_type(Unexpected::Map, exp),
         Event::SequenceEnd => panic!("unexpected end of sequence"),
         Event::MappingEnd => panic!("unexpected end of mapping"),
-        Event::Void => error::new(ErrorImpl::EndOfStream),
+        Event::Void => error::new(ErrorImpl::EmptyDocument(exp.to_string())),
     }
 }
 
@@ -1221,33 +2000,33 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
                 Event::Scalar(scalar) => {
                     if let Some(tag) = enum_tag(&scalar.tag, tagged_already) {
                         *self.pos -= 1;
-                        break visitor.visit_enum(EnumAccess {
-                            de: self,
-                            name: None,
-                            tag,
-                        });
+                        break self.visit_tagged(tag, visitor);
                     }
-                    break visit_scalar(visitor, scalar, tagged_already);
+                    if !tagged_already && is_binary(scalar) {
+                        *self.pos -= 1;
+                        break self.visit_tagged(BINARY_TAG, visitor);
+                    }
+                    break visit_scalar(visitor, scalar, tagged_already, self.options.parse_bool());
                 }
                 Event::SequenceStart(sequence) => {
                     if let Some(tag) = enum_tag(&sequence.tag, tagged_already) {
                         *self.pos -= 1;
-                        break visitor.visit_enum(EnumAccess {
-                            de: self,
-                            name: None,
-                            tag,
-                        });
+                        break self.visit_tagged(tag, visitor);
+                    }
+                    if !tagged_already && is_omap(&sequence.tag) {
+                        *self.pos -= 1;
+                        break self.visit_tagged(OMAP_TAG, visitor);
                     }
                     break self.visit_sequence(visitor, mark);
                 }
                 Event::MappingStart(mapping) => {
                     if let Some(tag) = enum_tag(&mapping.tag, tagged_already) {
                         *self.pos -= 1;
-                        break visitor.visit_enum(EnumAccess {
-                            de: self,
-                            name: None,
-                            tag,
-                        });
+                        break self.visit_tagged(tag, visitor);
+                    }
+                    if !tagged_already && is_set(&mapping.tag) {
+                        *self.pos -= 1;
+                        break self.visit_tagged(SET_TAG, visitor);
                     }
                     break self.visit_mapping(visitor, mark);
                 }
@@ -1274,7 +2053,7 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
                     if is_plain_or_tagged_literal_scalar(Tag::BOOL, scalar, tagged_already) =>
                 {
                     if let Ok(value) = str::from_utf8(&scalar.value) {
-                        if let Some(boolean) = parse_bool(value) {
+                        if let Some(boolean) = self.options.parse_bool()(value) {
                             break visitor.visit_bool(boolean);
                         }
                     }
@@ -1460,11 +2239,22 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         .map_err(|err| error::fix_mark(err, mark, self.path))
     }
 
+    /// Accepts any scalar of exactly one character, whatever its style, so
+    /// that `5` and `'5'` both give `'5'`.
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_str(visitor)
+        let (next, mark) = self.next_event_mark()?;
+        match next {
+            Event::Scalar(scalar) => match str::from_utf8(&scalar.value) {
+                Ok(v) => visit_char(visitor, v),
+                Err(_) => Err(invalid_type(next, &visitor)),
+            },
+            &Event::Alias(mut pos) => self.jump(&mut pos)?.deserialize_char(visitor),
+            other => Err(invalid_type(other, &visitor)),
+        }
+        .map_err(|err: Error| error::fix_mark(err, mark, self.path))
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
@@ -1497,18 +2287,47 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         self.deserialize_str(visitor)
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(error::new(ErrorImpl::BytesUnsupported))
+        self.deserialize_byte_buf(visitor)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    /// Parses a sequence of integers in `0..=255`, or the base64 content of a
+    /// `!!binary` scalar, as a byte buffer. Other input is not supported.
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(error::new(ErrorImpl::BytesUnsupported))
+        let (next, mark) = self.next_event_mark()?;
+        match next {
+            &Event::Alias(mut pos) => self.jump(&mut pos)?.deserialize_byte_buf(visitor),
+            Event::Scalar(scalar) if is_binary(scalar) => {
+                let expected = "base64 encoded !!binary data";
+                match str::from_utf8(&scalar.value) {
+                    Ok(base64) => match binary::decode(base64) {
+                        Some(bytes) => visitor.visit_byte_buf(bytes),
+                        None => Err(de::Error::invalid_value(Unexpected::Str(base64), &expected)),
+                    },
+                    Err(_) => Err(de::Error::invalid_value(
+                        Unexpected::Bytes(&scalar.value),
+                        &expected,
+                    )),
+                }
+            }
+            Event::SequenceStart(_) => {
+                // Exact for the usual flat sequence of scalars.
+                let capacity = self.document.events[*self.pos..]
+                    .iter()
+                    .take_while(|(event, _mark)| matches!(event, Event::Scalar(_)))
+                    .count();
+                self.visit_sequence(ByteBufVisitor { capacity }, mark)
+                    .and_then(|bytes| visitor.visit_byte_buf(bytes))
+            }
+            _ => Err(error::new(ErrorImpl::BytesUnsupported)),
+        }
+        .map_err(|err| error::fix_mark(err, mark, self.path))
     }
 
     /// Parses `null` as None and any other values as `Some(...)`.
@@ -1523,9 +2342,7 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
             }
             Event::Scalar(scalar) => {
                 let tagged_already = self.current_enum.is_some();
-                if scalar.style != ScalarStyle::Plain {
-                    true
-                } else if let (Some(tag), false) = (&scalar.tag, tagged_already) {
+                if let (Some(tag), false) = (&scalar.tag, tagged_already) {
                     if tag == Tag::NULL {
                         if let Some(()) = parse_null(&scalar.value) {
                             false
@@ -1540,6 +2357,8 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
                     } else {
                         true
                     }
+                } else if scalar.style != ScalarStyle::Plain {
+                    true
                 } else {
                     !scalar.value.is_empty() && parse_null(&scalar.value).is_none()
                 }
@@ -1566,10 +2385,10 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         let (next, mark) = self.next_event_mark()?;
         match next {
             Event::Scalar(scalar) => {
-                let is_null = if scalar.style != ScalarStyle::Plain {
-                    false
-                } else if let (Some(tag), false) = (&scalar.tag, tagged_already) {
+                let is_null = if let (Some(tag), false) = (&scalar.tag, tagged_already) {
                     tag == Tag::NULL && parse_null(&scalar.value).is_some()
+                } else if scalar.style != ScalarStyle::Plain {
+                    false
                 } else {
                     scalar.value.is_empty() || parse_null(&scalar.value).is_some()
                 };
@@ -1614,7 +2433,11 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         let (next, mark) = self.next_event_mark()?;
         match next {
             &Event::Alias(mut pos) => self.jump(&mut pos)?.deserialize_seq(visitor),
+            Event::SequenceStart(sequence) if is_omap(&sequence.tag) => {
+                self.visit_omap(visitor, mark)
+            }
             Event::SequenceStart(_) => self.visit_sequence(visitor, mark),
+            Event::MappingStart(mapping) if is_set(&mapping.tag) => self.visit_set(visitor, mark),
             other => {
                 if match other {
                     Event::Void => true,
@@ -1636,23 +2459,40 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         .map_err(|err| error::fix_mark(err, mark, self.path))
     }
 
-    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    /// Checks the length of the sequence up front, so that arrays and tuples
+    /// report a mismatch as "expected 3 elements, found 5" at the sequence.
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        let (next, mark) = self.peek_event_mark()?;
+        if let &Event::Alias(mut pos) = next {
+            *self.pos += 1;
+            return self.jump(&mut pos)?.deserialize_tuple(len, visitor);
+        }
+        let found = self.peek_sequence_len().unwrap_or(len);
+        if found != len {
+            let message = if len == 1 {
+                format!("expected 1 element, found {}", found)
+            } else {
+                format!("expected {} elements, found {}", len, found)
+            };
+            let err = error::new(ErrorImpl::Message(message, None));
+            return Err(error::fix_mark(err, mark, self.path));
+        }
         self.deserialize_seq(visitor)
     }
 
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_seq(visitor)
+        self.deserialize_tuple(len, visitor)
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
@@ -1663,6 +2503,9 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
         match next {
             &Event::Alias(mut pos) => self.jump(&mut pos)?.deserialize_map(visitor),
             Event::MappingStart(_) => self.visit_mapping(visitor, mark),
+            Event::SequenceStart(sequence) if is_omap(&sequence.tag) => {
+                self.visit_omap_map(visitor, mark)
+            }
             other => {
                 if match other {
                     Event::Void => true,
@@ -1754,9 +2597,17 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
                             tag,
                         });
                     }
-                    let err =
-                        de::Error::invalid_type(Unexpected::Map, &"a YAML tag starting with '!'");
-                    Err(error::fix_mark(err, mark, self.path))
+                    // A mapping with a single entry from the variant to its
+                    // content, as in Serde's externally tagged representation.
+                    *self.pos += 1;
+                    self.recursion_check(mark, |de| {
+                        if let Event::MappingEnd = de.peek_event()? {
+                            return Err(de::Error::invalid_length(0, &"map containing 1 entry"));
+                        }
+                        let value = visitor.visit_enum(MapVariantAccess { de })?;
+                        de.end_mapping(1)?;
+                        Ok(value)
+                    })
                 }
                 Event::SequenceStart(sequence) => {
                     if let Some(tag) = parse_tag(&sequence.tag) {
@@ -1772,7 +2623,10 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
                 }
                 Event::SequenceEnd => panic!("unexpected end of sequence"),
                 Event::MappingEnd => panic!("unexpected end of mapping"),
-                Event::Void => Err(error::new(ErrorImpl::EndOfStream)),
+                Event::Void => {
+                    let expected = format!("enum {}", name);
+                    Err(error::new(ErrorImpl::EmptyDocument(expected)))
+                }
             };
         }
         .map_err(|err| error::fix_mark(err, mark, self.path))
@@ -1803,6 +2657,21 @@ impl<'de> de::Deserializer<'de> for &mut DeserializerFromEvents<'de, '_> {
 /// is wrong with the data, for example required struct fields are missing from
 /// the YAML map or some number is too big to fit in the expected primitive
 /// type.
+///
+/// An empty document, meaning input that is empty or holds only whitespace
+/// and comments, deserializes like `null`: as `Value::Null`, `None` or `()`,
+/// or as an empty sequence, map, or struct whose fields are all optional.
+/// Any other type fails with an error for which [`Error::is_eof`] is true.
+///
+/// ```
+/// use serde_yaml::Value;
+///
+/// assert_eq!(serde_yaml::from_str::<Value>("# comment\n").unwrap(), Value::Null);
+///
+/// let error = serde_yaml::from_str::<u16>("\n").unwrap_err();
+/// assert!(error.is_eof());
+/// assert_eq!(error.to_string(), "empty document, expected u16");
+/// ```
 pub fn from_str<'de, T>(s: &'de str) -> Result<T>
 where
     T: Deserialize<'de>,
@@ -1810,6 +2679,66 @@ where
     T::deserialize(Deserializer::from_str(s))
 }
 
+/// Deserialize a [`Mapping`] from a string of YAML text whose root is a mapping,
+/// the usual shape of a configuration file.
+///
+/// An empty document gives an empty mapping. A tag on the root is ignored.
+/// Any other root is rejected with an error that names what was found
+/// instead, which is clearer than the generic type error of
+/// `from_str::<Mapping>`.
+///
+/// ```
+/// let mapping = serde_yaml::from_str_mapping("name: demo\nport: 80\n").unwrap();
+/// assert_eq!(mapping["port"], 80);
+///
+/// let error = serde_yaml::from_str_mapping("- name: demo\n").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "expected a YAML mapping at the document root, found sequence",
+/// );
+/// ```
+pub fn from_str_mapping(s: &str) -> Result<Mapping> {
+    let value = Value::deserialize(Deserializer::from_str(s))?;
+    match value.untag() {
+        Value::Null => Ok(Mapping::new()),
+        Value::Mapping(mapping) => Ok(mapping),
+        other => {
+            let message = format!(
+                "expected a YAML mapping at the document root, found {}",
+                other.type_name(),
+            );
+            Err(error::new(ErrorImpl::Message(message, None)))
+        }
+    }
+}
+
+/// Deserialize every document in a string of YAML text up to the first error,
+/// returning the documents that were parsed along with that error, if any.
+///
+/// This is meant for input that may have been cut off, such as the contents
+/// of a log that is still being written: the documents before the incomplete
+/// one are recovered instead of failing as a whole. Documents after an error
+/// are not read.
+///
+/// ```
+/// let yaml = "a: 1\n---\nb: 2\n---\nc: [3,";
+/// let (values, error) = serde_yaml::from_str_best_effort(yaml);
+///
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(values[1]["b"], 2);
+/// assert!(error.is_some());
+/// ```
+pub fn from_str_best_effort(s: &str) -> (Vec<Value>, Option<Error>) {
+    let mut values = Vec::new();
+    for document in Deserializer::from_str(s) {
+        match Value::deserialize(document) {
+            Ok(value) => values.push(value),
+            Err(err) => return (values, Some(err)),
+        }
+    }
+    (values, None)
+}
+
 /// Deserialize an instance of type `T` from an IO stream of YAML.
 ///
 /// This conversion can fail if the structure of the Value does not match the
@@ -1827,6 +2756,72 @@ where
     T::deserialize(Deserializer::from_reader(rdr))
 }
 
+/// Deserialize an instance of type `T` from the first document in an IO
+/// stream of YAML, and return the number of bytes read from the stream.
+///
+/// Unlike [`from_reader`], input after the first document is not an error, so
+/// a YAML document can be framed within a larger byte stream. The document
+/// must end with a `...` or `---` line or with the input. Reading stops at
+/// the end of that line, which is included in the count, and what follows is
+/// left in the reader. So to frame documents, end each one with a `...` line:
+/// a `---` line that starts the next document is read along with anything
+/// else on it.
+///
+/// The reader is read a byte at a time, so pass an unbuffered reader such as
+/// a socket in a [`BufReader`](io::BufReader), by reference if the rest of
+/// the stream is wanted afterwards.
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// let input = b"a: 1\n...\nsomething else";
+/// let (map, len): (BTreeMap<String, i32>, usize) =
+///     serde_yaml::from_reader_one(&input[..]).unwrap();
+/// assert_eq!(map["a"], 1);
+/// assert_eq!(&input[len..], b"something else");
+/// ```
+pub fn from_reader_one<R, T>(rdr: R) -> Result<(T, usize)>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    let (input, len) = read_first_document(rdr)?;
+    let mut loader = Loader::new(Progress::Slice(&input))?;
+    let document = match loader.next_document() {
+        Some(document) => document,
+        None => return Err(error::new(ErrorImpl::EndOfStream)),
+    };
+    let deserializer = Deserializer {
+        progress: Progress::Document(document),
+        options: Options::default(),
+    };
+    Ok((T::deserialize(deserializer)?, len))
+}
+
+// Deserializes a `Value` from a string of YAML text as `from_str` does, also
+// handing the events of the document to `f` so that they are parsed once.
+pub(crate) fn from_str_with_events<R>(
+    s: &str,
+    f: impl FnOnce(&[(Event<'_>, Mark)]) -> R,
+) -> Result<(Value, R)> {
+    let mut loader = Loader::new(Progress::Str(s))?;
+    let document = match loader.next_document() {
+        Some(document) => document,
+        None => return Err(error::new(ErrorImpl::EndOfStream)),
+    };
+    let r = f(&document.events);
+    let deserializer = Deserializer {
+        progress: Progress::Document(document),
+        options: Options::default(),
+    };
+    let value = Value::deserialize(deserializer)?;
+    if loader.next_document().is_none() {
+        Ok((value, r))
+    } else {
+        Err(error::new(ErrorImpl::MoreThanOneDocument))
+    }
+}
+
 /// Deserialize an instance of type `T` from bytes of YAML text.
 ///
 /// This conversion can fail if the structure of the Value does not match the
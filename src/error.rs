@@ -2,7 +2,7 @@ use crate::libyaml::{emitter, error as libyaml};
 use crate::path::Path;
 use serde::{de, ser};
 use std::error::Error as StdError;
-use std::fmt::{self, Debug, Display};
+use std::fmt::{self, Debug, Display, Write as _};
 use std::io;
 use std::result;
 use std::string;
@@ -23,11 +23,13 @@ pub(crate) enum ErrorImpl {
     FromUtf8(string::FromUtf8Error),
 
     EndOfStream,
+    EmptyDocument(String),
     MoreThanOneDocument,
     RecursionLimitExceeded(libyaml::Mark),
     RepetitionLimitExceeded,
     BytesUnsupported,
     UnknownAnchor(libyaml::Mark),
+    AnchorNameTooLong(usize, libyaml::Mark),
     SerializeNestedEnum,
     ScalarInMerge,
     TaggedInMerge,
@@ -35,6 +37,9 @@ pub(crate) enum ErrorImpl {
     SequenceInMergeElement,
     EmptyTag,
     FailedToParseNumber,
+    InvalidUtf16,
+    DuplicateKey(String),
+    EscapedTagPrefix(String),
 
     #[cfg(feature = "pretty")]
     FailedToPrettify,
@@ -74,7 +79,7 @@ impl Location {
 
     // This is to keep decoupled with the yaml crate
     #[doc(hidden)]
-    fn from_mark(mark: libyaml::Mark) -> Self {
+    pub(crate) fn from_mark(mark: libyaml::Mark) -> Self {
         Location {
             index: mark.index() as usize,
             // `line` and `column` returned from libyaml are 0-indexed but all error messages add +1 to this value
@@ -105,6 +110,126 @@ impl Error {
     pub fn location(&self) -> Option<Location> {
         self.0.location()
     }
+
+    /// Renders this error followed by the line of `source` that it points at,
+    /// with a `^` under the offending column.
+    ///
+    /// `source` should be the input that produced the error. If the error has
+    /// no location, or the location is not within `source`, this is the same
+    /// as the error's `Display` output.
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// let yaml = "timeout: 30\nport: http\n";
+    /// let err = serde_yaml::from_str::<BTreeMap<String, u16>>(yaml).unwrap_err();
+    /// let expected = "\
+    /// port: invalid type: string \"http\", expected u16 at line 2 column 7
+    ///   |
+    /// 2 | port: http
+    ///   |       ^";
+    /// assert_eq!(err.with_source_snippet(yaml), expected);
+    /// ```
+    pub fn with_source_snippet(&self, source: &str) -> String {
+        let mut snippet = self.to_string();
+        let Some(location) = self.location() else {
+            return snippet;
+        };
+        let Some(line) = source.lines().nth(location.line - 1) else {
+            return snippet;
+        };
+        let number = location.line;
+        let gutter = " ".repeat(number.to_string().len());
+        let indent: String = line
+            .chars()
+            .take(location.column - 1)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let _ = write!(
+            snippet,
+            "\n{gutter} |\n{number} | {line}\n{gutter} | {indent}^"
+        );
+        snippet
+    }
+
+    /// Returns true if this error was caused by a failure to read or write
+    /// bytes on an I/O stream.
+    pub fn is_io(&self) -> bool {
+        self.0.category() == Category::Io
+    }
+
+    /// Returns the I/O error that caused this error, if it was caused by a
+    /// failure to read or write bytes on an I/O stream.
+    ///
+    /// The same error is also exposed as this error's
+    /// [`source`](StdError::source).
+    ///
+    /// ```
+    /// use std::io::{self, Read};
+    ///
+    /// struct Unavailable;
+    ///
+    /// impl Read for Unavailable {
+    ///     fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+    ///         Err(io::ErrorKind::WouldBlock.into())
+    ///     }
+    /// }
+    ///
+    /// let err = serde_yaml::from_reader::<_, serde_yaml::Value>(Unavailable).unwrap_err();
+    /// assert_eq!(err.io_error().unwrap().kind(), io::ErrorKind::WouldBlock);
+    /// ```
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.0.io_error()
+    }
+
+    /// Returns true if this error was caused by input that is not
+    /// syntactically valid YAML.
+    ///
+    /// Input that merely ends too early is reported by [`is_eof`] instead.
+    ///
+    /// [`is_eof`]: Error::is_eof
+    ///
+    /// ```
+    /// let err = serde_yaml::from_str::<serde_yaml::Value>("[1, 2]]").unwrap_err();
+    /// assert!(err.is_syntax());
+    /// ```
+    pub fn is_syntax(&self) -> bool {
+        self.0.category() == Category::Syntax
+    }
+
+    /// Returns true if this error was caused by input that is valid YAML but
+    /// does not match the structure expected by the type being deserialized,
+    /// or by a value that cannot be serialized.
+    ///
+    /// ```
+    /// let err = serde_yaml::from_str::<u8>("[1, 2]").unwrap_err();
+    /// assert!(err.is_data());
+    /// ```
+    pub fn is_data(&self) -> bool {
+        self.0.category() == Category::Data
+    }
+
+    /// Returns true if this error was caused by the input ending before a
+    /// complete document was read.
+    ///
+    /// For input arriving in pieces, such as over a network, this
+    /// distinguishes a stream that was cut short and may succeed once more
+    /// data arrives from one that is invalid.
+    ///
+    /// ```
+    /// let err = serde_yaml::from_str::<serde_yaml::Value>("[1, 2").unwrap_err();
+    /// assert!(err.is_eof());
+    /// ```
+    pub fn is_eof(&self) -> bool {
+        self.0.category() == Category::Eof
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Category {
+    Io,
+    Syntax,
+    Data,
+    Eof,
 }
 
 pub(crate) fn new(inner: ErrorImpl) -> Error {
@@ -187,20 +312,64 @@ impl ErrorImpl {
         self.mark().map(Location::from_mark)
     }
 
+    fn category(&self) -> Category {
+        match self {
+            ErrorImpl::Io(_) => Category::Io,
+            ErrorImpl::EndOfStream | ErrorImpl::EmptyDocument(_) => Category::Eof,
+            ErrorImpl::Libyaml(err) => {
+                if err.is_at_end_of_input() {
+                    Category::Eof
+                } else {
+                    Category::Syntax
+                }
+            }
+            ErrorImpl::MoreThanOneDocument
+            | ErrorImpl::RecursionLimitExceeded(_)
+            | ErrorImpl::UnknownAnchor(_)
+            | ErrorImpl::AnchorNameTooLong(..)
+            | ErrorImpl::InvalidUtf16 => Category::Syntax,
+            ErrorImpl::Message(..)
+            | ErrorImpl::FromUtf8(_)
+            | ErrorImpl::RepetitionLimitExceeded
+            | ErrorImpl::BytesUnsupported
+            | ErrorImpl::SerializeNestedEnum
+            | ErrorImpl::ScalarInMerge
+            | ErrorImpl::TaggedInMerge
+            | ErrorImpl::ScalarInMergeElement
+            | ErrorImpl::SequenceInMergeElement
+            | ErrorImpl::EmptyTag
+            | ErrorImpl::FailedToParseNumber
+            | ErrorImpl::DuplicateKey(_)
+            | ErrorImpl::EscapedTagPrefix(_) => Category::Data,
+            #[cfg(feature = "pretty")]
+            ErrorImpl::FailedToPrettify => Category::Data,
+            ErrorImpl::Shared(err) => err.category(),
+        }
+    }
+
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
-            ErrorImpl::Io(err) => err.source(),
+            ErrorImpl::Io(err) => Some(err),
             ErrorImpl::FromUtf8(err) => err.source(),
             ErrorImpl::Shared(err) => err.source(),
             _ => None,
         }
     }
 
+    fn io_error(&self) -> Option<&io::Error> {
+        match self {
+            ErrorImpl::Io(err) => Some(err),
+            ErrorImpl::Shared(err) => err.io_error(),
+            _ => None,
+        }
+    }
+
     fn mark(&self) -> Option<libyaml::Mark> {
         match self {
             ErrorImpl::Message(_, Some(Pos { mark, path: _ }))
             | ErrorImpl::RecursionLimitExceeded(mark)
-            | ErrorImpl::UnknownAnchor(mark) => Some(*mark),
+            | ErrorImpl::UnknownAnchor(mark)
+            | ErrorImpl::AnchorNameTooLong(_, mark) => Some(*mark),
             ErrorImpl::Libyaml(err) => Some(err.mark()),
             ErrorImpl::Shared(err) => err.mark(),
             _ => None,
@@ -220,6 +389,9 @@ impl ErrorImpl {
             ErrorImpl::Io(err) => Display::fmt(err, f),
             ErrorImpl::FromUtf8(err) => Display::fmt(err, f),
             ErrorImpl::EndOfStream => f.write_str("EOF while parsing a value"),
+            ErrorImpl::EmptyDocument(expected) => {
+                write!(f, "empty document, expected {}", expected)
+            }
             ErrorImpl::MoreThanOneDocument => f.write_str(
                 "deserializing from YAML containing more than one document is not supported",
             ),
@@ -229,6 +401,9 @@ impl ErrorImpl {
                 f.write_str("serialization and deserialization of bytes in YAML is not implemented")
             }
             ErrorImpl::UnknownAnchor(_mark) => f.write_str("unknown anchor"),
+            ErrorImpl::AnchorNameTooLong(limit, _mark) => {
+                write!(f, "anchor name is longer than {} bytes", limit)
+            }
             ErrorImpl::SerializeNestedEnum => {
                 f.write_str("serializing nested enums in YAML is not supported yet")
             }
@@ -244,6 +419,15 @@ impl ErrorImpl {
             }
             ErrorImpl::EmptyTag => f.write_str("empty YAML tag is not allowed"),
             ErrorImpl::FailedToParseNumber => f.write_str("failed to parse YAML number"),
+            ErrorImpl::InvalidUtf16 => {
+                f.write_str("input starts with a UTF-16 byte order mark but is not valid UTF-16")
+            }
+            ErrorImpl::DuplicateKey(key) => {
+                write!(f, "more than one key of a map is written as {:?}", key)
+            }
+            ErrorImpl::EscapedTagPrefix(prefix) => {
+                write!(f, "%TAG prefix {:?} would be percent-escaped", prefix)
+            }
             ErrorImpl::Shared(_) => unreachable!(),
 
             #[cfg(feature = "pretty")]
@@ -160,25 +160,37 @@
     clippy::must_use_candidate,
 )]
 
-pub use crate::de::{from_reader, from_slice, from_str, Deserializer};
+pub use crate::comments::{from_str_with_comments, to_string_with_comments, Comments};
+pub use crate::de::{
+    from_reader, from_reader_one, from_slice, from_str, from_str_best_effort, from_str_mapping,
+    Deserializer,
+};
 pub use crate::error::{Error, Location, Result};
-pub use crate::ser::{to_string, to_writer, Serializer};
+pub use crate::locations::from_str_with_locations;
+pub use crate::ser::{
+    to_string, to_string_spaced, to_vec, to_writer, BoolStyle, EmptyStyle, EnumStyle, KeyCase,
+    NullStyle, QuoteStyle, Serializer,
+};
 
 #[cfg(feature = "pretty")]
 pub use crate::ser::to_string_pretty;
 
 #[doc(inline)]
-pub use crate::value::{from_value, to_value, Index, Number, Sequence, Value};
+pub use crate::value::{from_value, from_value_ref, to_value, Index, Number, Sequence, Value};
 
 #[doc(inline)]
 pub use crate::mapping::Mapping;
 
+mod comments;
 mod de;
 mod error;
 mod libyaml;
 mod loader;
+mod locations;
+mod macros;
 pub mod mapping;
 mod number;
+pub mod parser;
 mod path;
 mod ser;
 pub mod value;
@@ -15,6 +15,9 @@ pub(crate) enum Error {
 
 pub(crate) struct Emitter<'a> {
     pin: Owned<EmitterPinned<'a>>,
+    version_directive: bool,
+    // Handles and prefixes, each with a trailing NUL.
+    tag_directives: Vec<(String, String)>,
 }
 
 struct EmitterPinned<'a> {
@@ -29,6 +32,7 @@ pub(crate) enum Event<'a> {
     StreamEnd,
     DocumentStart,
     DocumentEnd,
+    Alias(String),
     Scalar(Scalar<'a>),
     SequenceStart(Sequence),
     SequenceEnd,
@@ -43,22 +47,27 @@ pub(crate) struct Scalar<'a> {
     pub style: ScalarStyle,
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum ScalarStyle {
     Any,
     Plain,
     SingleQuoted,
+    DoubleQuoted,
     Literal,
 }
 
 #[derive(Debug)]
 pub(crate) struct Sequence {
+    pub anchor: Option<String>,
     pub tag: Option<String>,
+    pub flow: bool,
 }
 
 #[derive(Debug)]
 pub(crate) struct Mapping {
+    pub anchor: Option<String>,
     pub tag: Option<String>,
+    pub flow: bool,
 }
 
 impl<'a> Emitter<'a> {
@@ -76,7 +85,11 @@ impl<'a> Emitter<'a> {
             sys::yaml_emitter_set_output(emitter, write_handler, owned.ptr.cast());
             Owned::assume_init(owned)
         };
-        Emitter { pin }
+        Emitter {
+            pin,
+            version_directive: false,
+            tag_directives: Vec::new(),
+        }
     }
 
     pub fn emit(&mut self, event: Event) -> Result<(), Error> {
@@ -90,9 +103,28 @@ impl<'a> Emitter<'a> {
                 }
                 Event::StreamEnd => sys::yaml_stream_end_event_initialize(sys_event),
                 Event::DocumentStart => {
-                    let version_directive = ptr::null_mut();
-                    let tag_directives_start = ptr::null_mut();
-                    let tag_directives_end = ptr::null_mut();
+                    // The directive structs are non_exhaustive, so they are
+                    // zero-initialized and then filled in.
+                    let mut version: sys::yaml_version_directive_t = mem::zeroed();
+                    version.major = 1;
+                    version.minor = 2;
+                    let version_directive = if self.version_directive {
+                        addr_of_mut!(version)
+                    } else {
+                        ptr::null_mut()
+                    };
+                    let mut tag_directives: Vec<sys::yaml_tag_directive_t> = self
+                        .tag_directives
+                        .iter()
+                        .map(|(handle, prefix)| {
+                            let mut directive: sys::yaml_tag_directive_t = mem::zeroed();
+                            directive.handle = handle.as_ptr().cast_mut();
+                            directive.prefix = prefix.as_ptr().cast_mut();
+                            directive
+                        })
+                        .collect();
+                    let tag_directives_start = tag_directives.as_mut_ptr();
+                    let tag_directives_end = tag_directives_start.add(tag_directives.len());
                     let implicit = true;
                     sys::yaml_document_start_event_initialize(
                         sys_event,
@@ -106,6 +138,10 @@ impl<'a> Emitter<'a> {
                     let implicit = true;
                     sys::yaml_document_end_event_initialize(sys_event, implicit)
                 }
+                Event::Alias(mut anchor) => {
+                    anchor.push('\0');
+                    sys::yaml_alias_event_initialize(sys_event, anchor.as_ptr())
+                }
                 Event::Scalar(mut scalar) => {
                     let anchor = ptr::null();
                     let tag = scalar.tag.as_mut().map_or_else(ptr::null, |tag| {
@@ -120,6 +156,7 @@ impl<'a> Emitter<'a> {
                         ScalarStyle::Any => sys::YAML_ANY_SCALAR_STYLE,
                         ScalarStyle::Plain => sys::YAML_PLAIN_SCALAR_STYLE,
                         ScalarStyle::SingleQuoted => sys::YAML_SINGLE_QUOTED_SCALAR_STYLE,
+                        ScalarStyle::DoubleQuoted => sys::YAML_DOUBLE_QUOTED_SCALAR_STYLE,
                         ScalarStyle::Literal => sys::YAML_LITERAL_SCALAR_STYLE,
                     };
                     sys::yaml_scalar_event_initialize(
@@ -134,26 +171,40 @@ impl<'a> Emitter<'a> {
                     )
                 }
                 Event::SequenceStart(mut sequence) => {
-                    let anchor = ptr::null();
+                    let anchor = sequence.anchor.as_mut().map_or_else(ptr::null, |anchor| {
+                        anchor.push('\0');
+                        anchor.as_ptr()
+                    });
                     let tag = sequence.tag.as_mut().map_or_else(ptr::null, |tag| {
                         tag.push('\0');
                         tag.as_ptr()
                     });
                     let implicit = tag.is_null();
-                    let style = sys::YAML_ANY_SEQUENCE_STYLE;
+                    let style = if sequence.flow {
+                        sys::YAML_FLOW_SEQUENCE_STYLE
+                    } else {
+                        sys::YAML_ANY_SEQUENCE_STYLE
+                    };
                     sys::yaml_sequence_start_event_initialize(
                         sys_event, anchor, tag, implicit, style,
                     )
                 }
                 Event::SequenceEnd => sys::yaml_sequence_end_event_initialize(sys_event),
                 Event::MappingStart(mut mapping) => {
-                    let anchor = ptr::null();
+                    let anchor = mapping.anchor.as_mut().map_or_else(ptr::null, |anchor| {
+                        anchor.push('\0');
+                        anchor.as_ptr()
+                    });
                     let tag = mapping.tag.as_mut().map_or_else(ptr::null, |tag| {
                         tag.push('\0');
                         tag.as_ptr()
                     });
                     let implicit = tag.is_null();
-                    let style = sys::YAML_ANY_MAPPING_STYLE;
+                    let style = if mapping.flow {
+                        sys::YAML_FLOW_MAPPING_STYLE
+                    } else {
+                        sys::YAML_ANY_MAPPING_STYLE
+                    };
                     sys::yaml_mapping_start_event_initialize(
                         sys_event, anchor, tag, implicit, style,
                     )
@@ -180,6 +231,23 @@ impl<'a> Emitter<'a> {
         Ok(())
     }
 
+    pub fn set_version_directive(&mut self, version_directive: bool) {
+        self.version_directive = version_directive;
+    }
+
+    pub fn add_tag_directive(&mut self, mut handle: String, mut prefix: String) {
+        handle.push('\0');
+        prefix.push('\0');
+        self.tag_directives.push((handle, prefix));
+    }
+
+    pub fn set_unicode(&mut self, unicode: bool) {
+        unsafe {
+            let emitter = addr_of_mut!((*self.pin.ptr).sys);
+            sys::yaml_emitter_set_unicode(emitter, unicode);
+        }
+    }
+
     pub fn into_inner(self) -> Box<dyn io::Write + 'a> {
         let sink = Box::new(io::sink());
         unsafe { mem::replace(&mut (*self.pin.ptr).write, sink) }
@@ -195,6 +263,14 @@ impl<'a> Emitter<'a> {
     }
 }
 
+// Whether libyaml writes the content of a tag as is, rather than
+// percent-escaping some of its characters.
+pub(crate) fn is_plain_tag_content(content: &str) -> bool {
+    content
+        .bytes()
+        .all(|byte| byte.is_ascii_alphanumeric() || b"-_;/?:@&=+$,.~*'()[]".contains(&byte))
+}
+
 unsafe fn write_handler(data: *mut c_void, buffer: *mut u8, size: u64) -> i32 {
     let data = data.cast::<EmitterPinned>();
     match io::Write::write_all(unsafe { &mut *(*data).write }, unsafe {
@@ -13,6 +13,7 @@ pub(crate) struct Error {
     problem_mark: Mark,
     context: Option<CStr<'static>>,
     context_mark: Mark,
+    at_end_of_input: bool,
 }
 
 impl Error {
@@ -34,6 +35,7 @@ impl Error {
             context_mark: Mark {
                 sys: unsafe { (&(*parser)).context_mark },
             },
+            at_end_of_input: false,
         }
     }
 
@@ -54,12 +56,24 @@ impl Error {
             context_mark: Mark {
                 sys: unsafe { MaybeUninit::<sys::yaml_mark_t>::zeroed().assume_init() },
             },
+            at_end_of_input: false,
         }
     }
 
     pub fn mark(&self) -> Mark {
         self.problem_mark
     }
+
+    /// Records whether the problem was found at the end of an input of the
+    /// given length, meaning the input ended before the document did.
+    pub fn with_input_len(mut self, len: usize) -> Self {
+        self.at_end_of_input = self.problem_mark.sys.index >= len as u64;
+        self
+    }
+
+    pub fn is_at_end_of_input(&self) -> bool {
+        self.at_end_of_input
+    }
 }
 
 impl Display for Error {
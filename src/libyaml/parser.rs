@@ -5,6 +5,7 @@ use crate::libyaml::util::Owned;
 use std::borrow::Cow;
 use std::fmt::{self, Debug};
 use std::mem::MaybeUninit;
+use std::ops::Deref;
 use std::ptr::{addr_of_mut, NonNull};
 use std::slice;
 use unsafe_libyaml as sys;
@@ -23,7 +24,8 @@ pub(crate) enum Event<'input> {
     StreamStart,
     StreamEnd,
     DocumentStart,
-    DocumentEnd,
+    // Carries the mark just past the end of the document, after any `...`.
+    DocumentEnd(Mark),
     Alias(Anchor),
     Scalar(Scalar<'input>),
     SequenceStart(SequenceStart),
@@ -84,12 +86,14 @@ impl<'input> Parser<'input> {
         let mut event = MaybeUninit::<sys::yaml_event_t>::uninit();
         unsafe {
             let parser = addr_of_mut!((*self.pin.ptr).sys);
+            let input = &(*self.pin.ptr).input;
+            let input_len = input.len();
             if (&(*parser)).error != sys::YAML_NO_ERROR {
-                return Err(Error::parse_error(parser));
+                return Err(Error::parse_error(parser).with_input_len(input_len));
             }
             let event = event.as_mut_ptr();
             if sys::yaml_parser_parse(parser, event).fail {
-                return Err(Error::parse_error(parser));
+                return Err(Error::parse_error(parser).with_input_len(input_len));
             }
             let ret = convert_event(&*event, &(*self.pin.ptr).input);
             let mark = Mark {
@@ -109,7 +113,7 @@ unsafe fn convert_event<'input>(
         sys::YAML_STREAM_START_EVENT => Event::StreamStart,
         sys::YAML_STREAM_END_EVENT => Event::StreamEnd,
         sys::YAML_DOCUMENT_START_EVENT => Event::DocumentStart,
-        sys::YAML_DOCUMENT_END_EVENT => Event::DocumentEnd,
+        sys::YAML_DOCUMENT_END_EVENT => Event::DocumentEnd(Mark { sys: sys.end_mark }),
         sys::YAML_ALIAS_EVENT => {
             Event::Alias(unsafe { optional_anchor(sys.data.alias.anchor) }.unwrap())
         }
@@ -188,6 +192,13 @@ impl Debug for Scalar<'_> {
     }
 }
 
+impl Deref for Anchor {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl Debug for Anchor {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         cstr::debug_lossy(&self.0, formatter)
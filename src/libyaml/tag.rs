@@ -10,9 +10,19 @@ impl Tag {
     pub const BOOL: &'static str = "tag:yaml.org,2002:bool";
     pub const INT: &'static str = "tag:yaml.org,2002:int";
     pub const FLOAT: &'static str = "tag:yaml.org,2002:float";
+    pub const STR: &'static str = "tag:yaml.org,2002:str";
+    pub const BINARY: &'static str = "tag:yaml.org,2002:binary";
+    pub const SET: &'static str = "tag:yaml.org,2002:set";
+    pub const OMAP: &'static str = "tag:yaml.org,2002:omap";
+    pub const SEQ: &'static str = "tag:yaml.org,2002:seq";
+    pub const MAP: &'static str = "tag:yaml.org,2002:map";
 }
 
 impl Tag {
+    pub fn new(tag: &str) -> Self {
+        Tag(Box::from(tag.as_bytes()))
+    }
+
     pub fn starts_with(&self, prefix: &str) -> bool {
         self.0.starts_with(prefix.as_bytes())
     }
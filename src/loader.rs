@@ -1,14 +1,20 @@
 use crate::de::{Event, Progress};
 use crate::error::{self, Error, ErrorImpl, Result};
 use crate::libyaml::error::Mark;
-use crate::libyaml::parser::{Event as YamlEvent, Parser};
+use crate::libyaml::parser::{Anchor, Event as YamlEvent, Parser};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::io;
+use std::slice;
 use std::sync::Arc;
 
+/// Longest anchor or alias name accepted unless configured otherwise.
+pub(crate) const DEFAULT_MAX_ANCHOR_NAME_LEN: usize = 1024;
+
 pub(crate) struct Loader<'input> {
     parser: Option<Parser<'input>>,
     document_count: usize,
+    max_anchor_name_len: usize,
 }
 
 pub(crate) struct Document<'input> {
@@ -16,30 +22,25 @@ pub(crate) struct Document<'input> {
     pub error: Option<Arc<ErrorImpl>>,
     /// Map from alias id to index in events.
     pub aliases: BTreeMap<usize, usize>,
+    /// Byte offset in the input just past the end of the document.
+    pub end: usize,
 }
 
 impl<'input> Loader<'input> {
     pub fn new(progress: Progress<'input>) -> Result<Self> {
-        let input = match progress {
-            Progress::Str(s) => Cow::Borrowed(s.as_bytes()),
-            Progress::Slice(bytes) => Cow::Borrowed(bytes),
-            Progress::Read(mut rdr) => {
-                let mut buffer = Vec::new();
-                if let Err(io_error) = rdr.read_to_end(&mut buffer) {
-                    return Err(error::new(ErrorImpl::Io(io_error)));
-                }
-                Cow::Owned(buffer)
-            }
-            Progress::Iterable(_) | Progress::Document(_) => unreachable!(),
-            Progress::Fail(err) => return Err(error::shared(err)),
-        };
+        let input = read_input(progress)?;
 
         Ok(Loader {
             parser: Some(Parser::new(input)),
             document_count: 0,
+            max_anchor_name_len: DEFAULT_MAX_ANCHOR_NAME_LEN,
         })
     }
 
+    pub fn set_max_anchor_name_len(&mut self, limit: usize) {
+        self.max_anchor_name_len = limit;
+    }
+
     pub fn next_document(&mut self) -> Option<Document<'input>> {
         let parser = match &mut self.parser {
             Some(parser) => parser,
@@ -54,6 +55,7 @@ impl<'input> Loader<'input> {
             events: Vec::new(),
             error: None,
             aliases: BTreeMap::new(),
+            end: 0,
         };
 
         loop {
@@ -64,10 +66,16 @@ impl<'input> Loader<'input> {
                     return Some(document);
                 }
             };
+            if anchor(&event).is_some_and(|anchor| anchor.len() > self.max_anchor_name_len) {
+                let err = ErrorImpl::AnchorNameTooLong(self.max_anchor_name_len, mark);
+                document.error = Some(error::new(err).shared());
+                return Some(document);
+            }
             let event = match event {
                 YamlEvent::StreamStart => continue,
                 YamlEvent::StreamEnd => {
                     self.parser = None;
+                    document.end = mark.index() as usize;
                     return if first {
                         if document.events.is_empty() {
                             document.events.push((Event::Void, mark));
@@ -78,7 +86,10 @@ impl<'input> Loader<'input> {
                     };
                 }
                 YamlEvent::DocumentStart => continue,
-                YamlEvent::DocumentEnd => return Some(document),
+                YamlEvent::DocumentEnd(end) => {
+                    document.end = end.index() as usize;
+                    return Some(document);
+                }
                 YamlEvent::Alias(alias) => match anchors.get(&alias) {
                     Some(id) => Event::Alias(*id),
                     None => {
@@ -117,3 +128,302 @@ impl<'input> Loader<'input> {
         }
     }
 }
+
+fn anchor<'a>(event: &'a YamlEvent<'_>) -> Option<&'a Anchor> {
+    match event {
+        YamlEvent::Alias(alias) => Some(alias),
+        YamlEvent::Scalar(scalar) => scalar.anchor.as_ref(),
+        YamlEvent::SequenceStart(sequence_start) => sequence_start.anchor.as_ref(),
+        YamlEvent::MappingStart(mapping_start) => mapping_start.anchor.as_ref(),
+        _ => None,
+    }
+}
+
+/// Collects the whole input as UTF-8, transcoding from UTF-16 if it starts
+/// with a byte order mark.
+pub(crate) fn read_input(progress: Progress) -> Result<Cow<[u8]>> {
+    let input = match progress {
+        Progress::Str(s) => Cow::Borrowed(s.as_bytes()),
+        Progress::Slice(bytes) => Cow::Borrowed(bytes),
+        Progress::Read(mut rdr) => {
+            let mut buffer = Vec::new();
+            if let Err(io_error) = rdr.read_to_end(&mut buffer) {
+                return Err(error::new(ErrorImpl::Io(io_error)));
+            }
+            Cow::Owned(buffer)
+        }
+        Progress::Iterable(_) | Progress::Document(_) => unreachable!(),
+        Progress::Fail(err) => return Err(error::shared(err)),
+    };
+    decode_bom(input)
+}
+
+/// Reads a stream through the end of the line on which its first document
+/// ends, or to the end of the input. Returns what was read as UTF-8, along
+/// with the number of bytes read.
+///
+/// A `...` line ends the first document, and so does a `---` line once the
+/// document has started. It starts at a `---` line or at the first line that
+/// is not blank, a comment or a directive.
+pub(crate) fn read_first_document(rdr: impl io::Read) -> Result<(Vec<u8>, usize)> {
+    let mut rdr = ByteReader { rdr, len: 0 };
+    // Neither byte of a UTF-16 byte order mark occurs in UTF-8.
+    let mut input = Vec::new();
+    while input.is_empty() || input == b"\xFF" || input == b"\xFE" {
+        match rdr.next()? {
+            Some(byte) => input.push(byte),
+            None => return Ok((input, rdr.len)),
+        }
+    }
+    let from_bytes: Option<fn([u8; 2]) -> u16> = match &input[..] {
+        b"\xFF\xFE" => Some(u16::from_le_bytes),
+        b"\xFE\xFF" => Some(u16::from_be_bytes),
+        _ => None,
+    };
+    if from_bytes.is_some() {
+        input.clear();
+    }
+
+    let mut started = false;
+    let mut line_start = 0;
+    loop {
+        let eof = match from_bytes {
+            Some(from_bytes) => rdr.read_utf16_line(from_bytes, &mut input)?,
+            None if line_start == 0 && input == b"\n" => false,
+            None => rdr.read_line(&mut input)?,
+        };
+        let mut line = &input[line_start..];
+        if line_start == 0 {
+            line = line.strip_prefix("\u{feff}".as_bytes()).unwrap_or(line);
+        }
+        let is_marker = |marker: &[u8]| {
+            line.starts_with(marker)
+                && line
+                    .get(3)
+                    .is_none_or(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+        };
+        if eof || is_marker(b"...") || (started && is_marker(b"---")) {
+            return Ok((input, rdr.len));
+        }
+        let content = line.trim_ascii_start();
+        started |= is_marker(b"---")
+            || !(content.is_empty() || content.starts_with(b"#") || line.starts_with(b"%"));
+        line_start = input.len();
+    }
+}
+
+// Reads one byte at a time, so that nothing past the end of the document is
+// read, and counts the bytes read.
+struct ByteReader<R> {
+    rdr: R,
+    len: usize,
+}
+
+impl<R> ByteReader<R>
+where
+    R: io::Read,
+{
+    fn next(&mut self) -> Result<Option<u8>> {
+        let mut byte = 0;
+        loop {
+            match self.rdr.read(slice::from_mut(&mut byte)) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.len += 1;
+                    return Ok(Some(byte));
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(error::new(ErrorImpl::Io(err))),
+            }
+        }
+    }
+
+    // Appends a line including its line break, returning whether the input
+    // ended first.
+    fn read_line(&mut self, line: &mut Vec<u8>) -> Result<bool> {
+        while let Some(byte) = self.next()? {
+            line.push(byte);
+            if byte == b'\n' {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn read_utf16_line(
+        &mut self,
+        from_bytes: fn([u8; 2]) -> u16,
+        line: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let mut units = Vec::new();
+        let eof = loop {
+            let Some(first) = self.next()? else {
+                break true;
+            };
+            let Some(second) = self.next()? else {
+                return Err(error::new(ErrorImpl::InvalidUtf16));
+            };
+            let unit = from_bytes([first, second]);
+            units.push(unit);
+            if unit == u16::from(b'\n') {
+                break false;
+            }
+        };
+        for ch in char::decode_utf16(units) {
+            match ch {
+                Ok(ch) => line.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+                Err(_) => return Err(error::new(ErrorImpl::InvalidUtf16)),
+            }
+        }
+        Ok(eof)
+    }
+}
+
+/// Replaces each tab in the indentation of a line with `width` spaces.
+///
+/// Tabs after the first non-blank character of a line are kept. Within the
+/// content of a block scalar, only the tabs before the first space are
+/// expanded, since whitespace after that may be part of the content. If the
+/// first line of content starts with a tab after its indentation, an
+/// indentation indicator is added to the header, since libyaml cannot detect
+/// the indentation of such content by itself.
+pub(crate) fn expand_leading_tabs(input: &[u8], width: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    // Indentation of the line whose value is the block scalar being read.
+    let mut block_scalar_parent = None;
+    // Until the first line of content of that block scalar, where in the
+    // output an indentation indicator would go, and the column it counts from.
+    let mut block_scalar_indicator = None;
+    for line in input.split_inclusive(|&b| b == b'\n') {
+        if let Some(parent) = block_scalar_parent {
+            let tabs = line.iter().take_while(|&&b| b == b'\t').count();
+            let spaces = line[tabs..].iter().take_while(|&&b| b == b' ').count();
+            let indent = tabs * width + spaces;
+            let blank = line.iter().all(u8::is_ascii_whitespace);
+            if indent > parent || blank {
+                match block_scalar_indicator {
+                    Some((offset, base)) if !blank => {
+                        if line[tabs + spaces] == b'\t' && (base + 1..=base + 9).contains(&indent) {
+                            output.insert(offset, b'0' + (indent - base) as u8);
+                        }
+                        block_scalar_indicator = None;
+                    }
+                    _ => {}
+                }
+                output.extend(std::iter::repeat_n(b' ', tabs * width));
+                output.extend_from_slice(&line[tabs..]);
+                continue;
+            }
+            block_scalar_parent = None;
+            block_scalar_indicator = None;
+        }
+        let indent_len = line
+            .iter()
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count();
+        let (indent, rest) = line.split_at(indent_len);
+        let start = output.len();
+        for &b in indent {
+            if b == b'\t' {
+                output.extend(std::iter::repeat_n(b' ', width));
+            } else {
+                output.push(b);
+            }
+        }
+        let expanded_len = output.len() - start;
+        output.extend_from_slice(rest);
+        if let Some(header) = block_scalar_header(rest) {
+            block_scalar_parent = Some(expanded_len);
+            block_scalar_indicator = match block_scalar_base(&rest[..header]) {
+                Some(base) if !rest[header..].iter().any(u8::is_ascii_digit) => {
+                    Some((start + expanded_len + header + 1, expanded_len + base))
+                }
+                _ => None,
+            };
+        }
+    }
+    output
+}
+
+/// Finds the block scalar header like `|`, `>-` or `|2+` that ends a line,
+/// possibly followed by a comment, and returns its offset.
+fn block_scalar_header(line: &[u8]) -> Option<usize> {
+    if line.first() == Some(&b'#') {
+        return None;
+    }
+    let line = match line
+        .windows(2)
+        .position(|w| w[0].is_ascii_whitespace() && w[1] == b'#')
+    {
+        Some(comment) => &line[..comment],
+        None => line,
+    };
+    let end = line.iter().rposition(|b| !b.is_ascii_whitespace())? + 1;
+    let start = line[..end]
+        .iter()
+        .rposition(u8::is_ascii_whitespace)
+        .map_or(0, |space| space + 1);
+    match line[start..end].split_first() {
+        Some((b'|' | b'>', indicators))
+            if indicators.len() <= 2
+                && indicators
+                    .iter()
+                    .all(|&b| b == b'+' || b == b'-' || b.is_ascii_digit()) =>
+        {
+            Some(start)
+        }
+        _ => None,
+    }
+}
+
+/// The column that the indentation of a block scalar is counted from, given
+/// what precedes its header on the line: the start of a mapping key, or else
+/// the `-` of a sequence entry. `None` if neither is on the line.
+fn block_scalar_base(before_header: &[u8]) -> Option<usize> {
+    if before_header.starts_with(b"--- ") {
+        return Some(0);
+    }
+    let mut base = None;
+    let mut column = 0;
+    while before_header[column..].starts_with(b"- ") {
+        base = Some(column);
+        column += 1;
+        column += before_header[column..]
+            .iter()
+            .take_while(|&&b| b == b' ')
+            .count();
+    }
+    if before_header[column..].windows(2).any(|w| w == b": ") {
+        base = Some(column);
+    }
+    base
+}
+
+// libyaml is configured to always read UTF-8, and skips a UTF-8 byte order
+// mark by itself. Input starting with a UTF-16 byte order mark is transcoded to
+// UTF-8 up front.
+pub(crate) fn decode_bom(input: Cow<[u8]>) -> Result<Cow<[u8]>> {
+    let from_bytes: fn([u8; 2]) -> u16 = if input.starts_with(b"\xFF\xFE") {
+        u16::from_le_bytes
+    } else if input.starts_with(b"\xFE\xFF") {
+        u16::from_be_bytes
+    } else {
+        return Ok(input);
+    };
+
+    let units = &input[2..];
+    if !units.len().is_multiple_of(2) {
+        return Err(error::new(ErrorImpl::InvalidUtf16));
+    }
+    let units = units
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    let mut utf8 = String::with_capacity(units.len());
+    for ch in char::decode_utf16(units) {
+        match ch {
+            Ok(ch) => utf8.push(ch),
+            Err(_) => return Err(error::new(ErrorImpl::InvalidUtf16)),
+        }
+    }
+    Ok(Cow::Owned(utf8.into_bytes()))
+}
@@ -0,0 +1,127 @@
+use crate::de::Event;
+use crate::error::{Location, Result};
+use crate::libyaml::error::Mark;
+use crate::libyaml::parser::Scalar;
+use crate::path::Path;
+use crate::Value;
+
+/// Deserialize a `Value` from a string of YAML text, together with the path and
+/// input location of every scalar value in it.
+///
+/// Paths are formatted as in error messages, like `servers[0].host`, with `.`
+/// for a scalar at the root. Map keys are not reported themselves; they make up
+/// the paths of their values. Scalars reached through an alias are reported
+/// once, at the anchored node. Entries are in document order.
+///
+/// ```
+/// # fn main() -> serde_yaml::Result<()> {
+/// let yaml = "name: demo\nports:\n  - 80\n  - 443\n";
+/// let (value, locations) = serde_yaml::from_str_with_locations(yaml)?;
+///
+/// assert_eq!(value["ports"][1], 443);
+///
+/// let (path, location) = &locations[2];
+/// assert_eq!(path, "ports[1]");
+/// assert_eq!((location.line(), location.column()), (4, 5));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_str_with_locations(s: &str) -> Result<(Value, Vec<(String, Location)>)> {
+    let mut locations = Locations(Vec::new());
+    let (value, ()) = crate::de::from_str_with_events(s, |events| walk(events, &mut locations))?;
+    Ok((value, locations.0))
+}
+
+struct Locations(Vec<(String, Location)>);
+
+impl Visitor for Locations {
+    fn scalar(&mut self, path: &Path, _scalar: &Scalar, mark: Mark) {
+        self.0.push((path.to_string(), Location::from_mark(mark)));
+    }
+}
+
+// Receives the nodes of a document from `walk`, in document order, each with
+// its path. Map keys that are not scalars are skipped along with everything
+// inside them.
+pub(crate) trait Visitor {
+    // A scalar map key. The path is that of the key's value.
+    fn key(&mut self, _path: &Path, _scalar: &Scalar, _mark: Mark) {}
+
+    // A scalar that is not a map key.
+    fn scalar(&mut self, path: &Path, scalar: &Scalar, mark: Mark);
+
+    fn alias(&mut self, _path: &Path, _mark: Mark) {}
+}
+
+pub(crate) fn walk(events: &[(Event<'_>, Mark)], visitor: &mut impl Visitor) {
+    let mut pos = 0;
+    node(events, &mut pos, &Path::Root, visitor);
+}
+
+fn node(events: &[(Event<'_>, Mark)], pos: &mut usize, path: &Path, visitor: &mut impl Visitor) {
+    let Some((event, mark)) = events.get(*pos) else {
+        return;
+    };
+    *pos += 1;
+    match event {
+        Event::Scalar(scalar) => visitor.scalar(path, scalar, *mark),
+        Event::Alias(_) => visitor.alias(path, *mark),
+        Event::SequenceStart(_) => {
+            let mut index = 0;
+            while let Some((event, _)) = events.get(*pos) {
+                if let Event::SequenceEnd = event {
+                    *pos += 1;
+                    break;
+                }
+                let child = Path::Seq {
+                    parent: path,
+                    index,
+                };
+                node(events, pos, &child, visitor);
+                index += 1;
+            }
+        }
+        Event::MappingStart(_) => {
+            while let Some((event, mark)) = events.get(*pos) {
+                let key = match event {
+                    Event::MappingEnd => {
+                        *pos += 1;
+                        break;
+                    }
+                    Event::Scalar(scalar) => {
+                        *pos += 1;
+                        Some((String::from_utf8_lossy(&scalar.value), scalar))
+                    }
+                    _ => {
+                        skip(events, pos);
+                        None
+                    }
+                };
+                match &key {
+                    Some((key, scalar)) => {
+                        let child = Path::Map { parent: path, key };
+                        visitor.key(&child, scalar, *mark);
+                        node(events, pos, &child, visitor);
+                    }
+                    None => node(events, pos, &Path::Unknown { parent: path }, visitor),
+                }
+            }
+        }
+        Event::SequenceEnd | Event::MappingEnd | Event::Void => {}
+    }
+}
+
+fn skip(events: &[(Event<'_>, Mark)], pos: &mut usize) {
+    let mut depth = 0;
+    while let Some((event, _)) = events.get(*pos) {
+        *pos += 1;
+        match event {
+            Event::SequenceStart(_) | Event::MappingStart(_) => depth += 1,
+            Event::SequenceEnd | Event::MappingEnd => depth -= 1,
+            Event::Alias(_) | Event::Scalar(_) | Event::Void => {}
+        }
+        if depth == 0 {
+            break;
+        }
+    }
+}
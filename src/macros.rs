@@ -0,0 +1,243 @@
+/// Construct a `serde_yaml::Value` from a JSON-like literal.
+///
+/// ```
+/// use serde_yaml::{yaml, Value};
+///
+/// let port = 8080;
+/// let value = yaml!({
+///     "name": "demo",
+///     "ports": [80, port],
+///     "tls": {
+///         "enabled": true,
+///         "cert": null,
+///     },
+/// });
+///
+/// assert_eq!(value["ports"][1], 8080);
+/// assert_eq!(value["tls"]["enabled"], true);
+/// assert!(value["tls"]["cert"].is_null());
+/// ```
+///
+/// Map keys must be expressions that convert into a `String`, and become
+/// string keys. Any other value is interpolated through the `From` impls of
+/// `Value`, so variables and expressions of type `bool`, the integer and
+/// floating point types, `&str`, `String`, `Vec`, `Mapping` and `Value` itself
+/// can be used in place of a literal. An expression that is not a single token
+/// is written as a map key inside parentheses.
+///
+/// ```
+/// # use serde_yaml::yaml;
+/// let key = "answer";
+/// let value = yaml!({ (key.to_uppercase()): 6 * 7 });
+/// assert_eq!(value["ANSWER"], 42);
+/// ```
+#[macro_export]
+macro_rules! yaml {
+    ($($yaml:tt)+) => {
+        $crate::yaml_internal!($($yaml)+)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! yaml_internal {
+    // Done with trailing comma.
+    (@sequence [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+
+    // Done without trailing comma.
+    (@sequence [$($elems:expr),*]) => {
+        ::std::vec![$($elems),*]
+    };
+
+    // Next element is `null`.
+    (@sequence [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!(null)] $($rest)*)
+    };
+
+    // Next element is `true`.
+    (@sequence [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!(true)] $($rest)*)
+    };
+
+    // Next element is `false`.
+    (@sequence [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!(false)] $($rest)*)
+    };
+
+    // Next element is a sequence.
+    (@sequence [$($elems:expr,)*] [$($sequence:tt)*] $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!([$($sequence)*])] $($rest)*)
+    };
+
+    // Next element is a mapping.
+    (@sequence [$($elems:expr,)*] {$($mapping:tt)*} $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!({$($mapping)*})] $($rest)*)
+    };
+
+    // Next element is an expression followed by comma.
+    (@sequence [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!($next),] $($rest)*)
+    };
+
+    // Last element is an expression with no trailing comma.
+    (@sequence [$($elems:expr,)*] $last:expr) => {
+        $crate::yaml_internal!(@sequence [$($elems,)* $crate::yaml_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@sequence [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::yaml_internal!(@sequence [$($elems,)*] $($rest)*)
+    };
+
+    // Unexpected token after most recent element.
+    (@sequence [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::yaml_unexpected!($unexpected)
+    };
+
+    // Done.
+    (@mapping $mapping:ident () () ()) => {};
+
+    // Insert the current entry followed by trailing comma.
+    (@mapping $mapping:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $mapping.insert($crate::Value::String(($($key)+).into()), $value);
+        $crate::yaml_internal!(@mapping $mapping () ($($rest)*) ($($rest)*));
+    };
+
+    // Current entry followed by unexpected token.
+    (@mapping $mapping:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::yaml_unexpected!($unexpected);
+    };
+
+    // Insert the last entry without trailing comma.
+    (@mapping $mapping:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $mapping.insert($crate::Value::String(($($key)+).into()), $value);
+    };
+
+    // Next value is `null`.
+    (@mapping $mapping:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!(null)) $($rest)*);
+    };
+
+    // Next value is `true`.
+    (@mapping $mapping:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!(true)) $($rest)*);
+    };
+
+    // Next value is `false`.
+    (@mapping $mapping:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!(false)) $($rest)*);
+    };
+
+    // Next value is a sequence.
+    (@mapping $mapping:ident ($($key:tt)+) (: [$($sequence:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!([$($sequence)*])) $($rest)*);
+    };
+
+    // Next value is a mapping.
+    (@mapping $mapping:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!({$($inner)*})) $($rest)*);
+    };
+
+    // Next value is an expression followed by comma.
+    (@mapping $mapping:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is an expression with no trailing comma.
+    (@mapping $mapping:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping [$($key)+] ($crate::yaml_internal!($value)));
+    };
+
+    // Missing value for last entry. Trigger a reasonable error message.
+    (@mapping $mapping:ident ($($key:tt)+) (:) $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::yaml_internal!();
+    };
+
+    // Missing colon and value for last entry. Trigger a reasonable error
+    // message.
+    (@mapping $mapping:ident ($($key:tt)+) () $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::yaml_internal!();
+    };
+
+    // Misplaced colon. Trigger a reasonable error message.
+    (@mapping $mapping:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `:`".
+        $crate::yaml_unexpected!($colon);
+    };
+
+    // Found a comma inside a key. Trigger a reasonable error message.
+    (@mapping $mapping:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `,`".
+        $crate::yaml_unexpected!($comma);
+    };
+
+    // Key is fully parenthesized. This avoids clippy double_parens false
+    // positives because the parenthesization may be necessary here.
+    (@mapping $mapping:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // Refuse to absorb colon token into key expression.
+    (@mapping $mapping:ident ($($key:tt)*) (: $($unexpected:tt)+) $copy:tt) => {
+        $crate::yaml_expect_expr_comma!($($unexpected)+);
+    };
+
+    // Munch a token into the current key.
+    (@mapping $mapping:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::yaml_internal!(@mapping $mapping ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::Value::Null
+    };
+
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+
+    ([]) => {
+        $crate::Value::Sequence(::std::vec![])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Sequence($crate::yaml_internal!(@sequence [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::Value::Mapping($crate::Mapping::new())
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Mapping({
+            let mut mapping = $crate::Mapping::new();
+            $crate::yaml_internal!(@mapping mapping () ($($tt)+) ($($tt)+));
+            mapping
+        })
+    };
+
+    // Any type that `Value` can be converted from: numbers, strings,
+    // variables, etc.
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! yaml_unexpected {
+    () => {};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! yaml_expect_expr_comma {
+    ($e:expr , $($tt:tt)*) => {};
+}
@@ -1,7 +1,18 @@
 //! A YAML mapping and its iterator types.
+//!
+//! By default a `Mapping` is backed by an `IndexMap`. With the `small` feature
+//! it is instead backed by a `Vec` of key-value pairs with linear lookup, which
+//! suits small maps and, together with `default-features = false`, drops the
+//! `indexmap` dependency. Both keep keys in insertion order.
 
+#[cfg(any(feature = "small", not(feature = "indexmap")))]
+mod small;
+
+#[cfg(any(feature = "small", not(feature = "indexmap")))]
+use self::small::{self as store, Equivalent, VecMap as Map};
 use crate::{private, Value};
-use indexmap::IndexMap;
+#[cfg(all(feature = "indexmap", not(feature = "small")))]
+use indexmap::{map as store, Equivalent, IndexMap as Map};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::Ordering;
 use std::collections::hash_map::DefaultHasher;
@@ -12,7 +23,7 @@ use std::mem;
 /// A YAML mapping in which the keys and values are both `serde_yaml::Value`.
 #[derive(Clone, Default, Eq, PartialEq)]
 pub struct Mapping {
-    map: IndexMap<Value, Value>,
+    map: Map<Value, Value>,
 }
 
 impl Mapping {
@@ -26,7 +37,7 @@ impl Mapping {
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
         Mapping {
-            map: IndexMap::with_capacity(capacity),
+            map: Map::with_capacity(capacity),
         }
     }
 
@@ -75,36 +86,156 @@ impl Mapping {
         index.index_into_mut(self)
     }
 
+    /// Returns the key-value pair at position `index` in insertion order, or
+    /// None if `index` is out of bounds.
+    ///
+    /// ```
+    /// # use serde_yaml::{Mapping, Value};
+    /// let mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2}").unwrap();
+    /// assert_eq!(mapping.get_index(1), Some((&Value::from("b"), &Value::from(2))));
+    /// assert_eq!(mapping.get_index(2), None);
+    /// ```
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(&Value, &Value)> {
+        self.map.get_index(index)
+    }
+
+    /// Returns the key and a mutable reference to the value at position
+    /// `index` in insertion order, or None if `index` is out of bounds.
+    #[inline]
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&Value, &mut Value)> {
+        self.map.get_index_mut(index)
+    }
+
+    /// Inserts a key-value pair at position `index`, shifting all of the
+    /// entries that follow it.
+    ///
+    /// If the key already existed, its entry is moved to `index`, its value is
+    /// replaced, and the old value is returned.
+    ///
+    /// ```
+    /// # use serde_yaml::{Mapping, Value};
+    /// let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2}").unwrap();
+    /// assert_eq!(mapping.insert_index(0, "c".into(), 3.into()), None);
+    /// assert_eq!(mapping.insert_index(2, "a".into(), 4.into()), Some(Value::from(1)));
+    /// assert_eq!(mapping, serde_yaml::from_str::<Mapping>("{c: 3, b: 2, a: 4}").unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of the map, or, for a key
+    /// that already existed, equal to it.
+    #[inline]
+    pub fn insert_index(&mut self, index: usize, k: Value, v: Value) -> Option<Value> {
+        self.map.shift_insert(index, k, v)
+    }
+
+    /// Moves the entry at position `from` to position `to`, shifting the
+    /// entries in between.
+    ///
+    /// ```
+    /// # use serde_yaml::{Mapping, Value};
+    /// let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2, c: 3}").unwrap();
+    /// mapping.move_index(2, 0);
+    /// let keys: Vec<&Value> = mapping.keys().collect();
+    /// assert_eq!(keys, ["c", "a", "b"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    #[inline]
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        self.map.move_index(from, to);
+    }
+
+    /// Replaces the key `from` with `to`, keeping the entry's position and
+    /// value. Returns false, leaving the map unchanged, if `from` is not
+    /// present.
+    ///
+    /// If `to` is already a key elsewhere in the map, that entry is removed:
+    /// the renamed entry wins and the values are not merged.
+    ///
+    /// ```
+    /// # use serde_yaml::{Mapping, Value};
+    /// let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2, c: 3}").unwrap();
+    /// assert!(mapping.rename_key(&"b".into(), "x".into()));
+    /// assert!(!mapping.rename_key(&"b".into(), "y".into()));
+    /// let keys: Vec<&Value> = mapping.keys().collect();
+    /// assert_eq!(keys, ["a", "x", "c"]);
+    ///
+    /// assert!(mapping.rename_key(&"c".into(), "a".into()));
+    /// assert_eq!(mapping, serde_yaml::from_str::<Mapping>("{x: 2, a: 3}").unwrap());
+    /// ```
+    pub fn rename_key(&mut self, from: &Value, to: Value) -> bool {
+        let Some(mut index) = self.map.get_index_of(from) else {
+            return false;
+        };
+        if *from == to {
+            return true;
+        }
+        if let Some(existing) = self.map.get_index_of(&to) {
+            self.map.shift_remove_index(existing);
+            if existing < index {
+                index -= 1;
+            }
+        }
+        let (_from, value) = self.map.shift_remove_index(index).unwrap();
+        self.map.shift_insert(index, to, value);
+        true
+    }
+
+    /// Returns the value of the first string key, in insertion order, that
+    /// equals `key` ignoring case. Keys that are not strings are skipped.
+    ///
+    /// This scans the whole map, so it takes O(n) time. It is meant for
+    /// small config maps with inconsistently cased keys, not as a replacement
+    /// for [`get`][Mapping::get].
+    ///
+    /// ```
+    /// # use serde_yaml::{Mapping, Value};
+    /// let yaml = "{Port: 80, PORT: 443}";
+    /// let mapping: Mapping = serde_yaml::from_str(yaml).unwrap();
+    /// assert_eq!(mapping.get_ci("port"), Some(&Value::from(80)));
+    /// assert_eq!(mapping.get("port"), None);
+    /// ```
+    pub fn get_ci(&self, key: &str) -> Option<&Value> {
+        self.map.iter().find_map(|(k, v)| match k {
+            Value::String(k) if eq_ignore_case(k, key) => Some(v),
+            _ => None,
+        })
+    }
+
     /// Gets the given key's corresponding entry in the map for insertion and/or
     /// in-place manipulation.
     #[inline]
     pub fn entry(&mut self, k: Value) -> Entry<'_> {
         match self.map.entry(k) {
-            indexmap::map::Entry::Occupied(occupied) => Entry::Occupied(OccupiedEntry { occupied }),
-            indexmap::map::Entry::Vacant(vacant) => Entry::Vacant(VacantEntry { vacant }),
+            store::Entry::Occupied(occupied) => Entry::Occupied(OccupiedEntry { occupied }),
+            store::Entry::Vacant(vacant) => Entry::Vacant(VacantEntry { vacant }),
         }
     }
 
     /// Removes and returns the value corresponding to the key from the map.
     ///
-    /// This is equivalent to [`.swap_remove(index)`][Self::swap_remove],
-    /// replacing this entry's position with the last element. If you need to
-    /// preserve the relative order of the keys in the map, use
-    /// [`.shift_remove(key)`][Self::shift_remove] instead.
+    /// This is equivalent to [`.shift_remove(index)`][Self::shift_remove],
+    /// preserving the relative order of the remaining keys. If order does not
+    /// matter, [`.swap_remove(key)`][Self::swap_remove] runs in constant time
+    /// instead.
     #[inline]
     pub fn remove<I: Index>(&mut self, index: I) -> Option<Value> {
-        self.swap_remove(index)
+        self.shift_remove(index)
     }
 
     /// Remove and return the key-value pair.
     ///
-    /// This is equivalent to [`.swap_remove_entry(index)`][Self::swap_remove_entry],
-    /// replacing this entry's position with the last element. If you need to
-    /// preserve the relative order of the keys in the map, use
-    /// [`.shift_remove_entry(key)`][Self::shift_remove_entry] instead.
+    /// This is equivalent to [`.shift_remove_entry(index)`][Self::shift_remove_entry],
+    /// preserving the relative order of the remaining keys. If order does not
+    /// matter, [`.swap_remove_entry(key)`][Self::swap_remove_entry] runs in
+    /// constant time instead.
     #[inline]
     pub fn remove_entry<I: Index>(&mut self, index: I) -> Option<(Value, Value)> {
-        self.swap_remove_entry(index)
+        self.shift_remove_entry(index)
     }
 
     /// Removes and returns the value corresponding to the key from the map.
@@ -147,6 +278,27 @@ impl Mapping {
         index.shift_remove_entry_from(self)
     }
 
+    /// Moves all key-value pairs from `other` into `self`, leaving `other`
+    /// empty.
+    ///
+    /// Keys not yet in `self` are appended in their order in `other`. For a key
+    /// already in `self`, the value from `other` replaces the existing one,
+    /// which keeps its position.
+    ///
+    /// ```
+    /// # use serde_yaml::Mapping;
+    /// let mut a: Mapping = serde_yaml::from_str("{x: 1, y: 2}").unwrap();
+    /// let mut b: Mapping = serde_yaml::from_str("{z: 3, x: 4}").unwrap();
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a, serde_yaml::from_str::<Mapping>("{x: 4, y: 2, z: 3}").unwrap());
+    /// assert!(b.is_empty());
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Mapping) {
+        self.extend(mem::take(other));
+    }
+
     /// Scan through each key-value pair in the map and keep those where the
     /// closure `keep` returns true.
     #[inline]
@@ -266,7 +418,7 @@ pub trait Index: private::Sealed {
 
 struct HashLikeValue<'a>(&'a str);
 
-impl indexmap::Equivalent<Value> for HashLikeValue<'_> {
+impl Equivalent<Value> for HashLikeValue<'_> {
     fn equivalent(&self, key: &Value) -> bool {
         match key {
             Value::String(string) => self.0 == string,
@@ -403,77 +555,6 @@ impl PartialOrd for Mapping {
         let mut self_entries = Vec::from_iter(self);
         let mut other_entries = Vec::from_iter(other);
 
-        // Sort in an arbitrary order that is consistent with Value's PartialOrd
-        // impl.
-        fn total_cmp(a: &Value, b: &Value) -> Ordering {
-            match (a, b) {
-                (Value::Null, Value::Null) => Ordering::Equal,
-                (Value::Null, _) => Ordering::Less,
-                (_, Value::Null) => Ordering::Greater,
-
-                (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
-                (Value::Bool(_), _) => Ordering::Less,
-                (_, Value::Bool(_)) => Ordering::Greater,
-
-                (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
-                (Value::Number(_), _) => Ordering::Less,
-                (_, Value::Number(_)) => Ordering::Greater,
-
-                (Value::String(a), Value::String(b)) => a.cmp(b),
-                (Value::String(_), _) => Ordering::Less,
-                (_, Value::String(_)) => Ordering::Greater,
-
-                (Value::Sequence(a), Value::Sequence(b)) => iter_cmp_by(a, b, total_cmp),
-                (Value::Sequence(_), _) => Ordering::Less,
-                (_, Value::Sequence(_)) => Ordering::Greater,
-
-                (Value::Mapping(a), Value::Mapping(b)) => {
-                    iter_cmp_by(a, b, |(ak, av), (bk, bv)| {
-                        total_cmp(ak, bk).then_with(|| total_cmp(av, bv))
-                    })
-                }
-                (Value::Mapping(_), _) => Ordering::Less,
-                (_, Value::Mapping(_)) => Ordering::Greater,
-
-                (Value::Tagged(a), Value::Tagged(b)) => a
-                    .tag
-                    .cmp(&b.tag)
-                    .then_with(|| total_cmp(&a.value, &b.value)),
-            }
-        }
-
-        fn iter_cmp_by<I, F>(this: I, other: I, mut cmp: F) -> Ordering
-        where
-            I: IntoIterator,
-            F: FnMut(I::Item, I::Item) -> Ordering,
-        {
-            let mut this = this.into_iter();
-            let mut other = other.into_iter();
-
-            loop {
-                let x = match this.next() {
-                    None => {
-                        if other.next().is_none() {
-                            return Ordering::Equal;
-                        } else {
-                            return Ordering::Less;
-                        }
-                    }
-                    Some(val) => val,
-                };
-
-                let y = match other.next() {
-                    None => return Ordering::Greater,
-                    Some(val) => val,
-                };
-
-                match cmp(x, y) {
-                    Ordering::Equal => {}
-                    non_eq => return non_eq,
-                }
-            }
-        }
-
         // While sorting by map key, we get to assume that no two keys are
         // equal, otherwise they wouldn't both be in the map. This is not a safe
         // assumption outside of this situation.
@@ -484,6 +565,75 @@ impl PartialOrd for Mapping {
     }
 }
 
+// Sorts in an arbitrary order that is consistent with Value's PartialOrd
+// impl.
+pub(crate) fn total_cmp(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Bool(_), _) => Ordering::Less,
+        (_, Value::Bool(_)) => Ordering::Greater,
+
+        (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
+        (Value::Number(_), _) => Ordering::Less,
+        (_, Value::Number(_)) => Ordering::Greater,
+
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::String(_), _) => Ordering::Less,
+        (_, Value::String(_)) => Ordering::Greater,
+
+        (Value::Sequence(a), Value::Sequence(b)) => iter_cmp_by(a, b, total_cmp),
+        (Value::Sequence(_), _) => Ordering::Less,
+        (_, Value::Sequence(_)) => Ordering::Greater,
+
+        (Value::Mapping(a), Value::Mapping(b)) => iter_cmp_by(a, b, |(ak, av), (bk, bv)| {
+            total_cmp(ak, bk).then_with(|| total_cmp(av, bv))
+        }),
+        (Value::Mapping(_), _) => Ordering::Less,
+        (_, Value::Mapping(_)) => Ordering::Greater,
+
+        (Value::Tagged(a), Value::Tagged(b)) => a
+            .tag
+            .cmp(&b.tag)
+            .then_with(|| total_cmp(&a.value, &b.value)),
+    }
+}
+
+fn iter_cmp_by<I, F>(this: I, other: I, mut cmp: F) -> Ordering
+where
+    I: IntoIterator,
+    F: FnMut(I::Item, I::Item) -> Ordering,
+{
+    let mut this = this.into_iter();
+    let mut other = other.into_iter();
+
+    loop {
+        let x = match this.next() {
+            None => {
+                if other.next().is_none() {
+                    return Ordering::Equal;
+                } else {
+                    return Ordering::Less;
+                }
+            }
+            Some(val) => val,
+        };
+
+        let y = match other.next() {
+            None => return Ordering::Greater,
+            Some(val) => val,
+        };
+
+        match cmp(x, y) {
+            Ordering::Equal => {}
+            non_eq => return non_eq,
+        }
+    }
+}
+
 impl<I> std::ops::Index<I> for Mapping
 where
     I: Index,
@@ -519,7 +669,7 @@ impl FromIterator<(Value, Value)> for Mapping {
     #[inline]
     fn from_iter<I: IntoIterator<Item = (Value, Value)>>(iter: I) -> Self {
         Mapping {
-            map: IndexMap::from_iter(iter),
+            map: Map::from_iter(iter),
         }
     }
 }
@@ -538,6 +688,13 @@ macro_rules! delegate_iterator {
             }
         }
 
+        impl $($generics)* DoubleEndedIterator for $name $($generics)* {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.iter.next_back()
+            }
+        }
+
         impl $($generics)* ExactSizeIterator for $name $($generics)* {
             #[inline]
             fn len(&self) -> usize {
@@ -549,7 +706,7 @@ macro_rules! delegate_iterator {
 
 /// Iterator over `&serde_yaml::Mapping`.
 pub struct Iter<'a> {
-    iter: indexmap::map::Iter<'a, Value, Value>,
+    iter: store::Iter<'a, Value, Value>,
 }
 
 delegate_iterator!((Iter<'a>) => (&'a Value, &'a Value));
@@ -567,7 +724,7 @@ impl<'a> IntoIterator for &'a Mapping {
 
 /// Iterator over `&mut serde_yaml::Mapping`.
 pub struct IterMut<'a> {
-    iter: indexmap::map::IterMut<'a, Value, Value>,
+    iter: store::IterMut<'a, Value, Value>,
 }
 
 delegate_iterator!((IterMut<'a>) => (&'a Value, &'a mut Value));
@@ -585,7 +742,7 @@ impl<'a> IntoIterator for &'a mut Mapping {
 
 /// Iterator over `serde_yaml::Mapping` by value.
 pub struct IntoIter {
-    iter: indexmap::map::IntoIter<Value, Value>,
+    iter: store::IntoIter<Value, Value>,
 }
 
 delegate_iterator!((IntoIter) => (Value, Value));
@@ -603,35 +760,35 @@ impl IntoIterator for Mapping {
 
 /// Iterator of the keys of a `&serde_yaml::Mapping`.
 pub struct Keys<'a> {
-    iter: indexmap::map::Keys<'a, Value, Value>,
+    iter: store::Keys<'a, Value, Value>,
 }
 
 delegate_iterator!((Keys<'a>) => &'a Value);
 
 /// Iterator of the keys of a `serde_yaml::Mapping`.
 pub struct IntoKeys {
-    iter: indexmap::map::IntoKeys<Value, Value>,
+    iter: store::IntoKeys<Value, Value>,
 }
 
 delegate_iterator!((IntoKeys) => Value);
 
 /// Iterator of the values of a `&serde_yaml::Mapping`.
 pub struct Values<'a> {
-    iter: indexmap::map::Values<'a, Value, Value>,
+    iter: store::Values<'a, Value, Value>,
 }
 
 delegate_iterator!((Values<'a>) => &'a Value);
 
 /// Iterator of the values of a `&mut serde_yaml::Mapping`.
 pub struct ValuesMut<'a> {
-    iter: indexmap::map::ValuesMut<'a, Value, Value>,
+    iter: store::ValuesMut<'a, Value, Value>,
 }
 
 delegate_iterator!((ValuesMut<'a>) => &'a mut Value);
 
 /// Iterator of the values of a `serde_yaml::Mapping`.
 pub struct IntoValues {
-    iter: indexmap::map::IntoValues<Value, Value>,
+    iter: store::IntoValues<Value, Value>,
 }
 
 delegate_iterator!((IntoValues) => Value);
@@ -647,13 +804,13 @@ pub enum Entry<'a> {
 /// A view into an occupied entry in a [`Mapping`]. It is part of the [`Entry`]
 /// enum.
 pub struct OccupiedEntry<'a> {
-    occupied: indexmap::map::OccupiedEntry<'a, Value, Value>,
+    occupied: store::OccupiedEntry<'a, Value, Value>,
 }
 
 /// A view into a vacant entry in a [`Mapping`]. It is part of the [`Entry`]
 /// enum.
 pub struct VacantEntry<'a> {
-    vacant: indexmap::map::VacantEntry<'a, Value, Value>,
+    vacant: store::VacantEntry<'a, Value, Value>,
 }
 
 impl<'a> Entry<'a> {
@@ -687,6 +844,28 @@ impl<'a> Entry<'a> {
         }
     }
 
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function if empty, and returns a mutable reference to the value in the
+    /// entry. The function is given a reference to the entry's key.
+    pub fn or_insert_with_key<F>(self, default: F) -> &'a mut Value
+    where
+        F: FnOnce(&Value) -> Value,
+    {
+        match self {
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `Value::Null` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut Value {
+        self.or_insert(Value::Null)
+    }
+
     /// Provides in-place mutable access to an occupied entry before any
     /// potential inserts into the map.
     pub fn and_modify<F>(self, f: F) -> Self
@@ -770,6 +949,12 @@ impl<'a> VacantEntry<'a> {
     }
 }
 
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
 impl Serialize for Mapping {
     #[inline]
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -0,0 +1,405 @@
+//! Backing store of `Mapping` when the `small` feature is enabled: a `Vec` of
+//! key-value pairs in insertion order, searched linearly.
+//!
+//! The names and signatures mirror the subset of `indexmap::map` that
+//! `Mapping` uses, so that the two stores can be swapped by changing imports.
+
+use std::borrow::Borrow;
+use std::mem;
+use std::slice;
+use std::vec;
+
+/// Key equivalence for lookups, like `indexmap::Equivalent`.
+pub(crate) trait Equivalent<K: ?Sized> {
+    fn equivalent(&self, key: &K) -> bool;
+}
+
+impl<Q, K> Equivalent<K> for Q
+where
+    Q: ?Sized + Eq,
+    K: ?Sized + Borrow<Q>,
+{
+    fn equivalent(&self, key: &K) -> bool {
+        *self == *key.borrow()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct VecMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> Default for VecMap<K, V> {
+    fn default() -> Self {
+        VecMap {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> VecMap<K, V> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        VecMap {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    pub(crate) fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.entries.retain_mut(|(k, v)| keep(k, v));
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        let (k, v) = self.entries.get(index)?;
+        Some((k, v))
+    }
+
+    pub(crate) fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        let (k, v) = self.entries.get_mut(index)?;
+        Some((k, v))
+    }
+
+    pub(crate) fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+
+    pub(crate) fn move_index(&mut self, from: usize, to: usize) {
+        if from < to {
+            self.entries[from..=to].rotate_left(1);
+        } else {
+            self.entries[to..=from].rotate_right(1);
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.entries.iter(),
+        }
+    }
+
+    pub(crate) fn into_keys(self) -> IntoKeys<K, V> {
+        IntoKeys {
+            iter: self.entries.into_iter(),
+        }
+    }
+
+    pub(crate) fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.entries.iter(),
+        }
+    }
+
+    pub(crate) fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+
+    pub(crate) fn into_values(self) -> IntoValues<K, V> {
+        IntoValues {
+            iter: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<K: Eq, V> VecMap<K, V> {
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        self.entries.iter().position(|(k, _)| key.equivalent(k))
+    }
+
+    pub(crate) fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        self.index_of(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.index_of(&key) {
+            Some(index) => Some(mem::replace(&mut self.entries[index].1, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn shift_insert(&mut self, index: usize, key: K, value: V) -> Option<V> {
+        match self.index_of(&key) {
+            Some(from) => {
+                let old = mem::replace(&mut self.entries[from].1, value);
+                self.move_index(from, index);
+                Some(old)
+            }
+            None => {
+                self.entries.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        self.index_of(key).is_some()
+    }
+
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        let index = self.index_of(key)?;
+        Some(&self.entries[index].1)
+    }
+
+    pub(crate) fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        let index = self.index_of(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    pub(crate) fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.index_of(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                entries: &mut self.entries,
+                index,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                entries: &mut self.entries,
+                key,
+            }),
+        }
+    }
+
+    pub(crate) fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        self.swap_remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub(crate) fn swap_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        let index = self.index_of(key)?;
+        Some(self.entries.swap_remove(index))
+    }
+
+    pub(crate) fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        self.shift_remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub(crate) fn shift_remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        let index = self.index_of(key)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+// Like IndexMap, equality does not depend on the order of the entries.
+impl<K: Eq, V: PartialEq> PartialEq for VecMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(k, v)| other.get(k).is_some_and(|other| *v == *other))
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for VecMap<K, V> {}
+
+impl<K: Eq, V> Extend<(K, V)> for VecMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Eq, V> FromIterator<(K, V)> for VecMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = VecMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> IntoIterator for VecMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.entries.into_iter(),
+        }
+    }
+}
+
+macro_rules! iterator {
+    ($name:ident<$($lt:lifetime,)? K, V>, $inner:ty, $item:ty $(, $f:expr)?) => {
+        pub(crate) struct $name<$($lt,)? K, V> {
+            iter: $inner,
+        }
+
+        impl<$($lt,)? K, V> Iterator for $name<$($lt,)? K, V> {
+            type Item = $item;
+            fn next(&mut self) -> Option<Self::Item> {
+                self.iter.next()$(.map($f))?
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<$($lt,)? K, V> DoubleEndedIterator for $name<$($lt,)? K, V> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.iter.next_back()$(.map($f))?
+            }
+        }
+
+        impl<$($lt,)? K, V> ExactSizeIterator for $name<$($lt,)? K, V> {
+            fn len(&self) -> usize {
+                self.iter.len()
+            }
+        }
+    };
+}
+
+iterator!(
+    Iter<'a, K, V>,
+    slice::Iter<'a, (K, V)>,
+    (&'a K, &'a V),
+    |entry| (&entry.0, &entry.1)
+);
+iterator!(
+    IterMut<'a, K, V>,
+    slice::IterMut<'a, (K, V)>,
+    (&'a K, &'a mut V),
+    |(k, v)| (&*k, v)
+);
+iterator!(IntoIter<K, V>, vec::IntoIter<(K, V)>, (K, V));
+iterator!(Keys<'a, K, V>, slice::Iter<'a, (K, V)>, &'a K, |(k, _)| k);
+iterator!(IntoKeys<K, V>, vec::IntoIter<(K, V)>, K, |(k, _)| k);
+iterator!(Values<'a, K, V>, slice::Iter<'a, (K, V)>, &'a V, |entry| {
+    &entry.1
+});
+iterator!(
+    ValuesMut<'a, K, V>,
+    slice::IterMut<'a, (K, V)>,
+    &'a mut V,
+    |(_, v)| v
+);
+iterator!(IntoValues<K, V>, vec::IntoIter<(K, V)>, V, |(_, v)| v);
+
+pub(crate) enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub(crate) struct OccupiedEntry<'a, K, V> {
+    entries: &'a mut Vec<(K, V)>,
+    index: usize,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub(crate) fn key(&self) -> &K {
+        &self.entries[self.index].0
+    }
+
+    pub(crate) fn get(&self) -> &V {
+        &self.entries[self.index].1
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut V {
+        &mut self.entries[self.index].1
+    }
+
+    pub(crate) fn into_mut(self) -> &'a mut V {
+        let OccupiedEntry { entries, index } = self;
+        &mut entries[index].1
+    }
+
+    pub(crate) fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub(crate) fn swap_remove(self) -> V {
+        self.swap_remove_entry().1
+    }
+
+    pub(crate) fn swap_remove_entry(self) -> (K, V) {
+        self.entries.swap_remove(self.index)
+    }
+}
+
+pub(crate) struct VacantEntry<'a, K, V> {
+    entries: &'a mut Vec<(K, V)>,
+    key: K,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub(crate) fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub(crate) fn into_key(self) -> K {
+        self.key
+    }
+
+    pub(crate) fn insert(self, value: V) -> &'a mut V {
+        let VacantEntry { entries, key } = self;
+        let index = entries.len();
+        entries.push((key, value));
+        &mut entries[index].1
+    }
+}
@@ -8,6 +8,19 @@ use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 /// Represents a YAML number, whether integer or floating point.
+///
+/// Equality and hashing distinguish integers from floats, so `1` and `1.0` are
+/// different numbers and remain distinct keys in a [`Mapping`]. Use
+/// [`Number::numeric_eq`] to compare the numeric values instead.
+///
+/// ```
+/// # use serde_yaml::Number;
+/// #
+/// assert_ne!(Number::from(1), Number::from(1.0));
+/// assert!(Number::from(1).numeric_eq(&Number::from(1.0)));
+/// ```
+///
+/// [`Mapping`]: crate::Mapping
 #[derive(Clone, PartialEq, PartialOrd)]
 pub struct Number {
     n: N,
@@ -290,6 +303,59 @@ impl Number {
             N::Float(f) => f.is_finite(),
         }
     }
+
+    /// Returns true if the two numbers have the same numeric value, regardless
+    /// of whether each is stored as an integer or a float.
+    ///
+    /// Unlike `==`, this treats the integer `1` and the float `1.0` as equal.
+    /// An integer only equals a float that represents it exactly, and NaN
+    /// equals NaN as it does under `==`.
+    ///
+    /// ```
+    /// # use serde_yaml::Number;
+    /// #
+    /// assert!(Number::from(-2).numeric_eq(&Number::from(-2.0)));
+    ///
+    /// assert!(!Number::from(1).numeric_eq(&Number::from(1.5)));
+    ///
+    /// assert!(!Number::from(u64::MAX).numeric_eq(&Number::from(u64::MAX as f64)));
+    /// ```
+    pub fn numeric_eq(&self, other: &Number) -> bool {
+        match (self.n, other.n) {
+            (N::PosInt(u), N::Float(f)) | (N::Float(f), N::PosInt(u)) => {
+                // 2^64, the first float above the range of u64
+                (0.0..18446744073709551616.0).contains(&f) && f.fract() == 0.0 && f as u64 == u
+            }
+            (N::NegInt(i), N::Float(f)) | (N::Float(f), N::NegInt(i)) => {
+                (i64::MIN as f64..0.0).contains(&f) && f.fract() == 0.0 && f as i64 == i
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Converts a finite or infinite `f64` to a `Number`. Returns None for NaN.
+    ///
+    /// YAML can represent NaN as `.nan`, and `From<f64>` accepts it, but a NaN
+    /// coming from arithmetic on untrusted input is usually a mistake that this
+    /// constructor catches.
+    ///
+    /// ```
+    /// # use serde_yaml::Number;
+    /// #
+    /// assert!(Number::from_f64(256.0).is_some());
+    ///
+    /// assert!(Number::from_f64(f64::INFINITY).is_some());
+    ///
+    /// assert!(Number::from_f64(f64::NAN).is_none());
+    /// ```
+    #[inline]
+    pub fn from_f64(f: f64) -> Option<Number> {
+        if f.is_nan() {
+            None
+        } else {
+            Some(Number { n: N::Float(f) })
+        }
+    }
 }
 
 impl Display for Number {
@@ -526,6 +592,8 @@ impl From<f32> for Number {
     }
 }
 
+/// Any `f64` converts, including NaN, which becomes the single YAML `.nan`.
+/// Use [`Number::from_f64`] to reject NaN instead.
 impl From<f64> for Number {
     fn from(mut f: f64) -> Self {
         if f.is_nan() {
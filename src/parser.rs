@@ -0,0 +1,189 @@
+//! Streaming access to the events produced by the YAML parser.
+//!
+//! This is a lower level alternative to deserializing into a `Value`. Events
+//! are yielded one at a time as the input is parsed, without building a tree,
+//! which suits tools that only need to inspect the structure of a document.
+//!
+//! ```
+//! use serde_yaml::parser::{Event, Events};
+//!
+//! let mut depth = 0;
+//! let mut max_depth = 0;
+//! for result in Events::from_str("a: [1, {b: 2}]") {
+//!     let (event, _location) = result.unwrap();
+//!     match event {
+//!         Event::MappingStart { .. } | Event::SequenceStart { .. } => {
+//!             depth += 1;
+//!             max_depth = max_depth.max(depth);
+//!         }
+//!         Event::MappingEnd | Event::SequenceEnd => depth -= 1,
+//!         _ => {}
+//!     }
+//! }
+//! assert_eq!(max_depth, 3);
+//! ```
+
+use crate::error::{Error, Location, Result};
+use crate::libyaml::parser::{self as libyaml, Parser};
+use crate::loader::decode_bom;
+use std::borrow::Cow;
+
+/// Iterator over the parse events of a YAML stream, together with the
+/// location in the input at which each event starts.
+///
+/// Iteration ends after [`Event::StreamEnd`] or after the first error.
+pub struct Events<'input> {
+    parser: Option<Parser<'input>>,
+    error: Option<Error>,
+}
+
+/// A single parse event.
+///
+/// Within a mapping, events alternate between a key node and a value node,
+/// where a node is a scalar, an alias, or a whole sequence or mapping from its
+/// start event to its matching end event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The start of the input.
+    StreamStart,
+    /// The end of the input.
+    StreamEnd,
+    /// The start of a document.
+    DocumentStart,
+    /// The end of a document.
+    DocumentEnd,
+    /// A reference to an earlier anchored node, like `*name`.
+    Alias {
+        /// The name of the referenced anchor, without the `*`.
+        anchor: String,
+    },
+    /// A scalar node.
+    Scalar {
+        /// The anchor defined on this node, without the `&`.
+        anchor: Option<String>,
+        /// The tag of this node, like `!thing` or `tag:yaml.org,2002:str`.
+        tag: Option<String>,
+        /// The content of the scalar, with quoting and escapes resolved.
+        value: String,
+        /// How the scalar was written in the input.
+        style: ScalarStyle,
+    },
+    /// The start of a sequence node.
+    SequenceStart {
+        /// The anchor defined on this node, without the `&`.
+        anchor: Option<String>,
+        /// The tag of this node.
+        tag: Option<String>,
+    },
+    /// The end of a sequence node.
+    SequenceEnd,
+    /// The start of a mapping node.
+    MappingStart {
+        /// The anchor defined on this node, without the `&`.
+        anchor: Option<String>,
+        /// The tag of this node.
+        tag: Option<String>,
+    },
+    /// The end of a mapping node.
+    MappingEnd,
+}
+
+/// How a scalar was written in the input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScalarStyle {
+    /// `value`
+    Plain,
+    /// `'value'`
+    SingleQuoted,
+    /// `"value"`
+    DoubleQuoted,
+    /// `|` followed by an indented block
+    Literal,
+    /// `>` followed by an indented block
+    Folded,
+}
+
+impl<'input> Events<'input> {
+    /// Creates a parse event iterator over a `&str` of YAML.
+    pub fn from_str(s: &'input str) -> Self {
+        Events::from_slice(s.as_bytes())
+    }
+
+    /// Creates a parse event iterator over a `&[u8]` of YAML.
+    ///
+    /// Input starting with a UTF-16 byte order mark is transcoded to UTF-8
+    /// first, as it is for deserialization.
+    pub fn from_slice(v: &'input [u8]) -> Self {
+        match decode_bom(Cow::Borrowed(v)) {
+            Ok(input) => Events {
+                parser: Some(Parser::new(input)),
+                error: None,
+            },
+            Err(error) => Events {
+                parser: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<(Event, Location)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(error));
+        }
+        let parser = self.parser.as_mut()?;
+        match parser.next() {
+            Ok((event, mark)) => {
+                if let libyaml::Event::StreamEnd = event {
+                    self.parser = None;
+                }
+                Some(Ok((convert(event), Location::from_mark(mark))))
+            }
+            Err(error) => {
+                self.parser = None;
+                Some(Err(Error::from(error)))
+            }
+        }
+    }
+}
+
+fn convert(event: libyaml::Event<'_>) -> Event {
+    match event {
+        libyaml::Event::StreamStart => Event::StreamStart,
+        libyaml::Event::StreamEnd => Event::StreamEnd,
+        libyaml::Event::DocumentStart => Event::DocumentStart,
+        libyaml::Event::DocumentEnd(_) => Event::DocumentEnd,
+        libyaml::Event::Alias(anchor) => Event::Alias {
+            anchor: lossy(&anchor),
+        },
+        libyaml::Event::Scalar(scalar) => Event::Scalar {
+            anchor: scalar.anchor.as_deref().map(lossy),
+            tag: scalar.tag.as_deref().map(lossy),
+            value: lossy(&scalar.value),
+            style: match scalar.style {
+                libyaml::ScalarStyle::Plain => ScalarStyle::Plain,
+                libyaml::ScalarStyle::SingleQuoted => ScalarStyle::SingleQuoted,
+                libyaml::ScalarStyle::DoubleQuoted => ScalarStyle::DoubleQuoted,
+                libyaml::ScalarStyle::Literal => ScalarStyle::Literal,
+                libyaml::ScalarStyle::Folded => ScalarStyle::Folded,
+            },
+        },
+        libyaml::Event::SequenceStart(sequence_start) => Event::SequenceStart {
+            anchor: sequence_start.anchor.as_deref().map(lossy),
+            tag: sequence_start.tag.as_deref().map(lossy),
+        },
+        libyaml::Event::SequenceEnd => Event::SequenceEnd,
+        libyaml::Event::MappingStart(mapping_start) => Event::MappingStart {
+            anchor: mapping_start.anchor.as_deref().map(lossy),
+            tag: mapping_start.tag.as_deref().map(lossy),
+        },
+        libyaml::Event::MappingEnd => Event::MappingEnd,
+    }
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
@@ -4,16 +4,25 @@
 
 use crate::error::{self, Error, ErrorImpl};
 use crate::libyaml;
-use crate::libyaml::emitter::{Emitter, Event, Mapping, Scalar, ScalarStyle, Sequence};
+use crate::libyaml::emitter::{self, Emitter, Event, Mapping, Scalar, ScalarStyle, Sequence};
+use crate::libyaml::tag::Tag;
+use crate::mapping;
 use crate::value::tagged::{self, MaybeTag};
-use serde::de::Visitor;
-use serde::ser::{self, Serializer as _};
+use crate::value::Value;
+use serde::de::{self, Deserialize, Visitor};
+use serde::ser;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Display};
 use std::io;
 use std::marker::PhantomData;
 use std::mem;
 use std::num;
+use std::rc::Rc;
+use std::slice;
 use std::str;
+use std::vec;
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -41,13 +50,299 @@ type Result<T, E = Error> = std::result::Result<T, E>;
 ///     Ok(())
 /// }
 /// ```
+///
+/// # Memory use
+///
+/// Output reaches the underlying writer as it is produced, through a fixed
+/// size buffer, and the emitter looks ahead by at most a few events. So a
+/// sequence or map produced by an iterator, as with
+/// [`collect_seq`](ser::Serializer::collect_seq), is written element by
+/// element in bounded memory however long it is. The exceptions are
+/// [`set_sorted_keys`](Serializer::set_sorted_keys) and
+/// [`set_anchor_dedup`](Serializer::set_anchor_dedup), which hold each whole
+/// document.
 pub struct Serializer<W> {
     depth: usize,
     state: State,
+    null_style: NullStyle,
+    bool_style: BoolStyle,
+    yaml_1_1_bools: bool,
+    enum_style: EnumStyle,
+    empty_style: EmptyStyle,
+    key_case: KeyCase,
+    key_pending: bool,
+    // The string keys written so far in each open mapping, when their case
+    // is converted.
+    mapping_keys: Vec<HashSet<String>>,
+    string_quoting: QuoteStyle,
+    flow: bool,
+    sort_keys: bool,
+    anchor_dedup: bool,
+    capture: bool,
+    document: Vec<Node>,
+    explicit_core_tags: bool,
+    digit_grouping: bool,
+    plus_sign: bool,
+    trailing_newline: Rc<Cell<bool>>,
     emitter: Emitter<'static>,
     writer: PhantomData<W>,
 }
 
+/// How `None` and `()` are written by the [`Serializer`].
+///
+/// All three spellings deserialize back to null.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NullStyle {
+    /// `key: null`
+    #[default]
+    Keyword,
+    /// `key: ~`
+    Tilde,
+    /// `key:`
+    ///
+    /// Where an empty scalar cannot be written, such as for a mapping key,
+    /// the emitter falls back to `~`.
+    Empty,
+}
+
+/// How booleans are written by the [`Serializer`].
+///
+/// Only the default is a boolean in YAML 1.2 under every schema. The
+/// capitalized spelling reads back as a boolean with this crate's
+/// [`Deserializer`](crate::Deserializer), and the others only with its
+/// [`yaml_1_1_bools`](crate::Deserializer::yaml_1_1_bools) option. Other
+/// YAML 1.2 parsers may read any of them as strings, so use them only for
+/// consumers known to expect them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BoolStyle {
+    /// `key: true`
+    #[default]
+    TrueFalse,
+    /// `key: True`
+    Capitalized,
+    /// `key: yes`
+    YesNo,
+    /// `key: on`
+    OnOff,
+}
+
+/// How enum variants with data are written by the [`Serializer`].
+///
+/// Unit variants are written as a plain string in either style, and both
+/// styles deserialize back to the same variant.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EnumStyle {
+    /// `key: !Variant value`
+    #[default]
+    Tag,
+    /// `key: {Variant: value}`, a map with a single entry as in Serde's
+    /// externally tagged representation.
+    Map,
+}
+
+/// How empty mappings and sequences are written by the [`Serializer`].
+///
+/// YAML has no block syntax for an empty collection, so both styles use flow
+/// brackets. Both deserialize back to the same empty value.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmptyStyle {
+    /// `key: {}` and `key: []`
+    #[default]
+    Flow,
+    /// `key: !!map {}` and `key: !!seq []`
+    ///
+    /// The tag is only added when the collection's length is known to be zero
+    /// when serializing it starts, which is the case for the standard
+    /// collections, structs and `Value`. A collection that already carries a
+    /// tag, such as an enum variant, keeps it.
+    Explicit,
+}
+
+/// How the string keys of maps and structs are written by the [`Serializer`].
+///
+/// Keys are split into words at `_`, `-` and spaces, and where a lowercase
+/// letter or digit is followed by an uppercase letter, so `max_retries`,
+/// `max-retries` and `maxRetries` all consist of the words `max` and
+/// `retries`. Keys that are not strings are written unchanged.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeyCase {
+    /// Keys as given by the `Serialize` impl
+    #[default]
+    AsIs,
+    /// `max_retries: 3`
+    Snake,
+    /// `max-retries: 3`
+    Kebab,
+    /// `maxRetries: 3`
+    Camel,
+}
+
+impl KeyCase {
+    fn convert(self, key: &str) -> Cow<'_, str> {
+        let separator = match self {
+            KeyCase::AsIs => return Cow::Borrowed(key),
+            KeyCase::Snake => "_",
+            KeyCase::Kebab => "-",
+            KeyCase::Camel => "",
+        };
+        let words = split_words(key);
+        if words.is_empty() {
+            return Cow::Borrowed(key);
+        }
+        let mut converted = String::with_capacity(key.len());
+        for (i, word) in words.into_iter().enumerate() {
+            let mut chars = word.chars();
+            if i > 0 {
+                converted.push_str(separator);
+                if self == KeyCase::Camel {
+                    converted.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                }
+            }
+            converted.extend(chars.flat_map(char::to_lowercase));
+        }
+        Cow::Owned(converted)
+    }
+}
+
+// Splits a key into words at separators and case changes. An uppercase letter
+// starts a new word after a lowercase letter or digit, or at the end of a run
+// of uppercase letters that is followed by a lowercase one, as in `HTTPServer`.
+fn split_words(key: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for segment in key.split(['_', '-', ' ']) {
+        let chars: Vec<(usize, char)> = segment.char_indices().collect();
+        let mut start = 0;
+        for (i, &(index, ch)) in chars.iter().enumerate().skip(1) {
+            let previous = chars[i - 1].1;
+            let next_is_lowercase = chars
+                .get(i + 1)
+                .is_some_and(|&(_, next)| next.is_lowercase());
+            let boundary = ch.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next_is_lowercase));
+            if boundary {
+                words.push(&segment[start..index]);
+                start = index;
+            }
+        }
+        if start < segment.len() {
+            words.push(&segment[start..]);
+        }
+    }
+    words
+}
+
+/// How string scalars are quoted by the [`Serializer`].
+///
+/// Whatever the choice, strings are quoted when written plain they would read
+/// back as something other than a string, like `true` or `1.0`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Leave the choice to the emitter: plain where possible, single quotes
+    /// where needed, and a `|` block for multi-line strings.
+    #[default]
+    Auto,
+    /// `key: 'value'`
+    Single,
+    /// `key: "value"`
+    Double,
+    /// `key: value`
+    ///
+    /// Multi-line strings are written as plain or quoted flow scalars rather
+    /// than `|` blocks.
+    Plain,
+}
+
+/// The writer given to the emitter. Unless trailing newlines are enabled, it
+/// holds back a line break at the end of what the emitter writes until more
+/// output follows, so that the last document does not end with one.
+struct Output<W> {
+    writer: W,
+    trailing_newline: Rc<Cell<bool>>,
+    pending_newline: bool,
+}
+
+impl<W> io::Write for Output<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if mem::take(&mut self.pending_newline) {
+            self.writer.write_all(b"\n")?;
+        }
+        match buf.strip_suffix(b"\n") {
+            Some(rest) if !self.trailing_newline.get() => {
+                self.writer.write_all(rest)?;
+                self.pending_newline = true;
+            }
+            _ => self.writer.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// An event within a document, held back by [`Serializer::set_sorted_keys`] or
+/// [`Serializer::set_anchor_dedup`] until the document is complete.
+#[derive(PartialEq, Eq, Hash)]
+enum Node {
+    Scalar(Option<String>, String, ScalarStyle),
+    SequenceStart(Option<String>, bool),
+    SequenceEnd,
+    MappingStart(Option<String>, bool),
+    MappingEnd,
+}
+
+impl Node {
+    fn event(&self) -> Event<'_> {
+        match self {
+            Node::Scalar(tag, value, style) => Event::Scalar(Scalar {
+                tag: tag.clone(),
+                value,
+                style: *style,
+            }),
+            Node::SequenceStart(tag, flow) => Event::SequenceStart(Sequence {
+                anchor: None,
+                tag: tag.clone(),
+                flow: *flow,
+            }),
+            Node::SequenceEnd => Event::SequenceEnd,
+            Node::MappingStart(tag, flow) => Event::MappingStart(Mapping {
+                anchor: None,
+                tag: tag.clone(),
+                flow: *flow,
+            }),
+            Node::MappingEnd => Event::MappingEnd,
+        }
+    }
+}
+
+impl From<Event<'_>> for Node {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Scalar(scalar) => {
+                Node::Scalar(scalar.tag, scalar.value.to_owned(), scalar.style)
+            }
+            Event::SequenceStart(sequence) => Node::SequenceStart(sequence.tag, sequence.flow),
+            Event::SequenceEnd => Node::SequenceEnd,
+            Event::MappingStart(mapping) => Node::MappingStart(mapping.tag, mapping.flow),
+            Event::MappingEnd => Node::MappingEnd,
+            Event::StreamStart
+            | Event::StreamEnd
+            | Event::DocumentStart
+            | Event::DocumentEnd
+            | Event::Alias(_) => unreachable!(),
+        }
+    }
+}
+
 enum State {
     NothingInParticular,
     CheckForTag,
@@ -62,19 +357,442 @@ where
 {
     /// Creates a new YAML serializer.
     pub fn new(writer: W) -> Self {
+        let trailing_newline = Rc::new(Cell::new(true));
         let mut emitter = Emitter::new({
-            let writer = Box::new(writer);
+            let writer = Box::new(Output {
+                writer,
+                trailing_newline: Rc::clone(&trailing_newline),
+                pending_newline: false,
+            });
             unsafe { mem::transmute::<Box<dyn io::Write>, Box<dyn io::Write>>(writer) }
         });
         emitter.emit(Event::StreamStart).unwrap();
         Serializer {
             depth: 0,
             state: State::NothingInParticular,
+            null_style: NullStyle::Keyword,
+            bool_style: BoolStyle::TrueFalse,
+            yaml_1_1_bools: false,
+            enum_style: EnumStyle::Tag,
+            empty_style: EmptyStyle::Flow,
+            key_case: KeyCase::AsIs,
+            key_pending: false,
+            mapping_keys: Vec::new(),
+            string_quoting: QuoteStyle::Auto,
+            flow: false,
+            sort_keys: false,
+            anchor_dedup: false,
+            capture: false,
+            document: Vec::new(),
+            explicit_core_tags: false,
+            digit_grouping: false,
+            plus_sign: false,
+            trailing_newline,
             emitter,
             writer: PhantomData,
         }
     }
 
+    /// Selects the style in which string scalars, including mapping keys,
+    /// are quoted. The default is [`QuoteStyle::Auto`].
+    ///
+    /// ```
+    /// use serde_yaml::{QuoteStyle, Serializer};
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("name", "demo");
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_string_quoting(QuoteStyle::Double);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"\"name\": \"demo\"\n");
+    /// ```
+    pub fn set_string_quoting(&mut self, quoting: QuoteStyle) {
+        self.string_quoting = quoting;
+    }
+
+    /// Selects whether the entries of every map and struct are written sorted
+    /// by key. Off by default.
+    ///
+    /// Each document is held in memory until it is complete, then the entries
+    /// of each map are sorted by the [`Value`] that their key reads back as,
+    /// so string keys are in lexicographic order and integer keys in numeric
+    /// order. Everything else is written as it would be without sorting.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    /// map.insert("a", 1);
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_sorted_keys(true);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"a: 1\nb: 2\nc: 3\n");
+    /// ```
+    pub fn set_sorted_keys(&mut self, sorted: bool) {
+        self.sort_keys = sorted;
+    }
+
+    /// Selects whether a sequence or map that is repeated within a document
+    /// is written only once, with an anchor, and as an alias to it everywhere
+    /// else. Off by default.
+    ///
+    /// Each document is held in memory until it is complete, then its
+    /// sequences and maps are compared by structure, including tags and
+    /// scalar styles. Anchors are named `id001`, `id002` and so on. Empty
+    /// collections and scalars are never replaced by aliases, as that would
+    /// not make the output shorter.
+    ///
+    /// This is meant for serializing a [`Value`] with heavily duplicated
+    /// subtrees. Because the comparison is made on what is about to be
+    /// written, it finds the same repeats in any other `Serialize` type.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use serde_yaml::{Serializer, Value};
+    ///
+    /// let yaml = "
+    /// dev: {image: app, env: [A=1, B=2]}
+    /// prod: {image: app, env: [A=1, B=2]}
+    /// test: {image: test, env: [A=1, B=2]}
+    /// ";
+    /// let value: Value = serde_yaml::from_str(yaml).unwrap();
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_anchor_dedup(true);
+    /// value.serialize(&mut ser).unwrap();
+    ///
+    /// let expected = "\
+    /// dev: &id001
+    ///   image: app
+    ///   env: &id002
+    ///   - A=1
+    ///   - B=2
+    /// prod: *id001
+    /// test:
+    ///   image: test
+    ///   env: *id002
+    /// ";
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    /// assert_eq!(serde_yaml::from_str::<Value>(expected).unwrap(), value);
+    /// ```
+    pub fn set_anchor_dedup(&mut self, dedup: bool) {
+        self.anchor_dedup = dedup;
+    }
+
+    /// Selects whether every non-ASCII character in a scalar is written as an
+    /// escape such as `\xE9`, `\u20AC` or `\U0001F600`, so that the output is
+    /// pure ASCII. Scalars containing such characters are then written
+    /// double-quoted.
+    ///
+    /// By default, non-ASCII characters are written as UTF-8.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("price", "5 \u{20ac}");
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_unicode_escapes(true);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"price: \"5 \\u20AC\"\n");
+    /// ```
+    pub fn set_unicode_escapes(&mut self, escape: bool) {
+        self.emitter.set_unicode(!escape);
+    }
+
+    /// Selects how null values are written.
+    ///
+    /// ```
+    /// use serde_yaml::{NullStyle, Serializer};
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("k", None::<u8>);
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_null_style(NullStyle::Tilde);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"k: ~\n");
+    /// ```
+    pub fn set_null_style(&mut self, style: NullStyle) {
+        self.null_style = style;
+    }
+
+    /// Selects how booleans are written. Refer to [`BoolStyle`] for which
+    /// spellings other parsers can be expected to read back.
+    ///
+    /// ```
+    /// use serde_yaml::{BoolStyle, Serializer};
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("enabled", true);
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_bool_style(BoolStyle::YesNo);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"enabled: yes\n");
+    /// ```
+    pub fn set_bool_style(&mut self, style: BoolStyle) {
+        self.bool_style = style;
+    }
+
+    /// Quotes strings that YAML 1.1 reads as booleans, like `yes` and `off`,
+    /// so that the output reads back as strings under
+    /// [`Deserializer::yaml_1_1_bools`](crate::Deserializer::yaml_1_1_bools)
+    /// too. Off by default, since YAML 1.2 reads them as strings either way.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_yaml_1_1_bools(true);
+    /// ["yes", "Off", "y"].serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"- 'yes'\n- 'Off'\n- y\n");
+    /// ```
+    pub fn set_yaml_1_1_bools(&mut self, enabled: bool) {
+        self.yaml_1_1_bools = enabled;
+    }
+
+    /// Selects how enum variants with data are written.
+    ///
+    /// ```
+    /// use serde_yaml::{EnumStyle, Serializer};
+    /// use serde::Serialize;
+    /// # use serde_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// enum Shape {
+    ///     Circle { radius: u32 },
+    /// }
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_enum_style(EnumStyle::Map);
+    /// Shape::Circle { radius: 1 }.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"Circle:\n  radius: 1\n");
+    /// ```
+    pub fn set_enum_style(&mut self, style: EnumStyle) {
+        self.enum_style = style;
+    }
+
+    /// Selects how empty mappings and sequences are written.
+    ///
+    /// ```
+    /// use serde_yaml::{EmptyStyle, Serializer};
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("k", Vec::<u8>::new());
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_empty_style(EmptyStyle::Explicit);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"k: !!seq []\n");
+    /// ```
+    pub fn set_empty_style(&mut self, style: EmptyStyle) {
+        self.empty_style = style;
+    }
+
+    /// Selects how the string keys of maps and structs are written, so that
+    /// the same types can follow a different naming convention in YAML
+    /// without `#[serde(rename_all)]`.
+    ///
+    /// ```
+    /// use serde_yaml::{KeyCase, Serializer};
+    /// use serde::Serialize;
+    /// # use serde_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     max_retries: u32,
+    ///     log_level: &'static str,
+    /// }
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_key_case(KeyCase::Kebab);
+    /// Config { max_retries: 3, log_level: "info" }.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"max-retries: 3\nlog-level: info\n");
+    /// ```
+    pub fn set_key_case(&mut self, case: KeyCase) {
+        self.key_case = case;
+    }
+
+    /// Writes null, boolean, integer and float scalars with their explicit
+    /// core schema tag, like `!!null null` and `!!bool true`, for consumers
+    /// that do not resolve untagged scalars to these types.
+    ///
+    /// Strings are not tagged. The output deserializes back to the same
+    /// values as it would without the tags.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("a", Some(true));
+    /// map.insert("b", None);
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_explicit_core_tags(true);
+    /// map.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"a: !!bool true\nb: !!null null\n");
+    /// ```
+    pub fn set_explicit_core_tags(&mut self, explicit: bool) {
+        self.explicit_core_tags = explicit;
+    }
+
+    /// Writes integers with their digits grouped in threes by underscores,
+    /// like `1_000_000`, which reads back as the same integer. Floats are
+    /// written as usual, since YAML does not allow underscores in them.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_digit_grouping(true);
+    /// (1_000_000, -12_345, 999, 1234.5).serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"- 1_000_000\n- -12_345\n- 999\n- 1234.5\n");
+    /// ```
+    pub fn set_digit_grouping(&mut self, grouping: bool) {
+        self.digit_grouping = grouping;
+    }
+
+    /// Writes non-negative integers and floats with an explicit `+` sign,
+    /// like `+1` and `+0.5`, which reads back as the same number. NaN is
+    /// written without a sign.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_plus_sign(true);
+    /// (1, -1, 0.5, f64::INFINITY).serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"- +1\n- -1\n- +0.5\n- +.inf\n");
+    /// ```
+    pub fn set_plus_sign(&mut self, plus: bool) {
+        self.plus_sign = plus;
+    }
+
+    /// Selects whether each document ends with a line break, which it does by
+    /// default. Without it the output can be embedded in a line of other text.
+    ///
+    /// Only the final line break is left out. If the document ends in a `|+`
+    /// block scalar that keeps its trailing line breaks, that scalar loses one.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_trailing_newline(false);
+    /// vec![1, 2].serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"- 1\n- 2");
+    /// ```
+    pub fn set_trailing_newline(&mut self, trailing: bool) {
+        self.trailing_newline.set(trailing);
+    }
+
+    /// Selects whether each document starts with a `%YAML 1.2` directive,
+    /// followed by an explicit `---` marker. Off by default.
+    ///
+    /// ```
+    /// use serde_yaml::Serializer;
+    /// use serde::Serialize;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_yaml_version_directive(true);
+    /// BTreeMap::from([("k", 1)]).serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"%YAML 1.2\n---\nk: 1\n");
+    /// ```
+    pub fn set_yaml_version_directive(&mut self, enabled: bool) {
+        self.emitter.set_version_directive(enabled);
+    }
+
+    /// Adds a `%TAG` directive at the start of each document, declaring
+    /// `handle`, such as `!e!`, as shorthand for `prefix`. Tags starting with
+    /// `prefix` are then written using the handle.
+    ///
+    /// The handle must start and end with `!`; otherwise serializing fails.
+    /// The prefix may only contain ASCII letters and digits and the characters
+    /// ``-_;/?:@&=+$,.~*'()[]``, which libyaml writes without percent-escaping
+    /// them. Any other prefix, including a local one starting with `!`, is
+    /// rejected, since escaping would change its meaning.
+    ///
+    /// ```
+    /// use serde_yaml::value::{Tag, TaggedValue};
+    /// use serde_yaml::{Serializer, Value};
+    /// use serde::Serialize;
+    ///
+    /// let value = Value::Tagged(Box::new(TaggedValue {
+    ///     tag: Tag::new("!!set"),
+    ///     value: Value::Null,
+    /// }));
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.add_tag_directive("!y!", "tag:yaml.org,2002:").unwrap();
+    /// assert!(ser.add_tag_directive("!e!", "!example.com/").is_err());
+    /// value.serialize(&mut ser).unwrap();
+    ///
+    /// assert_eq!(buffer, b"%TAG !y! tag:yaml.org,2002:\n--- !y!set null\n");
+    /// ```
+    pub fn add_tag_directive(&mut self, handle: &str, prefix: &str) -> Result<()> {
+        if !emitter::is_plain_tag_content(prefix) {
+            return Err(error::new(ErrorImpl::EscapedTagPrefix(prefix.to_owned())));
+        }
+        self.emitter
+            .add_tag_directive(handle.to_owned(), prefix.to_owned());
+        Ok(())
+    }
+
     /// Calls [`.flush()`](io::Write::flush) on the underlying `io::Write`
     /// object.
     pub fn flush(&mut self) -> Result<()> {
@@ -87,47 +805,239 @@ where
         self.emitter.emit(Event::StreamEnd)?;
         self.emitter.flush()?;
         let writer = self.emitter.into_inner();
-        Ok(*unsafe { Box::from_raw(Box::into_raw(writer).cast::<W>()) })
+        let output = unsafe { Box::from_raw(Box::into_raw(writer).cast::<Output<W>>()) };
+        Ok(output.writer)
     }
 
     fn emit_scalar(&mut self, mut scalar: Scalar) -> Result<()> {
+        self.key_pending = false;
         self.flush_mapping_start()?;
         if let Some(tag) = self.take_tag() {
             scalar.tag = Some(tag);
         }
         self.value_start()?;
-        self.emitter.emit(Event::Scalar(scalar))?;
+        self.emit_node(Event::Scalar(scalar))?;
         self.value_end()
     }
 
-    fn emit_sequence_start(&mut self) -> Result<()> {
+    fn emit_number(&mut self, tag: &str, number: &str) -> Result<()> {
+        let grouping = self.digit_grouping && tag == Tag::INT;
+        let plus = self.plus_sign && !number.starts_with('-') && number != ".nan";
+        let mut formatted = String::new();
+        let value = if grouping || plus {
+            if plus {
+                formatted.push('+');
+            }
+            if grouping {
+                push_grouped_digits(&mut formatted, number);
+            } else {
+                formatted.push_str(number);
+            }
+            &formatted
+        } else {
+            number
+        };
+        self.emit_scalar(Scalar {
+            tag: self.core_tag(tag),
+            value,
+            style: ScalarStyle::Plain,
+        })
+    }
+
+    fn core_tag(&self, tag: &str) -> Option<String> {
+        if self.explicit_core_tags {
+            Some(tag.to_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Tags an untagged collection that is known to be empty, if
+    /// [`EmptyStyle::Explicit`] is selected.
+    fn empty_tag(&self, tag: Option<String>, len: Option<usize>, core: &str) -> Option<String> {
+        if tag.is_none() && len == Some(0) && self.empty_style == EmptyStyle::Explicit {
+            Some(core.to_owned())
+        } else {
+            tag
+        }
+    }
+
+    fn emit_sequence_start(&mut self, len: Option<usize>) -> Result<()> {
+        self.key_pending = false;
         self.flush_mapping_start()?;
         self.value_start()?;
         let tag = self.take_tag();
-        self.emitter.emit(Event::SequenceStart(Sequence { tag }))?;
-        Ok(())
+        let tag = self.empty_tag(tag, len, Tag::SEQ);
+        let flow = mem::take(&mut self.flow);
+        self.emit_node(Event::SequenceStart(Sequence {
+            anchor: None,
+            tag,
+            flow,
+        }))
     }
 
     fn emit_sequence_end(&mut self) -> Result<()> {
-        self.emitter.emit(Event::SequenceEnd)?;
+        self.emit_node(Event::SequenceEnd)?;
         self.value_end()
     }
 
-    fn emit_mapping_start(&mut self) -> Result<()> {
+    fn emit_mapping_start(&mut self, len: Option<usize>) -> Result<()> {
+        self.key_pending = false;
         self.flush_mapping_start()?;
         self.value_start()?;
         let tag = self.take_tag();
-        self.emitter.emit(Event::MappingStart(Mapping { tag }))?;
-        Ok(())
+        let tag = self.empty_tag(tag, len, Tag::MAP);
+        let flow = mem::take(&mut self.flow);
+        self.mapping_keys.push(HashSet::new());
+        self.emit_node(Event::MappingStart(Mapping {
+            anchor: None,
+            tag,
+            flow,
+        }))
     }
 
     fn emit_mapping_end(&mut self) -> Result<()> {
-        self.emitter.emit(Event::MappingEnd)?;
+        self.mapping_keys.pop();
+        self.emit_node(Event::MappingEnd)?;
         self.value_end()
     }
 
+    /// Emits an event within a document, or holds it back until the end of
+    /// the document if keys are being sorted or repeats are being replaced by
+    /// aliases, or if the document is being captured by [`to_string_spaced`].
+    fn emit_node(&mut self, event: Event) -> Result<()> {
+        if self.sort_keys || self.anchor_dedup || self.capture {
+            self.document.push(Node::from(event));
+        } else {
+            self.emitter.emit(event)?;
+        }
+        Ok(())
+    }
+
+    /// Emits the held back events of a document, with the entries of each map
+    /// sorted, and with an anchor on the first of each repeated sequence or map
+    /// and an alias in place of the others.
+    fn emit_document(&mut self) -> Result<()> {
+        let mut nodes = mem::take(&mut self.document);
+        if self.sort_keys {
+            let mut sorted = Vec::with_capacity(nodes.len());
+            sort_entries(&mut nodes.into_iter(), &mut sorted);
+            nodes = sorted;
+        }
+
+        // The index one past the end of the collection starting at each index.
+        let mut ends = vec![0; nodes.len()];
+        let mut starts = Vec::new();
+        for (i, node) in nodes.iter().enumerate() {
+            match node {
+                Node::SequenceStart(..) | Node::MappingStart(..) => starts.push(i),
+                Node::SequenceEnd | Node::MappingEnd => ends[starts.pop().unwrap()] = i + 1,
+                Node::Scalar(..) => ends[i] = i + 1,
+            }
+        }
+        let subtree = |i: usize| &nodes[i..ends[i]];
+        let is_candidate = |i: usize| {
+            let is_start = matches!(nodes[i], Node::SequenceStart(..) | Node::MappingStart(..));
+            is_start && ends[i] - i > 2
+        };
+
+        let mut occurrences = HashMap::new();
+        if self.anchor_dedup {
+            for i in (0..nodes.len()).filter(|&i| is_candidate(i)) {
+                *occurrences.entry(subtree(i)).or_insert(0) += 1;
+            }
+        }
+
+        // Walk the document as it will be written, skipping the insides of
+        // aliased collections, to find the anchors that are referred to.
+        let mut anchors = HashMap::new();
+        let mut aliases = Vec::new();
+        let mut i = 0;
+        while i < nodes.len() {
+            if self.anchor_dedup && is_candidate(i) {
+                if let Some(&anchor) = anchors.get(subtree(i)) {
+                    aliases.push((i, anchor));
+                    i = ends[i];
+                    continue;
+                }
+                if occurrences[subtree(i)] > 1 {
+                    anchors.insert(subtree(i), i);
+                }
+            }
+            i += 1;
+        }
+        let referenced: BTreeSet<usize> = aliases.iter().map(|&(_, anchor)| anchor).collect();
+        let names: HashMap<usize, String> = referenced
+            .into_iter()
+            .enumerate()
+            .map(|(n, anchor)| (anchor, format!("id{:03}", n + 1)))
+            .collect();
+        let aliases: HashMap<usize, usize> = aliases.into_iter().collect();
+
+        let mut skip_until = 0;
+        for (i, node) in nodes.into_iter().enumerate() {
+            if i < skip_until {
+                continue;
+            }
+            let event = match (aliases.get(&i), node) {
+                (Some(anchor), _) => {
+                    skip_until = ends[i];
+                    Event::Alias(names[anchor].clone())
+                }
+                (None, Node::Scalar(tag, value, style)) => {
+                    let scalar = Scalar {
+                        tag,
+                        value: &value,
+                        style,
+                    };
+                    self.emitter.emit(Event::Scalar(scalar))?;
+                    continue;
+                }
+                (None, Node::SequenceStart(tag, flow)) => Event::SequenceStart(Sequence {
+                    anchor: names.get(&i).cloned(),
+                    tag,
+                    flow,
+                }),
+                (None, Node::SequenceEnd) => Event::SequenceEnd,
+                (None, Node::MappingStart(tag, flow)) => Event::MappingStart(Mapping {
+                    anchor: names.get(&i).cloned(),
+                    tag,
+                    flow,
+                }),
+                (None, Node::MappingEnd) => Event::MappingEnd,
+            };
+            self.emitter.emit(event)?;
+        }
+        Ok(())
+    }
+
+    fn serialize_mapping_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // An empty plain scalar is not allowed as a simple key; libyaml would
+        // quote it, which reads back as an empty string rather than null.
+        let null_style = self.null_style;
+        if null_style == NullStyle::Empty {
+            self.null_style = NullStyle::Tilde;
+        }
+        // If the key turns out to be a string, serialize_str applies the
+        // key case to it.
+        self.key_pending = true;
+        let result = key.serialize(&mut *self);
+        self.null_style = null_style;
+        result
+    }
+
+    /// Opens the single-entry mapping that holds an enum variant in
+    /// [`EnumStyle::Map`], leaving the serializer ready for the variant's data.
+    fn emit_variant_key(&mut self, variant: &'static str) -> Result<()> {
+        self.emit_mapping_start(Some(1))?;
+        ser::Serializer::serialize_str(&mut *self, variant)
+    }
+
     fn value_start(&mut self) -> Result<()> {
-        if self.depth == 0 {
+        if self.depth == 0 && !self.capture {
             self.emitter.emit(Event::DocumentStart)?;
         }
         self.depth += 1;
@@ -136,7 +1046,8 @@ where
 
     fn value_end(&mut self) -> Result<()> {
         self.depth -= 1;
-        if self.depth == 0 {
+        if self.depth == 0 && !self.capture {
+            self.emit_document()?;
             self.emitter.emit(Event::DocumentEnd)?;
         }
         Ok(())
@@ -145,8 +1056,11 @@ where
     fn take_tag(&mut self) -> Option<String> {
         let state = mem::replace(&mut self.state, State::NothingInParticular);
         if let State::FoundTag(mut tag) = state {
-            if !tag.starts_with('!') {
-                tag.insert(0, '!');
+            match tag.strip_prefix('!') {
+                // A `!!name` tag is shorthand for a tag in the YAML core schema.
+                Some(name) if !name.is_empty() => tag = format!("tag:yaml.org,2002:{}", name),
+                Some(_) => {}
+                None => tag.insert(0, '!'),
             }
             Some(tag)
         } else {
@@ -158,7 +1072,7 @@ where
     fn flush_mapping_start(&mut self) -> Result<()> {
         if let State::CheckForTag = self.state {
             self.state = State::NothingInParticular;
-            self.emit_mapping_start()?;
+            self.emit_mapping_start(Some(1))?;
         } else if let State::CheckForDuplicateTag = self.state {
             self.state = State::NothingInParticular;
         }
@@ -183,118 +1097,81 @@ where
 
     fn serialize_bool(self, v: bool) -> Result<()> {
         self.emit_scalar(Scalar {
-            tag: None,
-            value: if v { "true" } else { "false" },
+            tag: self.core_tag(Tag::BOOL),
+            value: match (self.bool_style, v) {
+                (BoolStyle::TrueFalse, true) => "true",
+                (BoolStyle::TrueFalse, false) => "false",
+                (BoolStyle::Capitalized, true) => "True",
+                (BoolStyle::Capitalized, false) => "False",
+                (BoolStyle::YesNo, true) => "yes",
+                (BoolStyle::YesNo, false) => "no",
+                (BoolStyle::OnOff, true) => "on",
+                (BoolStyle::OnOff, false) => "off",
+            },
             style: ScalarStyle::Plain,
         })
     }
 
     fn serialize_i8(self, v: i8) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_i16(self, v: i16) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_i128(self, v: i128) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_u8(self, v: u8) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_u16(self, v: u16) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_u32(self, v: u32) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_u128(self, v: u128) -> Result<()> {
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: itoa::Buffer::new().format(v),
-            style: ScalarStyle::Plain,
-        })
+        self.emit_number(Tag::INT, itoa::Buffer::new().format(v))
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         let mut buffer = ryu::Buffer::new();
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: match v.classify() {
-                num::FpCategory::Infinite if v.is_sign_positive() => ".inf",
-                num::FpCategory::Infinite => "-.inf",
-                num::FpCategory::Nan => ".nan",
-                _ => buffer.format_finite(v),
-            },
-            style: ScalarStyle::Plain,
-        })
+        let value = match v.classify() {
+            num::FpCategory::Infinite if v.is_sign_positive() => ".inf",
+            num::FpCategory::Infinite => "-.inf",
+            num::FpCategory::Nan => ".nan",
+            _ => buffer.format_finite(v),
+        };
+        self.emit_number(Tag::FLOAT, value)
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
         let mut buffer = ryu::Buffer::new();
-        self.emit_scalar(Scalar {
-            tag: None,
-            value: match v.classify() {
-                num::FpCategory::Infinite if v.is_sign_positive() => ".inf",
-                num::FpCategory::Infinite => "-.inf",
-                num::FpCategory::Nan => ".nan",
-                _ => buffer.format_finite(v),
-            },
-            style: ScalarStyle::Plain,
-        })
+        let value = match v.classify() {
+            num::FpCategory::Infinite if v.is_sign_positive() => ".inf",
+            num::FpCategory::Infinite => "-.inf",
+            num::FpCategory::Nan => ".nan",
+            _ => buffer.format_finite(v),
+        };
+        self.emit_number(Tag::FLOAT, value)
     }
 
     fn serialize_char(self, value: char) -> Result<()> {
@@ -306,6 +1183,23 @@ where
     }
 
     fn serialize_str(self, value: &str) -> Result<()> {
+        if mem::take(&mut self.key_pending) {
+            let key = self.key_case.convert(value);
+            // Different keys may convert to the same one, which would not
+            // read back. A mapping that may hold a tag is opened only by its
+            // first scalar, so open it first to check against its own keys.
+            if self.key_case != KeyCase::AsIs {
+                self.flush_mapping_start()?;
+                let keys = self.mapping_keys.last_mut();
+                if keys.is_some_and(|keys| !keys.insert(key.clone().into_owned())) {
+                    return Err(error::new(ErrorImpl::DuplicateKey(key.into_owned())));
+                }
+            }
+            if let Cow::Owned(key) = key {
+                return self.serialize_str(&key);
+            }
+        }
+
         struct InferScalarStyle;
 
         impl Visitor<'_> for InferScalarStyle {
@@ -352,16 +1246,27 @@ where
             }
         }
 
-        let style = if value.contains('\n') {
-            ScalarStyle::Literal
-        } else {
-            let result = crate::de::visit_untagged_scalar(
-                InferScalarStyle,
-                value,
-                None,
-                libyaml::parser::ScalarStyle::Plain,
-            );
-            result.unwrap_or(ScalarStyle::Any)
+        let style = match self.string_quoting {
+            QuoteStyle::Single => ScalarStyle::SingleQuoted,
+            QuoteStyle::Double => ScalarStyle::DoubleQuoted,
+            QuoteStyle::Auto if value.contains('\n') => ScalarStyle::Literal,
+            QuoteStyle::Auto | QuoteStyle::Plain => {
+                let parse_bool = if self.yaml_1_1_bools {
+                    crate::de::parse_bool_yaml_1_1
+                } else {
+                    crate::de::parse_bool
+                };
+                let result = crate::de::visit_untagged_scalar(
+                    InferScalarStyle,
+                    value,
+                    None,
+                    libyaml::parser::ScalarStyle::Plain,
+                    parse_bool,
+                );
+                // Where plain is not allowed, such as for strings containing
+                // `: ` or line breaks, libyaml falls back to quoting.
+                result.unwrap_or(ScalarStyle::Any)
+            }
         };
 
         self.emit_scalar(Scalar {
@@ -377,8 +1282,12 @@ where
 
     fn serialize_unit(self) -> Result<()> {
         self.emit_scalar(Scalar {
-            tag: None,
-            value: "null",
+            tag: self.core_tag(Tag::NULL),
+            value: match self.null_style {
+                NullStyle::Keyword => "null",
+                NullStyle::Tilde => "~",
+                NullStyle::Empty => "",
+            },
             style: ScalarStyle::Plain,
         })
     }
@@ -396,10 +1305,18 @@ where
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + ser::Serialize,
     {
+        if name == crate::with::flow::NAME {
+            // Applies to the next sequence or mapping started, which is the
+            // value itself unless the value is a scalar.
+            self.flow = true;
+            let result = value.serialize(&mut *self);
+            self.flow = false;
+            return result;
+        }
         value.serialize(self)
     }
 
@@ -413,6 +1330,11 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
+        if self.enum_style == EnumStyle::Map {
+            self.emit_variant_key(variant)?;
+            value.serialize(&mut *self)?;
+            return self.emit_mapping_end();
+        }
         if let State::FoundTag(_) = self.state {
             return Err(error::new(ErrorImpl::SerializeNestedEnum));
         }
@@ -431,22 +1353,22 @@ where
         value.serialize(self)
     }
 
-    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        self.emit_sequence_start()?;
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.emit_sequence_start(len)?;
         Ok(self)
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-        self.emit_sequence_start()?;
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.emit_sequence_start(Some(len))?;
         Ok(self)
     }
 
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        self.emit_sequence_start()?;
+        self.emit_sequence_start(Some(len))?;
         Ok(self)
     }
 
@@ -455,32 +1377,35 @@ where
         _enm: &'static str,
         _idx: u32,
         variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        if let State::FoundTag(_) = self.state {
+        if self.enum_style == EnumStyle::Map {
+            self.emit_variant_key(variant)?;
+        } else if let State::FoundTag(_) = self.state {
             return Err(error::new(ErrorImpl::SerializeNestedEnum));
+        } else {
+            self.state = State::FoundTag(variant.to_owned());
         }
-        self.state = State::FoundTag(variant.to_owned());
-        self.emit_sequence_start()?;
+        self.emit_sequence_start(Some(len))?;
         Ok(self)
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
         if len == Some(1) {
             self.state = if let State::FoundTag(_) = self.state {
-                self.emit_mapping_start()?;
+                self.emit_mapping_start(len)?;
                 State::CheckForDuplicateTag
             } else {
                 State::CheckForTag
             };
         } else {
-            self.emit_mapping_start()?;
+            self.emit_mapping_start(len)?;
         }
         Ok(self)
     }
 
-    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        self.emit_mapping_start()?;
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.emit_mapping_start(Some(len))?;
         Ok(self)
     }
 
@@ -489,13 +1414,16 @@ where
         _enm: &'static str,
         _idx: u32,
         variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        if let State::FoundTag(_) = self.state {
+        if self.enum_style == EnumStyle::Map {
+            self.emit_variant_key(variant)?;
+        } else if let State::FoundTag(_) = self.state {
             return Err(error::new(ErrorImpl::SerializeNestedEnum));
+        } else {
+            self.state = State::FoundTag(variant.to_owned());
         }
-        self.state = State::FoundTag(variant.to_owned());
-        self.emit_mapping_start()?;
+        self.emit_mapping_start(Some(len))?;
         Ok(self)
     }
 
@@ -507,6 +1435,7 @@ where
             match tagged::check_for_tag(value) {
                 MaybeTag::NotTag(string) => string,
                 MaybeTag::Tag(string) => {
+                    self.key_pending = false;
                     return if let State::CheckForDuplicateTag = self.state {
                         Err(error::new(ErrorImpl::SerializeNestedEnum))
                     } else {
@@ -595,7 +1524,11 @@ where
     }
 
     fn end(self) -> Result<()> {
-        self.emit_sequence_end()
+        self.emit_sequence_end()?;
+        if self.enum_style == EnumStyle::Map {
+            self.emit_mapping_end()?;
+        }
+        Ok(())
     }
 }
 
@@ -611,7 +1544,7 @@ where
         T: ?Sized + ser::Serialize,
     {
         self.flush_mapping_start()?;
-        key.serialize(&mut **self)
+        self.serialize_mapping_key(key)
     }
 
     fn serialize_value<T>(&mut self, value: &T) -> Result<()>
@@ -626,7 +1559,7 @@ where
         K: ?Sized + ser::Serialize,
         V: ?Sized + ser::Serialize,
     {
-        key.serialize(&mut **self)?;
+        self.serialize_mapping_key(key)?;
         let tagged = matches!(self.state, State::FoundTag(_));
         value.serialize(&mut **self)?;
         if tagged {
@@ -637,7 +1570,7 @@ where
 
     fn end(self) -> Result<()> {
         if let State::CheckForTag = self.state {
-            self.emit_mapping_start()?;
+            self.emit_mapping_start(None)?;
         }
         if !matches!(self.state, State::AlreadyTagged) {
             self.emit_mapping_end()?;
@@ -658,7 +1591,7 @@ where
     where
         V: ?Sized + ser::Serialize,
     {
-        self.serialize_str(key)?;
+        self.serialize_mapping_key(key)?;
         value.serialize(&mut **self)
     }
 
@@ -678,12 +1611,114 @@ where
     where
         V: ?Sized + ser::Serialize,
     {
-        self.serialize_str(field)?;
+        self.serialize_mapping_key(field)?;
         v.serialize(&mut **self)
     }
 
     fn end(self) -> Result<()> {
-        self.emit_mapping_end()
+        self.emit_mapping_end()?;
+        if self.enum_style == EnumStyle::Map {
+            self.emit_mapping_end()?;
+        }
+        Ok(())
+    }
+}
+
+// Moves one node, with everything inside it, from `nodes` to `sorted`,
+// reordering the entries of each map by key.
+fn sort_entries(nodes: &mut vec::IntoIter<Node>, sorted: &mut Vec<Node>) {
+    let node = nodes.next().unwrap();
+    let is_mapping = matches!(node, Node::MappingStart(..));
+    let is_sequence = matches!(node, Node::SequenceStart(..));
+    sorted.push(node);
+    if is_sequence {
+        while !matches!(nodes.as_slice().first(), Some(Node::SequenceEnd)) {
+            sort_entries(nodes, sorted);
+        }
+        sorted.extend(nodes.next());
+    } else if is_mapping {
+        let mut entries = Vec::new();
+        while !matches!(nodes.as_slice().first(), Some(Node::MappingEnd)) {
+            let mut key = Vec::new();
+            sort_entries(nodes, &mut key);
+            let mut value = Vec::new();
+            sort_entries(nodes, &mut value);
+            entries.push((read_back(&mut key.iter()), key, value));
+        }
+        entries.sort_by(|(a, ..), (b, ..)| mapping::total_cmp(a, b));
+        for (_, key, value) in entries {
+            sorted.extend(key);
+            sorted.extend(value);
+        }
+        sorted.extend(nodes.next());
+    }
+}
+
+// The value that a node, with everything inside it, reads back as, leaving
+// out tags. Only numbers, bools and null are written in plain style.
+fn read_back(nodes: &mut slice::Iter<Node>) -> Value {
+    match nodes.next().unwrap() {
+        Node::Scalar(_, value, ScalarStyle::Plain) => {
+            Value::deserialize(PlainScalar(value)).unwrap_or_else(|_| Value::String(value.clone()))
+        }
+        Node::Scalar(_, value, _) => Value::String(value.clone()),
+        Node::SequenceStart(..) => {
+            let mut sequence = Vec::new();
+            while !matches!(nodes.as_slice().first(), Some(Node::SequenceEnd)) {
+                sequence.push(read_back(nodes));
+            }
+            nodes.next();
+            Value::Sequence(sequence)
+        }
+        Node::MappingStart(..) => {
+            let mut mapping = crate::Mapping::new();
+            while !matches!(nodes.as_slice().first(), Some(Node::MappingEnd)) {
+                let key = read_back(nodes);
+                mapping.insert(key, read_back(nodes));
+            }
+            nodes.next();
+            Value::Mapping(mapping)
+        }
+        Node::SequenceEnd | Node::MappingEnd => unreachable!(),
+    }
+}
+
+// A plain scalar, resolved the way the deserializer resolves an untagged one.
+struct PlainScalar<'a>(&'a str);
+
+impl<'de> de::Deserializer<'de> for PlainScalar<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let style = libyaml::parser::ScalarStyle::Plain;
+        crate::de::visit_untagged_scalar(visitor, self.0, None, style, crate::de::parse_bool)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+// Appends a decimal integer with underscores between groups of three digits,
+// counted from the right.
+fn push_grouped_digits(out: &mut String, int: &str) {
+    let digits = match int.strip_prefix('-') {
+        Some(digits) => {
+            out.push('-');
+            digits
+        }
+        None => int,
+    };
+    for (i, digit) in digits.char_indices() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push('_');
+        }
+        out.push(digit);
     }
 }
 
@@ -700,19 +1735,133 @@ where
     value.serialize(&mut serializer)
 }
 
-/// Serialize the given data structure as a String of YAML.
+/// Serialize the given data structure as a byte vector of YAML.
+///
+/// The bytes are the same UTF-8 that [`to_string`] returns, without the
+/// conversion to `String`.
 ///
 /// Serialization can fail if `T`'s implementation of `Serialize` decides to
 /// return an error.
-pub fn to_string<T>(value: &T) -> Result<String>
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>>
 where
     T: ?Sized + ser::Serialize,
 {
     let mut vec = Vec::with_capacity(128);
     to_writer(&mut vec, value)?;
+    Ok(vec)
+}
+
+/// Serialize the given data structure as a String of YAML.
+///
+/// Serialization can fail if `T`'s implementation of `Serialize` decides to
+/// return an error.
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let vec = to_vec(value)?;
     String::from_utf8(vec).map_err(|error| error::new(ErrorImpl::FromUtf8(error)))
 }
 
+/// Serialize the given data structure as a String of YAML, with a blank line
+/// between the entries of a top-level mapping or sequence.
+///
+/// Nested structures are written the same as by [`to_string`], and the output
+/// deserializes to the same data.
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("a", vec![1, 2]);
+/// map.insert("b", vec![3]);
+///
+/// let yaml = serde_yaml::to_string_spaced(&map).unwrap();
+/// assert_eq!(yaml, "a:\n- 1\n- 2\n\nb:\n- 3\n");
+/// ```
+pub fn to_string_spaced<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut serializer = Serializer::new(io::sink());
+    serializer.capture = true;
+    value.serialize(&mut serializer)?;
+    let nodes = mem::take(&mut serializer.document);
+
+    // A scalar, an empty collection or a collection in flow style is written
+    // as is.
+    let is_mapping = match nodes.first() {
+        Some(Node::MappingStart(_, false)) if nodes.len() > 2 => true,
+        Some(Node::SequenceStart(_, false)) if nodes.len() > 2 => false,
+        _ => return emit_spaced_entry(&nodes),
+    };
+    let (start, end) = if is_mapping {
+        (Node::MappingStart(None, false), Node::MappingEnd)
+    } else {
+        (Node::SequenceStart(None, false), Node::SequenceEnd)
+    };
+
+    // Each entry of the top-level collection is emitted as the only entry of
+    // a collection of its own. libyaml writes it the same as it would in
+    // place, so the entries only need joining with blank lines. The first
+    // one keeps the collection's tag.
+    let mut yaml = String::new();
+    let mut i = 1;
+    while i + 1 < nodes.len() {
+        let mut len = subtree_len(&nodes[i..]);
+        if is_mapping {
+            len += subtree_len(&nodes[i + len..]);
+        }
+        let first = if i == 1 { &nodes[0] } else { &start };
+        let mut entry = vec![first];
+        entry.extend(&nodes[i..i + len]);
+        entry.push(&end);
+        // Skip the separator if the previous entry already ends in a blank
+        // line, which is the case for a block scalar that keeps its trailing
+        // line breaks. Adding another would change the scalar's value.
+        if i > 1 && !yaml.ends_with("\n\n") {
+            yaml.push('\n');
+        }
+        yaml += &emit_spaced_entry(entry)?;
+        i += len;
+    }
+    Ok(yaml)
+}
+
+// Writes a document consisting of the given nodes.
+fn emit_spaced_entry<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Result<String> {
+    let mut yaml = Vec::new();
+    let mut emitter = Emitter::new(Box::new(&mut yaml));
+    emitter.emit(Event::StreamStart)?;
+    emitter.emit(Event::DocumentStart)?;
+    for node in nodes {
+        emitter.emit(node.event())?;
+    }
+    // As for `to_string`, the stream is left unfinished, so that libyaml does
+    // not mark the end of a document that ends in a `|+` scalar with `...`.
+    emitter.emit(Event::DocumentEnd)?;
+    emitter.flush()?;
+    drop(emitter);
+    String::from_utf8(yaml).map_err(|error| error::new(ErrorImpl::FromUtf8(error)))
+}
+
+// The number of nodes in the node at the start of `nodes`, counting
+// everything inside it.
+fn subtree_len(nodes: &[Node]) -> usize {
+    let mut depth = 0;
+    for (i, node) in nodes.iter().enumerate() {
+        match node {
+            Node::SequenceStart(..) | Node::MappingStart(..) => depth += 1,
+            Node::SequenceEnd | Node::MappingEnd => depth -= 1,
+            Node::Scalar(..) => {}
+        }
+        if depth == 0 {
+            return i + 1;
+        }
+    }
+    nodes.len()
+}
+
 /// Serialize the given data structure as a String of YAML with prettify.
 #[cfg(feature = "pretty")]
 pub fn to_string_pretty<T>(value: &T) -> Result<String>
@@ -0,0 +1,144 @@
+use crate::value::tagged::{Tag, TaggedValue};
+use crate::value::Value;
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde::ser::{Serialize, Serializer};
+
+/// The tag of a base64 encoded `!!binary` scalar, as stored in a [`Tag`].
+pub(crate) const BINARY_TAG: &str = "!!binary";
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Binary data, written as a base64 encoded `!!binary` scalar.
+///
+/// ```
+/// use serde_yaml::value::Binary;
+///
+/// let yaml = serde_yaml::to_string(&Binary(b"hello".to_vec())).unwrap();
+/// assert_eq!(yaml, "!!binary aGVsbG8=\n");
+///
+/// let binary: Binary = serde_yaml::from_str(&yaml).unwrap();
+/// assert_eq!(binary.0, b"hello");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Binary(pub Vec<u8>);
+
+impl Value {
+    /// If the `Value` is a `!!binary` scalar, decodes its base64 content and
+    /// returns the bytes. Returns None otherwise, or if the content is not
+    /// valid base64.
+    ///
+    /// Whitespace and line breaks within the base64 content are ignored.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("!!binary |\n  aGVs\n  bG8=\n").unwrap();
+    /// assert_eq!(v.as_bytes(), Some(b"hello".to_vec()));
+    ///
+    /// let v: Value = serde_yaml::from_str("aGVsbG8=").unwrap();
+    /// assert_eq!(v.as_bytes(), None);
+    /// ```
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Value::Tagged(tagged) if tagged.tag == Tag::new(BINARY_TAG) => {
+                decode(tagged.value.as_str()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Binary> for Value {
+    fn from(binary: Binary) -> Self {
+        Value::Tagged(Box::new(TaggedValue {
+            tag: Tag::new(BINARY_TAG),
+            value: Value::String(encode(&binary.0)),
+        }))
+    }
+}
+
+impl Serialize for Binary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        TaggedValue {
+            tag: Tag::new(BINARY_TAG),
+            value: Value::String(encode(&self.0)),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Binary {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        match value.as_bytes() {
+            Some(bytes) => Ok(Binary(bytes)),
+            None => Err(D::Error::invalid_type(
+                value.unexpected(),
+                &"a base64 encoded !!binary scalar",
+            )),
+        }
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    let mut string = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let triple = (u32::from(chunk[0]) << 16) | (u32::from(b1) << 8) | u32::from(b2);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (triple >> (18 - 6 * i)) & 0x3f;
+                string.push(char::from(ALPHABET[index as usize]));
+            } else {
+                string.push('=');
+            }
+        }
+    }
+    string
+}
+
+pub(crate) fn decode(string: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(string.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+    for ch in string.bytes() {
+        if ch.is_ascii_whitespace() {
+            continue;
+        }
+        if ch == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return None;
+        }
+        let sextet = match ch {
+            b'A'..=b'Z' => ch - b'A',
+            b'a'..=b'z' => ch - b'a' + 26,
+            b'0'..=b'9' => ch - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    // A lone trailing character or nonzero leftover bits mean the input was
+    // truncated or is not canonical base64.
+    if bits >= 6 || acc != 0 || padding > 2 {
+        return None;
+    }
+    Some(bytes)
+}
@@ -1,10 +1,12 @@
+use crate::value::omap::{self, is_omap};
+use crate::value::set::is_set;
 use crate::value::tagged::{self, TagStringVisitor};
 use crate::value::TaggedValue;
 use crate::{number, Error, Mapping, Sequence, Value};
 use serde::de::value::{BorrowedStrDeserializer, StrDeserializer};
 use serde::de::{
-    self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error as _, Expected, MapAccess,
-    SeqAccess, Unexpected, VariantAccess, Visitor,
+    self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error as _, Expected,
+    IntoDeserializer, MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor,
 };
 use serde::forward_to_deserialize_any;
 use std::fmt;
@@ -162,6 +164,44 @@ where
     }
 }
 
+fn visit_set<'de, I, V>(members: I, visitor: V) -> Result<V::Value, Error>
+where
+    I: Iterator,
+    I::Item: IntoDeserializer<'de, Error>,
+    V: Visitor<'de>,
+{
+    let mut deserializer = de::value::SeqDeserializer::<I, Error>::new(members);
+    let seq = visitor.visit_seq(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(seq)
+}
+
+fn visit_omap<'de, I, K, T, V>(entries: I, visitor: V) -> Result<V::Value, Error>
+where
+    I: Iterator<Item = (K, T)>,
+    K: IntoDeserializer<'de, Error>,
+    T: IntoDeserializer<'de, Error>,
+    V: Visitor<'de>,
+{
+    let mut deserializer = de::value::MapDeserializer::<'de, I, Error>::new(entries);
+    let seq = visitor.visit_seq(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(seq)
+}
+
+fn visit_omap_map<'de, I, K, T, V>(entries: I, visitor: V) -> Result<V::Value, Error>
+where
+    I: Iterator<Item = (K, T)>,
+    K: IntoDeserializer<'de, Error>,
+    T: IntoDeserializer<'de, Error>,
+    V: Visitor<'de>,
+{
+    let mut deserializer = de::value::MapDeserializer::<'de, I, Error>::new(entries);
+    let map = visitor.visit_map(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(map)
+}
+
 fn visit_mapping<'de, V>(mapping: Mapping, visitor: V) -> Result<V::Value, Error>
 where
     V: Visitor<'de>,
@@ -308,7 +348,11 @@ impl<'de> Deserializer<'de> for Value {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_string(visitor)
+        match self.untag() {
+            Value::String(v) => crate::de::visit_char(visitor, &v),
+            Value::Number(n) => crate::de::visit_char(visitor, &n.to_string()),
+            other => Err(other.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -339,6 +383,9 @@ impl<'de> Deserializer<'de> for Value {
     where
         V: Visitor<'de>,
     {
+        if let Some(bytes) = self.as_bytes() {
+            return visitor.visit_byte_buf(bytes);
+        }
         match self.untag() {
             Value::String(v) => visitor.visit_string(v),
             Value::Sequence(v) => visit_sequence(v, visitor),
@@ -388,10 +435,20 @@ impl<'de> Deserializer<'de> for Value {
     where
         V: Visitor<'de>,
     {
-        match self.untag() {
-            Value::Sequence(v) => visit_sequence(v, visitor),
-            Value::Null => visit_sequence(Sequence::new(), visitor),
-            other => Err(other.invalid_type(&visitor)),
+        match self {
+            Value::Tagged(tagged) if is_set(&tagged) => match tagged.value {
+                Value::Mapping(mapping) => visit_set(mapping.into_keys(), visitor),
+                _ => unreachable!(),
+            },
+            Value::Tagged(tagged) if is_omap(&tagged) => match tagged.value {
+                Value::Sequence(entries) => visit_omap(omap::into_entries(entries), visitor),
+                _ => unreachable!(),
+            },
+            other => match other.untag() {
+                Value::Sequence(v) => visit_sequence(v, visitor),
+                Value::Null => visit_sequence(Sequence::new(), visitor),
+                other => Err(other.invalid_type(&visitor)),
+            },
         }
     }
 
@@ -418,10 +475,16 @@ impl<'de> Deserializer<'de> for Value {
     where
         V: Visitor<'de>,
     {
-        match self.untag() {
-            Value::Mapping(v) => visit_mapping(v, visitor),
-            Value::Null => visit_mapping(Mapping::new(), visitor),
-            other => Err(other.invalid_type(&visitor)),
+        match self {
+            Value::Tagged(tagged) if is_omap(&tagged) => match tagged.value {
+                Value::Sequence(entries) => visit_omap_map(omap::into_entries(entries), visitor),
+                _ => unreachable!(),
+            },
+            other => match other.untag() {
+                Value::Mapping(v) => visit_mapping(v, visitor),
+                Value::Null => visit_mapping(Mapping::new(), visitor),
+                other => Err(other.invalid_type(&visitor)),
+            },
         }
     }
 
@@ -462,6 +525,21 @@ impl<'de> Deserializer<'de> for Value {
                 },
                 value: None,
             },
+            Value::Mapping(mapping) if mapping.len() == 1 => match mapping.into_iter().next() {
+                Some((Value::String(variant), value)) => EnumDeserializer {
+                    tag: {
+                        tag = variant;
+                        &tag
+                    },
+                    value: Some(value),
+                },
+                _ => {
+                    return Err(Error::invalid_type(
+                        Unexpected::Map,
+                        &"a Value::Tagged enum",
+                    ));
+                }
+            },
             other => {
                 return Err(Error::invalid_type(
                     other.unexpected(),
@@ -825,7 +903,11 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_string(visitor)
+        match self.untag_ref() {
+            Value::String(v) => crate::de::visit_char(visitor, v),
+            Value::Number(n) => crate::de::visit_char(visitor, &n.to_string()),
+            other => Err(other.invalid_type(&visitor)),
+        }
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -849,6 +931,9 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
+        if let Some(bytes) = self.as_bytes() {
+            return visitor.visit_byte_buf(bytes);
+        }
         match self.untag_ref() {
             Value::String(v) => visitor.visit_borrowed_str(v),
             Value::Sequence(v) => visit_sequence_ref(v, visitor),
@@ -906,6 +991,12 @@ impl<'de> Deserializer<'de> for &'de Value {
         V: Visitor<'de>,
     {
         static EMPTY: Sequence = Sequence::new();
+        if let Some(members) = self.as_set() {
+            return visit_set(members.into_iter(), visitor);
+        }
+        if let Some(entries) = self.as_omap() {
+            return visit_omap(entries.into_iter(), visitor);
+        }
         match self.untag_ref() {
             Value::Sequence(v) => visit_sequence_ref(v, visitor),
             Value::Null => visit_sequence_ref(&EMPTY, visitor),
@@ -936,6 +1027,9 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
+        if let Some(entries) = self.as_omap() {
+            return visit_omap_map(entries.into_iter(), visitor);
+        }
         match self.untag_ref() {
             Value::Mapping(v) => visit_mapping_ref(v, visitor),
             Value::Null => visitor.visit_map(&mut MapRefDeserializer {
@@ -976,6 +1070,18 @@ impl<'de> Deserializer<'de> for &'de Value {
                 tag: variant,
                 value: None,
             },
+            Value::Mapping(mapping) if mapping.len() == 1 => match mapping.iter().next() {
+                Some((Value::String(variant), value)) => EnumRefDeserializer {
+                    tag: variant,
+                    value: Some(value),
+                },
+                _ => {
+                    return Err(Error::invalid_type(
+                        Unexpected::Map,
+                        &"a Value::Tagged enum",
+                    ));
+                }
+            },
             other => {
                 return Err(Error::invalid_type(
                     other.unexpected(),
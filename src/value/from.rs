@@ -1,4 +1,5 @@
-use crate::{Mapping, Value};
+use crate::value::{type_error, Sequence};
+use crate::{Error, Mapping, Number, Value};
 
 // Implement a bunch of conversion to make it easier to create YAML values
 // on the fly.
@@ -113,6 +114,22 @@ impl From<Mapping> for Value {
     }
 }
 
+impl From<Number> for Value {
+    /// Convert `Number` to `Value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::{Number, Value};
+    ///
+    /// let n = Number::from(7);
+    /// let x: Value = n.into();
+    /// ```
+    fn from(f: Number) -> Self {
+        Value::Number(f)
+    }
+}
+
 impl<T: Into<Value>> From<Vec<T>> for Value {
     /// Convert a `Vec` to `Value`
     ///
@@ -176,3 +193,183 @@ impl<T: Into<Value>> FromIterator<T> for Value {
         Value::Sequence(vec)
     }
 }
+
+// And the conversions back, which drop any tags and fail if the value is of
+// another kind.
+
+impl TryFrom<Value> for Mapping {
+    type Error = Error;
+
+    /// Convert a `Value` holding a mapping to `Mapping`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::{Mapping, Value};
+    ///
+    /// let v: Value = serde_yaml::from_str("a: 1").unwrap();
+    /// let m = Mapping::try_from(v).unwrap();
+    /// assert_eq!(m["a"], 1);
+    ///
+    /// let error = Mapping::try_from(Value::from("a")).unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a mapping, found string");
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.untag() {
+            Value::Mapping(mapping) => Ok(mapping),
+            other => Err(type_error("mapping", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Sequence {
+    type Error = Error;
+
+    /// Convert a `Value` holding a sequence to `Sequence`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::{Sequence, Value};
+    ///
+    /// let v: Value = serde_yaml::from_str("[1, 2]").unwrap();
+    /// let s = Sequence::try_from(v).unwrap();
+    /// assert_eq!(s, [1, 2]);
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.untag() {
+            Value::Sequence(sequence) => Ok(sequence),
+            other => Err(type_error("sequence", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Number {
+    type Error = Error;
+
+    /// Convert a `Value` holding a number to `Number`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::{Number, Value};
+    ///
+    /// let n = Number::try_from(Value::from(1.5)).unwrap();
+    /// assert_eq!(n.as_f64(), Some(1.5));
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.untag() {
+            Value::Number(number) => Ok(number),
+            other => Err(type_error("number", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    /// Convert a `Value` holding a string to `String`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// let s = String::try_from(Value::from("lorem")).unwrap();
+    /// assert_eq!(s, "lorem");
+    ///
+    /// let error = String::try_from(Value::from(1)).unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a string, found number");
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.untag() {
+            Value::String(string) => Ok(string),
+            other => Err(type_error("string", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    /// Convert a `Value` holding a boolean to `bool`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// let v: Value = serde_yaml::from_str("!Flag true").unwrap();
+    /// assert!(bool::try_from(v).unwrap());
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        let value = value.untag();
+        value.as_bool().ok_or_else(|| type_error("bool", &value))
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = Error;
+
+    /// Convert a `Value` holding an integer that fits in `i64` to `i64`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// assert_eq!(i64::try_from(Value::from(-3)).unwrap(), -3);
+    ///
+    /// let error = i64::try_from(Value::from(1.5)).unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a 64-bit signed integer, found number");
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        let value = value.untag();
+        value
+            .as_i64()
+            .ok_or_else(|| type_error("64-bit signed integer", &value))
+    }
+}
+
+impl TryFrom<Value> for u64 {
+    type Error = Error;
+
+    /// Convert a `Value` holding a non-negative integer that fits in `u64` to
+    /// `u64`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// assert_eq!(u64::try_from(Value::from(3)).unwrap(), 3);
+    /// assert!(u64::try_from(Value::from(-3)).is_err());
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        let value = value.untag();
+        value
+            .as_u64()
+            .ok_or_else(|| type_error("64-bit unsigned integer", &value))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    /// Convert a `Value` holding any number to `f64`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// assert_eq!(f64::try_from(Value::from(2)).unwrap(), 2.0);
+    ///
+    /// let error = f64::try_from(Value::from("2")).unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a number, found string");
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Error> {
+        let value = value.untag();
+        value.as_f64().ok_or_else(|| type_error("number", &value))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::mapping::Entry;
 use crate::{mapping, private, Mapping, Value};
-use std::fmt::{self, Debug};
+use std::fmt::Debug;
 use std::ops;
 
 /// A type that can be used to index into a `serde_yaml::Value`. See the `get`
@@ -57,7 +57,7 @@ impl Index for usize {
                     return map.entry(n).or_insert(Value::Null);
                 }
                 Value::Tagged(tagged) => v = &mut tagged.value,
-                _ => panic!("cannot access index {} of YAML {}", self, Type(v)),
+                _ => panic!("cannot access index {} of YAML {}", self, v.type_name()),
             }
         }
     }
@@ -104,7 +104,7 @@ where
                 return map.entry(index.to_owned().into()).or_insert(Value::Null);
             }
             Value::Tagged(tagged) => v = &mut tagged.value,
-            _ => panic!("cannot access key {:?} in YAML {}", index, Type(v)),
+            _ => panic!("cannot access key {:?} in YAML {}", index, v.type_name()),
         }
     }
 }
@@ -160,23 +160,6 @@ where
     }
 }
 
-/// Used in panic messages.
-struct Type<'a>(&'a Value);
-
-impl fmt::Display for Type<'_> {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        match self.0 {
-            Value::Null => formatter.write_str("null"),
-            Value::Bool(_) => formatter.write_str("boolean"),
-            Value::Number(_) => formatter.write_str("number"),
-            Value::String(_) => formatter.write_str("string"),
-            Value::Sequence(_) => formatter.write_str("sequence"),
-            Value::Mapping(_) => formatter.write_str("mapping"),
-            Value::Tagged(_) => unreachable!(),
-        }
-    }
-}
-
 // The usual semantics of Index is to panic on invalid indexing.
 //
 // That said, the usual semantics are for things like `Vec` and `BTreeMap` which
@@ -1,20 +1,28 @@
 //! The Value enum, a loosely typed way of representing any valid YAML value.
 
+pub(crate) mod binary;
 mod de;
 mod debug;
 mod from;
 mod index;
+pub(crate) mod omap;
 mod partial_eq;
+mod schema;
 mod ser;
+pub(crate) mod set;
 pub(crate) mod tagged;
 
 use crate::error::{self, Error, ErrorImpl};
 use serde::de::{Deserialize, DeserializeOwned, IntoDeserializer};
 use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::iter;
 use std::mem;
 
+pub use self::binary::Binary;
 pub use self::index::Index;
+pub use self::schema::{Schema, ValidationError, ValueKind};
 pub use self::ser::Serializer;
 pub use self::tagged::{Tag, TaggedValue};
 #[doc(inline)]
@@ -78,6 +86,19 @@ impl Default for Value {
     }
 }
 
+/// Where [`Value::apply_merge_with`] places the keys merged in from `<<`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MergeOrder {
+    /// After the mapping's own keys, in the order of the merged mappings.
+    /// This is what [`Value::apply_merge`] does.
+    #[default]
+    Append,
+    /// Before the mapping's own keys, in the order of the merged mappings. A
+    /// key that the mapping overrides keeps its merged position but takes the
+    /// mapping's value.
+    Prepend,
+}
+
 /// A YAML sequence in which the elements are `serde_yaml::Value`.
 pub type Sequence = Vec<Value>;
 
@@ -87,6 +108,11 @@ pub type Sequence = Vec<Value>;
 /// This conversion can fail if `T`'s implementation of `Serialize` decides to
 /// return an error.
 ///
+/// A `Value` passed in is rebuilt piece by piece like any other input, and
+/// compares equal to the original. Without specialization there is no way for
+/// a generic `T` to skip that, so prefer [`Clone::clone`] when the input is
+/// statically known to be a `Value`.
+///
 /// ```
 /// # use serde_yaml::Value;
 /// let val = serde_yaml::to_value("s").unwrap();
@@ -122,6 +148,26 @@ where
     Deserialize::deserialize(value)
 }
 
+/// Interpret a borrowed `serde_yaml::Value` as an instance of type `T`.
+///
+/// Unlike [`from_value`], the `Value` is left intact, so several target types
+/// can be tried against the same tree without cloning it. Strings in the result
+/// may borrow from the `Value`.
+///
+/// ```
+/// # use serde_yaml::Value;
+/// let val: Value = serde_yaml::from_str("[1, 2]").unwrap();
+/// assert!(serde_yaml::from_value_ref::<String>(&val).is_err());
+/// let v: Vec<u8> = serde_yaml::from_value_ref(&val).unwrap();
+/// assert_eq!(v, [1, 2]);
+/// ```
+pub fn from_value_ref<'de, T>(value: &'de Value) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    Deserialize::deserialize(value)
+}
+
 impl Value {
     /// Index into a YAML sequence or map. A string index can be used to access
     /// a value in a map, and a usize index can be used to access an element of
@@ -189,6 +235,276 @@ impl Value {
         index.index_into_mut(self)
     }
 
+    /// Returns mutable references to the values of several string keys of a
+    /// YAML map at once.
+    ///
+    /// Returns `None` if `self` is not a map, if any key is missing, or if a
+    /// key is given more than once.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut config: Value = serde_yaml::from_str("{a: 1, b: 2, c: 3}").unwrap();
+    ///
+    /// let [a, b] = config.get_many_mut(["a", "b"]).unwrap();
+    /// std::mem::swap(a, b);
+    /// assert_eq!(config, serde_yaml::from_str::<Value>("{a: 2, b: 1, c: 3}").unwrap());
+    ///
+    /// assert!(config.get_many_mut(["a", "a"]).is_none());
+    /// assert!(config.get_many_mut(["a", "z"]).is_none());
+    /// ```
+    pub fn get_many_mut<const N: usize>(&mut self, keys: [&str; N]) -> Option<[&mut Value; N]> {
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].contains(key) {
+                return None;
+            }
+        }
+        let mut values: [Option<&mut Value>; N] = std::array::from_fn(|_| None);
+        for (k, v) in self.as_mapping_mut()?.iter_mut() {
+            let Value::String(k) = k else {
+                continue;
+            };
+            if let Some(i) = keys.iter().position(|key| k == key) {
+                values[i] = Some(v);
+            }
+        }
+        if values.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(values.map(Option::unwrap))
+    }
+
+    /// Looks up a string key in a YAML map ignoring case, returning the value
+    /// of the first match in insertion order. Returns `None` if `self` is not
+    /// a map or no string key matches.
+    ///
+    /// Like [`Mapping::get_ci`], this is O(n) in the size of the map and is
+    /// meant for small config maps, not as a replacement for indexing.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let object: Value = serde_yaml::from_str("{ Log_Level: debug }").unwrap();
+    /// assert_eq!(object.get_ci("LOG_LEVEL").unwrap(), "debug");
+    /// ```
+    pub fn get_ci(&self, key: &str) -> Option<&Value> {
+        self.as_mapping()?.get_ci(key)
+    }
+
+    /// Returns true if `self` is a YAML map containing the given key. Returns
+    /// false otherwise, including when `self` is not a map.
+    ///
+    /// ```
+    /// # fn main() -> serde_yaml::Result<()> {
+    /// use serde_yaml::Value;
+    ///
+    /// let object: Value = serde_yaml::from_str("{ A: 65, B: 66 }")?;
+    /// assert!(object.contains_key("A"));
+    /// assert!(!object.contains_key("C"));
+    ///
+    /// let sequence: Value = serde_yaml::from_str("[A, B]")?;
+    /// assert!(!sequence.contains_key(0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_key<I: Index>(&self, key: I) -> bool {
+        self.is_mapping() && key.index_into(self).is_some()
+    }
+
+    /// Returns true if `self` is a YAML sequence containing an element equal
+    /// to the given value. Returns false otherwise, including when `self` is
+    /// not a sequence.
+    ///
+    /// ```
+    /// # fn main() -> serde_yaml::Result<()> {
+    /// use serde_yaml::Value;
+    ///
+    /// let sequence: Value = serde_yaml::from_str("[A, B]")?;
+    /// assert!(sequence.contains(&Value::String("A".into())));
+    /// assert!(!sequence.contains(&Value::String("C".into())));
+    ///
+    /// let object: Value = serde_yaml::from_str("{ A: 65 }")?;
+    /// assert!(!object.contains(&Value::String("A".into())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains(&self, value: &Value) -> bool {
+        match self.as_sequence() {
+            Some(seq) => seq.contains(value),
+            None => false,
+        }
+    }
+
+    /// Returns the kind of this `Value` as a lowercase name, for use in error
+    /// messages: one of `"null"`, `"bool"`, `"number"`, `"string"`,
+    /// `"sequence"`, `"mapping"` or `"tagged"`.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("[1, 2]").unwrap();
+    /// assert_eq!(v.type_name(), "sequence");
+    ///
+    /// let v: Value = serde_yaml::from_str("!Thing 1").unwrap();
+    /// assert_eq!(v.type_name(), "tagged");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Sequence(_) => "sequence",
+            Value::Mapping(_) => "mapping",
+            Value::Tagged(_) => "tagged",
+        }
+    }
+
+    /// Returns true if the `Value` has no content: it is `Null`, an empty
+    /// string, an empty sequence or an empty mapping. Booleans and numbers,
+    /// including `false` and `0`, are never empty. A tagged value is empty if
+    /// the value inside the tag is.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("{a: ~, b: '', c: [], d: 0}").unwrap();
+    /// assert!(v["a"].is_empty());
+    /// assert!(v["b"].is_empty());
+    /// assert!(v["c"].is_empty());
+    /// assert!(!v["d"].is_empty());
+    /// assert!(!v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Null => true,
+            Value::Bool(_) | Value::Number(_) => false,
+            Value::String(string) => string.is_empty(),
+            Value::Sequence(sequence) => sequence.is_empty(),
+            Value::Mapping(mapping) => mapping.is_empty(),
+            Value::Tagged(tagged) => tagged.value.is_empty(),
+        }
+    }
+
+    /// Hashes the shape of the `Value` but not its scalar contents, so that
+    /// two values with the same structure hash the same.
+    ///
+    /// The shape is made of the kind of every node, the length of every
+    /// sequence, the keys of every mapping and the tags of tagged values. The
+    /// hash of a mapping does not depend on the order of its entries. Keys are
+    /// hashed in full, while the scalars in values only contribute their kind:
+    /// `1` and `2.5` are both numbers, but `"1"` is a string.
+    ///
+    /// The hash is stable within one build of a program but is not meant to be
+    /// persisted.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let a: Value = serde_yaml::from_str("{host: a, port: 80, tags: [x]}").unwrap();
+    /// let b: Value = serde_yaml::from_str("{port: 443, host: b, tags: [y]}").unwrap();
+    /// let c: Value = serde_yaml::from_str("{host: a, port: '80', tags: [x]}").unwrap();
+    ///
+    /// assert_eq!(a.structure_hash(), b.structure_hash());
+    /// assert_ne!(a.structure_hash(), c.structure_hash());
+    /// ```
+    pub fn structure_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_structure(self, &mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a 64-bit hash of this value computed with a fixed algorithm,
+    /// suitable for persisting, for example as the key of an on-disk cache.
+    /// Values that are equal under `==` hash the same, so the order of the
+    /// entries of a mapping does not matter.
+    ///
+    /// Unlike the `Hash` impl, which is meant for in-memory collections, the
+    /// result is the same on every platform and will not change in any
+    /// future release. It is the 64-bit FNV-1a hash of the following byte
+    /// encoding, where lengths and counts are little-endian `u64`:
+    ///
+    /// - null: `0x00`
+    /// - bool: `0x01` followed by `0x00` for false or `0x01` for true
+    /// - integer: `0x02` followed by the value as a little-endian `i128`
+    /// - float: `0x03` followed by the little-endian bits of the `f64`, with
+    ///   `-0.0` encoded as `0.0` and every NaN as `0x7FF8000000000000`
+    /// - string: `0x04`, the length in bytes, then the UTF-8 bytes
+    /// - sequence: `0x05`, the number of elements, then each element
+    /// - mapping: `0x06`, the number of entries, then the little-endian `u64`
+    ///   wrapping sum over the entries of the FNV-1a hash of the encoding of
+    ///   the key followed by that of the value
+    /// - tagged: `0x07`, the length of the tag without its leading `!`, the
+    ///   bytes of the tag, then the encoding of the value
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let a: Value = serde_yaml::from_str("{host: a, port: 80}").unwrap();
+    /// let b: Value = serde_yaml::from_str("{port: 80, host: a}").unwrap();
+    /// let c: Value = serde_yaml::from_str("{host: a, port: '80'}").unwrap();
+    ///
+    /// assert_eq!(a.stable_hash(), b.stable_hash());
+    /// assert_ne!(a.stable_hash(), c.stable_hash());
+    /// assert_eq!(Value::Null.stable_hash(), 0xaf63bd4c8601b7df);
+    /// ```
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hash_stable(self, &mut hasher);
+        hasher.0
+    }
+
+    /// Compares two values like `==`, except that sequences are compared as
+    /// multisets: two sequences are equal if their elements can be paired up
+    /// so that each pair is equal under this same comparison, whatever their
+    /// order.
+    ///
+    /// The rest follows `==`. Scalars are compared as usual, tags must match
+    /// exactly, and mappings must have the same keys, in any order, with
+    /// values equal under this comparison. Mapping keys themselves are
+    /// compared with `==`, so a key that is a sequence only matches a key with
+    /// its elements in the same order.
+    ///
+    /// Comparing two sequences of length `n` takes `O(n²)` element
+    /// comparisons.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let a: Value = serde_yaml::from_str("{hosts: [a, b, a], port: 80}").unwrap();
+    /// let b: Value = serde_yaml::from_str("{port: 80, hosts: [a, a, b]}").unwrap();
+    /// let c: Value = serde_yaml::from_str("{port: 80, hosts: [a, b, b]}").unwrap();
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_unordered(&b));
+    /// assert!(!a.eq_unordered(&c));
+    /// ```
+    pub fn eq_unordered(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Sequence(a), Value::Sequence(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut matched = vec![false; b.len()];
+                a.iter().all(|x| {
+                    let found = b
+                        .iter()
+                        .zip(&matched)
+                        .position(|(y, &used)| !used && x.eq_unordered(y));
+                    match found {
+                        Some(i) => {
+                            matched[i] = true;
+                            true
+                        }
+                        None => false,
+                    }
+                })
+            }
+            (Value::Mapping(a), Value::Mapping(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(k, v)| b.get(k).is_some_and(|w| v.eq_unordered(w)))
+            }
+            (Value::Tagged(a), Value::Tagged(b)) => {
+                a.tag == b.tag && a.value.eq_unordered(&b.value)
+            }
+            (a, b) => a == b,
+        }
+    }
+
     /// Returns true if the `Value` is a Null. Returns false otherwise.
     ///
     /// For any Value on which `is_null` returns true, `as_null` is guaranteed
@@ -274,6 +590,24 @@ impl Value {
         }
     }
 
+    /// If the `Value` is a Boolean, returns the associated bool. Returns
+    /// `default` otherwise.
+    ///
+    /// Together with indexing, which gives `Null` for a missing key, this reads
+    /// an optional setting in one step.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("{verbose: true, quiet: 1}").unwrap();
+    ///
+    /// assert!(v["verbose"].as_bool_or(false));
+    /// assert!(!v["quiet"].as_bool_or(false));
+    /// assert!(v["missing"].as_bool_or(true));
+    /// ```
+    pub fn as_bool_or(&self, default: bool) -> bool {
+        self.as_bool().unwrap_or(default)
+    }
+
     /// Returns true if the `Value` is a Number. Returns false otherwise.
     ///
     /// ```
@@ -336,6 +670,21 @@ impl Value {
         }
     }
 
+    /// If the `Value` is an integer representable as i64, returns it. Returns
+    /// `default` otherwise.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("{port: 8080, ratio: 0.5}").unwrap();
+    ///
+    /// assert_eq!(v["port"].as_i64_or(80), 8080);
+    /// assert_eq!(v["ratio"].as_i64_or(1), 1);
+    /// assert_eq!(v["missing"].as_i64_or(3), 3);
+    /// ```
+    pub fn as_i64_or(&self, default: i64) -> i64 {
+        self.as_i64().unwrap_or(default)
+    }
+
     /// Returns true if the `Value` is an integer between `u64::MIN` and
     /// `u64::MAX`.
     ///
@@ -466,6 +815,45 @@ impl Value {
         }
     }
 
+    /// If the `Value` is a String, returns the associated str. Returns
+    /// `default` otherwise.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("{host: example.com, port: 80}").unwrap();
+    ///
+    /// assert_eq!(v["host"].as_str_or("localhost"), "example.com");
+    /// assert_eq!(v["port"].as_str_or("80"), "80");
+    /// assert_eq!(v["missing"].as_str_or("localhost"), "localhost");
+    /// ```
+    pub fn as_str_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.as_str().unwrap_or(default)
+    }
+
+    /// If the `Value` is a String, returns the String, dropping any tags.
+    /// Returns the original value unchanged as the error otherwise.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("!Tag 'lorem ipsum'").unwrap();
+    /// assert_eq!(v.into_string(), Ok("lorem ipsum".to_owned()));
+    /// ```
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("false").unwrap();
+    /// assert_eq!(v.into_string(), Err(Value::Bool(false)));
+    /// ```
+    pub fn into_string(self) -> Result<String, Value> {
+        if !self.is_string() {
+            return Err(self);
+        }
+        match self.untag() {
+            Value::String(s) => Ok(s),
+            _ => unreachable!(),
+        }
+    }
+
     /// Returns true if the `Value` is a sequence. Returns false otherwise.
     ///
     /// ```
@@ -527,6 +915,56 @@ impl Value {
         }
     }
 
+    /// If the `Value` is a sequence, returns the sequence, dropping any tags.
+    /// Returns the original value unchanged as the error otherwise.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("[1, 2]").unwrap();
+    /// assert_eq!(v.into_sequence().unwrap(), [1, 2]);
+    /// ```
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("!Tag {a: 1}").unwrap();
+    /// assert!(v.into_sequence().unwrap_err().is_mapping());
+    /// ```
+    pub fn into_sequence(self) -> Result<Sequence, Value> {
+        if !self.is_sequence() {
+            return Err(self);
+        }
+        match self.untag() {
+            Value::Sequence(seq) => Ok(seq),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the sequence in this `Value`, first
+    /// replacing `Null` with an empty sequence. Tags are looked through, like
+    /// in [`as_sequence_mut`](Self::as_sequence_mut). Returns an error if the
+    /// `Value` is neither a sequence nor null.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut v = Value::Null;
+    /// v.ensure_sequence().unwrap().push(Value::from(1));
+    /// assert_eq!(v, serde_yaml::from_str::<Value>("[1]").unwrap());
+    ///
+    /// let mut v = Value::from("text");
+    /// let error = v.ensure_sequence().unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a sequence, found string");
+    /// ```
+    pub fn ensure_sequence(&mut self) -> Result<&mut Sequence, Error> {
+        let value = self.untag_mut();
+        if let Value::Null = value {
+            *value = Value::Sequence(Sequence::new());
+        }
+        match value {
+            Value::Sequence(seq) => Ok(seq),
+            other => Err(type_error("sequence", other)),
+        }
+    }
+
     /// Returns true if the `Value` is a mapping. Returns false otherwise.
     ///
     /// ```
@@ -597,6 +1035,136 @@ impl Value {
         }
     }
 
+    /// If the `Value` is a mapping, returns the mapping, dropping any tags.
+    /// Returns the original value unchanged as the error otherwise.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("a: 42").unwrap();
+    /// let m = v.into_mapping().unwrap();
+    /// assert_eq!(m["a"], 42);
+    /// ```
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("false").unwrap();
+    /// assert_eq!(v.into_mapping(), Err(Value::Bool(false)));
+    /// ```
+    pub fn into_mapping(self) -> Result<Mapping, Value> {
+        if !self.is_mapping() {
+            return Err(self);
+        }
+        match self.untag() {
+            Value::Mapping(map) => Ok(map),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the mapping in this `Value`, first
+    /// replacing `Null` with an empty mapping. Tags are looked through, like
+    /// in [`as_mapping_mut`](Self::as_mapping_mut). Returns an error if the
+    /// `Value` is neither a mapping nor null.
+    ///
+    /// Unlike indexing with [`IndexMut`](std::ops::IndexMut), this never
+    /// panics.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut v = Value::Null;
+    /// v.ensure_mapping().unwrap().insert("port".into(), 80.into());
+    /// assert_eq!(v["port"], 80);
+    ///
+    /// let mut v = Value::from(false);
+    /// let error = v.ensure_mapping().unwrap_err();
+    /// assert_eq!(error.to_string(), "expected a mapping, found bool");
+    /// ```
+    pub fn ensure_mapping(&mut self) -> Result<&mut Mapping, Error> {
+        let value = self.untag_mut();
+        if let Value::Null = value {
+            *value = Value::Mapping(Mapping::new());
+        }
+        match value {
+            Value::Mapping(map) => Ok(map),
+            other => Err(type_error("mapping", other)),
+        }
+    }
+
+    /// Returns a mutable reference to the value of `key` in this mapping,
+    /// inserting `Null` if the key is missing. Like indexing with
+    /// [`IndexMut`](std::ops::IndexMut), `Null` is first replaced with an
+    /// empty mapping and tags are looked through, but a `Value` of any other
+    /// type returns `None` instead of panicking.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut v = Value::Null;
+    /// *v.get_or_null_mut("name").unwrap() = "demo".into();
+    /// assert_eq!(v["name"], "demo");
+    ///
+    /// let mut v = Value::from(vec![1, 2]);
+    /// assert_eq!(v.get_or_null_mut("name"), None);
+    /// ```
+    pub fn get_or_null_mut<I: Into<Value>>(&mut self, key: I) -> Option<&mut Value> {
+        let map = self.ensure_mapping().ok()?;
+        Some(map.entry(key.into()).or_insert(Value::Null))
+    }
+
+    /// Returns a mutable reference to the value at the end of a path of
+    /// string keys, creating the mappings along the way. Each step behaves
+    /// like indexing with [`IndexMut`](std::ops::IndexMut): `Null` becomes an
+    /// empty mapping, a missing key is inserted with the value `Null`, and
+    /// tags are looked through.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut config = Value::Null;
+    /// *config.entry_path(["server", "tls", "port"]) = 443.into();
+    /// *config.entry_path(["server", "host"]) = "localhost".into();
+    ///
+    /// let expected = "{server: {tls: {port: 443}, host: localhost}}";
+    /// assert_eq!(config, serde_yaml::from_str::<Value>(expected).unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics, like `IndexMut`, if a value along the path is neither a
+    /// mapping nor null. Use [`get_or_null_mut`](Self::get_or_null_mut) at
+    /// each step to handle that case instead.
+    pub fn entry_path<'a, I>(&mut self, path: I) -> &mut Value
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut value = self;
+        for key in path {
+            value = &mut value[key];
+        }
+        value
+    }
+
+    /// If the `Value` is a mapping with exactly one entry, returns its key and
+    /// value. Returns None otherwise. Tags are looked through, like in
+    /// [`as_mapping`](Self::as_mapping).
+    ///
+    /// This is the shape of an externally tagged enum variant with data, so it
+    /// is a convenient way to tell such variants apart without deserializing.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("Circle: {radius: 2}").unwrap();
+    /// let (variant, data) = v.as_single_entry().unwrap();
+    /// assert_eq!(*variant, "Circle");
+    /// assert_eq!(data["radius"], 2);
+    ///
+    /// let v: Value = serde_yaml::from_str("{x: 1, y: 2}").unwrap();
+    /// assert_eq!(v.as_single_entry(), None);
+    /// ```
+    pub fn as_single_entry(&self) -> Option<(&Value, &Value)> {
+        match self.as_mapping() {
+            Some(map) if map.len() == 1 => map.get_index(0),
+            _ => None,
+        }
+    }
+
     /// Performs merging of `<<` keys into the surrounding mapping.
     ///
     /// The intended use of this in YAML is described in
@@ -624,23 +1192,82 @@ impl Value {
     /// assert_eq!(value["tasks"]["start"]["args"], "start");
     /// ```
     pub fn apply_merge(&mut self) -> Result<(), Error> {
+        self.apply_merge_depth(usize::MAX)
+    }
+
+    /// Performs merging of `<<` keys like [`apply_merge`][Value::apply_merge],
+    /// but only in mappings nested at most `max_depth` levels below `self`.
+    ///
+    /// Depth 0 is `self` itself. Each sequence or mapping adds a level; tags do
+    /// not. Deeper `<<` keys are left in place as ordinary entries.
+    ///
+    /// ```
+    /// use serde_yaml::Value;
+    ///
+    /// let config = "\
+    /// base: &base
+    ///   retries: 3
+    /// <<: *base
+    /// jobs:
+    ///   <<: *base
+    /// ";
+    ///
+    /// let mut value: Value = serde_yaml::from_str(config).unwrap();
+    /// value.apply_merge_depth(0).unwrap();
+    ///
+    /// assert_eq!(value["retries"], 3);
+    /// assert!(value["jobs"].contains_key("<<"));
+    /// ```
+    pub fn apply_merge_depth(&mut self, max_depth: usize) -> Result<(), Error> {
+        self.merge(max_depth, MergeOrder::Append)
+    }
+
+    /// Performs merging of `<<` keys like [`apply_merge`][Value::apply_merge],
+    /// placing the merged keys as selected by `order`.
+    ///
+    /// ```
+    /// use serde_yaml::value::MergeOrder;
+    /// use serde_yaml::Value;
+    ///
+    /// let config = "\
+    /// base: &base {image: alpine, retries: 3}
+    /// job:
+    ///   name: test
+    ///   retries: 5
+    ///   <<: *base
+    /// ";
+    ///
+    /// let mut value: Value = serde_yaml::from_str(config).unwrap();
+    /// value.apply_merge_with(MergeOrder::Prepend).unwrap();
+    ///
+    /// let keys: Vec<&str> = value["job"]
+    ///     .as_mapping()
+    ///     .unwrap()
+    ///     .keys()
+    ///     .map(|key| key.as_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(keys, ["image", "retries", "name"]);
+    /// assert_eq!(value["job"]["retries"], 5);
+    /// ```
+    pub fn apply_merge_with(&mut self, order: MergeOrder) -> Result<(), Error> {
+        self.merge(usize::MAX, order)
+    }
+
+    fn merge(&mut self, max_depth: usize, order: MergeOrder) -> Result<(), Error> {
         let mut stack = Vec::new();
-        stack.push(self);
-        while let Some(node) = stack.pop() {
+        stack.push((self, 0));
+        while let Some((node, depth)) = stack.pop() {
             match node {
                 Value::Mapping(mapping) => {
+                    let mut merged = Mapping::new();
                     match mapping.remove("<<") {
-                        Some(Value::Mapping(merge)) => {
-                            for (k, v) in merge {
-                                mapping.entry(k).or_insert(v);
-                            }
-                        }
+                        Some(Value::Mapping(merge)) => merged = merge,
                         Some(Value::Sequence(sequence)) => {
                             for value in sequence {
                                 match value {
                                     Value::Mapping(merge) => {
                                         for (k, v) in merge {
-                                            mapping.entry(k).or_insert(v);
+                                            merged.entry(k).or_insert(v);
                                         }
                                     }
                                     Value::Sequence(_) => {
@@ -659,15 +1286,716 @@ impl Value {
                         Some(Value::Tagged(_)) => return Err(error::new(ErrorImpl::TaggedInMerge)),
                         Some(_unexpected) => return Err(error::new(ErrorImpl::ScalarInMerge)),
                     }
-                    stack.extend(mapping.values_mut());
+                    match order {
+                        MergeOrder::Prepend if !merged.is_empty() => {
+                            let local = mem::replace(mapping, merged);
+                            mapping.extend(local);
+                        }
+                        _ => {
+                            for (k, v) in merged {
+                                mapping.entry(k).or_insert(v);
+                            }
+                        }
+                    }
+                    if depth < max_depth {
+                        stack.extend(mapping.values_mut().map(|value| (value, depth + 1)));
+                    }
                 }
-                Value::Sequence(sequence) => stack.extend(sequence),
-                Value::Tagged(tagged) => stack.push(&mut tagged.value),
+                Value::Sequence(sequence) if depth < max_depth => {
+                    stack.extend(sequence.iter_mut().map(|value| (value, depth + 1)));
+                }
+                Value::Tagged(tagged) => stack.push((&mut tagged.value, depth)),
                 _ => {}
             }
         }
         Ok(())
     }
+
+    /// Calls `f` on every node of this value in depth-first order, starting
+    /// with `self`.
+    ///
+    /// The first argument is the trail of mapping keys and sequence indices
+    /// leading to the node. Tagged values are visited once, and their
+    /// contents are traversed as if the tag were absent.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("a: [x, {b: y}]").unwrap();
+    ///
+    /// let mut strings = Vec::new();
+    /// value.visit(|path, node| {
+    ///     if let Some(string) = node.as_str() {
+    ///         strings.push((path.to_vec(), string.to_owned()));
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(strings.len(), 2);
+    /// assert_eq!(strings[0].0, [Value::from("a"), Value::from(0)]);
+    /// assert_eq!(strings[1].0, [Value::from("a"), Value::from(1), Value::from("b")]);
+    /// ```
+    pub fn visit<F>(&self, mut f: F)
+    where
+        F: FnMut(&[Value], &Value),
+    {
+        visit(self, &mut Vec::new(), &mut f);
+    }
+
+    /// Returns every node of this value for which `predicate` returns true,
+    /// in the depth-first order of [`visit`][Value::visit], each paired with
+    /// the trail of mapping keys and sequence indices leading to it.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let yaml = "{db: {password: secret-1}, api: [secret-2, public]}";
+    /// let value: Value = serde_yaml::from_str(yaml).unwrap();
+    ///
+    /// let is_secret = |node: &Value| node.as_str().is_some_and(|s| s.starts_with("secret-"));
+    /// let secrets = value.find_all(is_secret);
+    ///
+    /// assert_eq!(secrets.len(), 2);
+    /// assert_eq!(secrets[0].0, [Value::from("db"), Value::from("password")]);
+    /// assert_eq!(secrets[0].1, "secret-1");
+    /// assert_eq!(secrets[1].0, [Value::from("api"), Value::from(0)]);
+    /// assert_eq!(secrets[1].1, "secret-2");
+    /// ```
+    pub fn find_all<F>(&self, predicate: F) -> Vec<(Vec<Value>, &Value)>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let mut found = Vec::new();
+        visit(self, &mut Vec::new(), &mut |path: &[Value], node| {
+            if predicate(node) {
+                found.push((path.to_vec(), node));
+            }
+        });
+        found
+    }
+
+    /// Returns an iterator over the leaves of this value, meaning every node
+    /// that is not a sequence or mapping, in the depth-first order of
+    /// [`visit`][Value::visit]. Each is paired with the trail of mapping keys
+    /// and sequence indices leading to it. Empty sequences and mappings are
+    /// not leaves.
+    ///
+    /// The traversal keeps its own stack rather than recursing, so it handles
+    /// arbitrarily deep trees.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("{a: [1, {b: x}], c: [], d: ~}").unwrap();
+    ///
+    /// let leaves: Vec<_> = value.leaves().collect();
+    /// assert_eq!(leaves.len(), 3);
+    /// assert_eq!(leaves[0].0, [Value::from("a"), Value::from(0)]);
+    /// assert_eq!(leaves[1].0, [Value::from("a"), Value::from(1), Value::from("b")]);
+    /// assert_eq!(leaves[2].0, [Value::from("d")]);
+    ///
+    /// assert_eq!(value.leaves().filter(|(_path, leaf)| leaf.is_number()).count(), 1);
+    /// ```
+    pub fn leaves(&self) -> impl Iterator<Item = (Vec<Value>, &Value)> {
+        let mut stack = vec![(Vec::new(), self)];
+        iter::from_fn(move || {
+            while let Some((path, node)) = stack.pop() {
+                let child = |key: Value| {
+                    let mut path = path.clone();
+                    path.push(key);
+                    path
+                };
+                match node.untag_ref() {
+                    Value::Sequence(sequence) => {
+                        for (i, v) in sequence.iter().enumerate().rev() {
+                            stack.push((child(Value::Number(i.into())), v));
+                        }
+                    }
+                    Value::Mapping(mapping) => {
+                        for (k, v) in mapping.iter().rev() {
+                            stack.push((child(k.clone()), v));
+                        }
+                    }
+                    _ => return Some((path, node)),
+                }
+            }
+            None
+        })
+    }
+
+    /// Like [`visit`][Value::visit] but provides mutable access to each node.
+    ///
+    /// Children are traversed after `f` returns, so replacing a node visits
+    /// the replacement's children.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut value: Value = serde_yaml::from_str("a: [' x ', {b: 'y '}]").unwrap();
+    ///
+    /// value.visit_mut(|_path, node| {
+    ///     if let Value::String(string) = node {
+    ///         *string = string.trim().to_owned();
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(value["a"][0], "x");
+    /// assert_eq!(value["a"][1]["b"], "y");
+    /// ```
+    pub fn visit_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[Value], &mut Value),
+    {
+        visit_mut(self, &mut Vec::new(), &mut f);
+    }
+
+    /// Removes every tag from this value, replacing each `Value::Tagged` with
+    /// the value inside it throughout the tree, including in mapping keys.
+    ///
+    /// If two keys of a mapping become equal once untagged, the later entry's
+    /// value replaces the earlier one's.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut value: Value = serde_yaml::from_str("!Thing {a: [!Int 1], !Key b: c}").unwrap();
+    /// value.untag_all();
+    ///
+    /// let expected: Value = serde_yaml::from_str("{a: [1], b: c}").unwrap();
+    /// assert_eq!(value, expected);
+    /// ```
+    pub fn untag_all(&mut self) {
+        *self = mem::take(self).untag();
+        match self {
+            Value::Sequence(sequence) => {
+                for v in sequence {
+                    v.untag_all();
+                }
+            }
+            Value::Mapping(mapping) => {
+                *mapping = mem::take(mapping)
+                    .into_iter()
+                    .map(|(mut k, mut v)| {
+                        k.untag_all();
+                        v.untag_all();
+                        (k, v)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns a copy of this value with every tag removed, as by
+    /// [`untag_all`][Value::untag_all].
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("[!Int 1, !Int 2]").unwrap();
+    ///
+    /// assert_eq!(value.untagged(), Value::from(vec![1, 2]));
+    /// assert!(matches!(value[0], Value::Tagged(_)));
+    /// ```
+    pub fn untagged(&self) -> Value {
+        let mut value = self.clone();
+        value.untag_all();
+        value
+    }
+
+    /// Replaces every string in this value that reads as a YAML integer or
+    /// float with the corresponding `Value::Number`.
+    ///
+    /// This is a lossy normalization, meant for comparing against data in
+    /// which quoting did not carry meaning. Spellings are not preserved, so
+    /// `"0x1F"` and `"1e3"` become `31` and `1000.0`, and the distinction
+    /// between a quoted and an unquoted number is lost. Strings that read as
+    /// booleans or null, strings directly inside a tag, and mapping keys are
+    /// left as they are.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut value: Value = serde_yaml::from_str("{a: '42', b: ['-1.5', 'x', 'true']}").unwrap();
+    /// value.coerce_strings_to_numbers();
+    ///
+    /// assert_eq!(value["a"], 42);
+    /// assert_eq!(value["b"][0], -1.5);
+    /// assert_eq!(value["b"][1], "x");
+    /// assert_eq!(value["b"][2], "true");
+    /// ```
+    pub fn coerce_strings_to_numbers(&mut self) {
+        self.visit_mut(|_path, node| {
+            let number = match node {
+                Value::String(string) => string.parse::<Number>(),
+                _ => return,
+            };
+            if let Ok(number) = number {
+                *node = Value::Number(number);
+            }
+        });
+    }
+
+    /// Replaces every string in this value, including strings in mapping keys
+    /// and inside tags, with the result of calling `f` on it.
+    ///
+    /// If rewriting makes two keys of a mapping equal, the entries are merged:
+    /// the later entry's value wins, and the merged entry stays at the
+    /// position of the earlier one.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let mut value: Value = serde_yaml::from_str("{url: 'http://${HOST}/', '${HOST}': [x]}").unwrap();
+    /// value.map_strings(|string| string.replace("${HOST}", "example.com"));
+    ///
+    /// assert_eq!(value["url"], "http://example.com/");
+    /// assert_eq!(value["example.com"][0], "x");
+    /// ```
+    pub fn map_strings<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str) -> String,
+    {
+        map_strings(self, &mut f);
+    }
+
+    /// Removes empty sequences and mappings nested in this value: mapping
+    /// entries whose value is empty, and sequence elements that are empty.
+    ///
+    /// Pruning works bottom-up, so a container left empty by the removal of
+    /// its contents is removed in turn. With `prune_null`, null values are
+    /// removed the same way. Tagged values are never removed, though their
+    /// contents are pruned, and `self` is left in place even if it ends up
+    /// empty.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let yaml = "{a: {b: [], c: {}}, d: [[], 1, null], e: ~}";
+    ///
+    /// let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    /// value.prune_empty(false);
+    /// assert_eq!(value, serde_yaml::from_str::<Value>("{d: [1, null], e: ~}").unwrap());
+    ///
+    /// let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    /// value.prune_empty(true);
+    /// assert_eq!(value, serde_yaml::from_str::<Value>("{d: [1]}").unwrap());
+    /// ```
+    pub fn prune_empty(&mut self, prune_null: bool) {
+        let prunable = |value: &Value| match value {
+            Value::Null => prune_null,
+            Value::Sequence(sequence) => sequence.is_empty(),
+            Value::Mapping(mapping) => mapping.is_empty(),
+            _ => false,
+        };
+        match self {
+            Value::Sequence(sequence) => {
+                for value in sequence.iter_mut() {
+                    value.prune_empty(prune_null);
+                }
+                sequence.retain(|value| !prunable(value));
+            }
+            Value::Mapping(mapping) => {
+                for value in mapping.values_mut() {
+                    value.prune_empty(prune_null);
+                }
+                mapping.retain(|_key, value| !prunable(&*value));
+            }
+            Value::Tagged(tagged) => tagged.value.prune_empty(prune_null),
+            _ => {}
+        }
+    }
+
+    /// Returns the total number of nodes in this value, counting `self`, every
+    /// sequence element, and every mapping key and mapping value.
+    ///
+    /// A tagged value counts as the single node it wraps. The tree is walked
+    /// without recursion, so deeply nested values do not overflow the stack.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("{a: [1, 2], b: c}").unwrap();
+    ///
+    /// // the mapping, 2 keys, the sequence and its 2 elements, and `c`
+    /// assert_eq!(value.node_count(), 7);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            count += 1;
+            match value.untag_ref() {
+                Value::Sequence(sequence) => stack.extend(sequence),
+                Value::Mapping(mapping) => {
+                    for (k, v) in mapping {
+                        stack.push(k);
+                        stack.push(v);
+                    }
+                }
+                _ => {}
+            }
+        }
+        count
+    }
+
+    /// Returns the maximum nesting depth of sequences and mappings in this
+    /// value.
+    ///
+    /// A scalar has depth 0, and a sequence or mapping is one deeper than the
+    /// deepest of its elements, keys and values. Like
+    /// [`node_count`][Value::node_count], this does not recurse.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("{a: [1, [2]], b: {}}").unwrap();
+    /// assert_eq!(value.depth(), 3);
+    ///
+    /// assert_eq!(Value::from("scalar").depth(), 0);
+    /// ```
+    pub fn depth(&self) -> usize {
+        let mut max = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((value, depth)) = stack.pop() {
+            match value.untag_ref() {
+                Value::Sequence(sequence) => {
+                    max = max.max(depth + 1);
+                    stack.extend(sequence.iter().map(|v| (v, depth + 1)));
+                }
+                Value::Mapping(mapping) => {
+                    max = max.max(depth + 1);
+                    for (k, v) in mapping {
+                        stack.push((k, depth + 1));
+                        stack.push((v, depth + 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+        max
+    }
+
+    /// Flattens nested mappings and sequences into a single-level mapping
+    /// whose keys are the paths to each leaf, joined by `separator`.
+    ///
+    /// Sequence elements are addressed by their index. Empty mappings and
+    /// empty sequences are kept as leaves. If `self` is neither a mapping nor
+    /// a sequence, the result is empty.
+    ///
+    /// Different paths join to the same key when a key contains `separator`.
+    /// Only the leaf that comes last is kept, so such a value does not survive
+    /// [`unflatten_keys`][Value::unflatten_keys].
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("a: {b: 1, c: [x, y]}").unwrap();
+    /// let flat = value.flatten_keys(".");
+    ///
+    /// assert_eq!(flat["a.b"], 1);
+    /// assert_eq!(flat["a.c.0"], "x");
+    /// assert_eq!(flat["a.c.1"], "y");
+    ///
+    /// let value: Value = serde_yaml::from_str("{a.b: 1, a: {b: 2}}").unwrap();
+    /// let flat = value.flatten_keys(".");
+    ///
+    /// assert_eq!(flat.len(), 1);
+    /// assert_eq!(flat["a.b"], 2);
+    /// ```
+    pub fn flatten_keys(&self, separator: &str) -> Mapping {
+        let mut flat = Mapping::new();
+        flatten_into(self, None, separator, &mut flat);
+        flat
+    }
+
+    /// Rebuilds a nested value from a mapping produced by
+    /// [`flatten_keys`][Value::flatten_keys].
+    ///
+    /// Each key is split on `separator` and the value is placed at the
+    /// resulting path. Mappings whose keys are exactly the indices `0..len`
+    /// are turned back into sequences. An empty `separator` leaves keys
+    /// unsplit.
+    ///
+    /// A key whose path is a prefix of another's, like `a` and `a.b`, cannot
+    /// be both a leaf and a mapping. Whichever of the two comes later in
+    /// `flat` replaces the other.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let value: Value = serde_yaml::from_str("a: {b: 1, c: [x, y]}").unwrap();
+    /// let flat = value.flatten_keys(".");
+    ///
+    /// assert_eq!(Value::unflatten_keys(flat, "."), value);
+    /// ```
+    pub fn unflatten_keys(flat: Mapping, separator: &str) -> Value {
+        let mut root = Value::Mapping(Mapping::new());
+        for (key, value) in flat {
+            let key = key_segment(&key);
+            let segments: Vec<&str> = if separator.is_empty() {
+                vec![&key]
+            } else {
+                key.split(separator).collect()
+            };
+            let mut node = &mut root;
+            for segment in segments {
+                if !matches!(node, Value::Mapping(_)) {
+                    *node = Value::Mapping(Mapping::new());
+                }
+                node = match node {
+                    Value::Mapping(mapping) => mapping
+                        .entry(Value::String(segment.to_owned()))
+                        .or_insert(Value::Null),
+                    _ => unreachable!(),
+                };
+            }
+            *node = value;
+        }
+        sequences_from_indices(&mut root);
+        root
+    }
+}
+
+fn visit<'a, F>(value: &'a Value, path: &mut Vec<Value>, f: &mut F)
+where
+    F: FnMut(&[Value], &'a Value),
+{
+    f(path, value);
+    match value.untag_ref() {
+        Value::Sequence(sequence) => {
+            for (i, v) in sequence.iter().enumerate() {
+                path.push(Value::Number(i.into()));
+                visit(v, path, f);
+                path.pop();
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (k, v) in mapping {
+                path.push(k.clone());
+                visit(v, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn visit_mut<F>(value: &mut Value, path: &mut Vec<Value>, f: &mut F)
+where
+    F: FnMut(&[Value], &mut Value),
+{
+    f(path, value);
+    match value.untag_mut() {
+        Value::Sequence(sequence) => {
+            for (i, v) in sequence.iter_mut().enumerate() {
+                path.push(Value::Number(i.into()));
+                visit_mut(v, path, f);
+                path.pop();
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (k, v) in mapping {
+                path.push(k.clone());
+                visit_mut(v, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn map_strings<F>(value: &mut Value, f: &mut F)
+where
+    F: FnMut(&str) -> String,
+{
+    match value.untag_mut() {
+        Value::String(string) => *string = f(string),
+        Value::Sequence(sequence) => {
+            for v in sequence {
+                map_strings(v, f);
+            }
+        }
+        Value::Mapping(mapping) => {
+            *mapping = mem::take(mapping)
+                .into_iter()
+                .map(|(mut k, mut v)| {
+                    map_strings(&mut k, f);
+                    map_strings(&mut v, f);
+                    (k, v)
+                })
+                .collect();
+        }
+        _ => {}
+    }
+}
+
+fn flatten_into(value: &Value, prefix: Option<&str>, separator: &str, flat: &mut Mapping) {
+    let join = |segment: &str| match prefix {
+        Some(prefix) => format!("{}{}{}", prefix, separator, segment),
+        None => segment.to_owned(),
+    };
+    match value.untag_ref() {
+        Value::Mapping(mapping) if !mapping.is_empty() => {
+            for (k, v) in mapping {
+                flatten_into(v, Some(&join(&key_segment(k))), separator, flat);
+            }
+        }
+        Value::Sequence(sequence) if !sequence.is_empty() => {
+            for (i, v) in sequence.iter().enumerate() {
+                flatten_into(v, Some(&join(&i.to_string())), separator, flat);
+            }
+        }
+        _ => {
+            if let Some(path) = prefix {
+                flat.insert(Value::String(path.to_owned()), value.clone());
+            }
+        }
+    }
+}
+
+fn key_segment(key: &Value) -> String {
+    match key.untag_ref() {
+        Value::Null => "null".to_owned(),
+        Value::Bool(boolean) => boolean.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => string.clone(),
+        complex => crate::to_string(complex)
+            .map(|yaml| yaml.trim_end().to_owned())
+            .unwrap_or_default(),
+    }
+}
+
+fn sequences_from_indices(value: &mut Value) {
+    let Value::Mapping(mapping) = value else {
+        return;
+    };
+    for v in mapping.values_mut() {
+        sequences_from_indices(v);
+    }
+    let len = mapping.len();
+    if len == 0 {
+        return;
+    }
+    let mut indices = Vec::with_capacity(len);
+    for key in mapping.keys() {
+        match key.as_str().and_then(parse_index) {
+            Some(index) if index < len => indices.push(index),
+            _ => return,
+        }
+    }
+    let mut sequence = vec![Value::Null; len];
+    for (index, (_k, v)) in indices.into_iter().zip(mem::take(mapping)) {
+        sequence[index] = v;
+    }
+    *value = Value::Sequence(sequence);
+}
+
+fn parse_index(segment: &str) -> Option<usize> {
+    let index = segment.parse::<usize>().ok()?;
+    // Reject spellings like "01" or "+1" which would not round trip.
+    if index.to_string() == segment {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+fn type_error(expected: &str, found: &Value) -> Error {
+    let message = format!("expected a {}, found {}", expected, found.type_name());
+    error::new(ErrorImpl::Message(message, None))
+}
+
+fn hash_structure<H: Hasher>(value: &Value, state: &mut H) {
+    mem::discriminant(value).hash(state);
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+        Value::Sequence(sequence) => {
+            sequence.len().hash(state);
+            for element in sequence {
+                hash_structure(element, state);
+            }
+        }
+        Value::Mapping(mapping) => {
+            // Combine the entries in a way that is not sensitive to their
+            // order, like the Hash impl of Mapping.
+            let mut xor = 0;
+            for (k, v) in mapping {
+                let mut hasher = DefaultHasher::new();
+                k.hash(&mut hasher);
+                hash_structure(v, &mut hasher);
+                xor ^= hasher.finish();
+            }
+            mapping.len().hash(state);
+            xor.hash(state);
+        }
+        Value::Tagged(tagged) => {
+            tagged.tag.hash(state);
+            hash_structure(&tagged.value, state);
+        }
+    }
+}
+
+// 64-bit FNV-1a. The parameters must never change; see Value::stable_hash.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+fn hash_stable(value: &Value, hasher: &mut Fnv1a) {
+    match value {
+        Value::Null => hasher.write(&[0x00]),
+        Value::Bool(b) => hasher.write(&[0x01, u8::from(*b)]),
+        Value::Number(n) => {
+            let int = n.as_i64().map(i128::from);
+            if let Some(int) = int.or_else(|| n.as_u64().map(i128::from)) {
+                hasher.write(&[0x02]);
+                hasher.write(&int.to_le_bytes());
+            } else {
+                let float = n.as_f64().unwrap_or(f64::NAN);
+                let bits = if float.is_nan() {
+                    0x7FF8_0000_0000_0000
+                } else if float == 0.0 {
+                    0
+                } else {
+                    float.to_bits()
+                };
+                hasher.write(&[0x03]);
+                hasher.write(&bits.to_le_bytes());
+            }
+        }
+        Value::String(string) => {
+            hasher.write(&[0x04]);
+            hasher.write_len(string.len());
+            hasher.write(string.as_bytes());
+        }
+        Value::Sequence(sequence) => {
+            hasher.write(&[0x05]);
+            hasher.write_len(sequence.len());
+            for element in sequence {
+                hash_stable(element, hasher);
+            }
+        }
+        Value::Mapping(mapping) => {
+            let mut sum = 0u64;
+            for (k, v) in mapping {
+                let mut entry = Fnv1a::new();
+                hash_stable(k, &mut entry);
+                hash_stable(v, &mut entry);
+                sum = sum.wrapping_add(entry.0);
+            }
+            hasher.write(&[0x06]);
+            hasher.write_len(mapping.len());
+            hasher.write(&sum.to_le_bytes());
+        }
+        Value::Tagged(tagged) => {
+            let tag = tagged::nobang(&tagged.tag.string);
+            hasher.write(&[0x07]);
+            hasher.write_len(tag.len());
+            hasher.write(tag.as_bytes());
+            hash_stable(&tagged.value, hasher);
+        }
+    }
 }
 
 impl Eq for Value {}
@@ -696,3 +2024,11 @@ impl IntoDeserializer<'_, Error> for Value {
         self
     }
 }
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
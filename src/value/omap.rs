@@ -0,0 +1,63 @@
+use crate::value::tagged::{Tag, TaggedValue};
+use crate::value::{Sequence, Value};
+
+/// The tag of a `!!omap` sequence, as stored in a [`Tag`].
+pub(crate) const OMAP_TAG: &str = "!!omap";
+
+impl Value {
+    /// If the `Value` is an `!!omap`, meaning a sequence tagged `!!omap` whose
+    /// elements are all mappings with a single entry, returns its entries in
+    /// order. Returns None otherwise.
+    ///
+    /// Deserializing an `!!omap` into a sequence of pairs such as
+    /// `Vec<(K, V)>`, or into a map type, yields the same entries.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("!!omap [b: 1, a: 2]").unwrap();
+    /// assert_eq!(
+    ///     v.as_omap(),
+    ///     Some(vec![
+    ///         (&Value::from("b"), &Value::from(1)),
+    ///         (&Value::from("a"), &Value::from(2)),
+    ///     ]),
+    /// );
+    ///
+    /// let v: Value = serde_yaml::from_str("[b: 1, a: 2]").unwrap();
+    /// assert_eq!(v.as_omap(), None);
+    /// ```
+    pub fn as_omap(&self) -> Option<Vec<(&Value, &Value)>> {
+        match self {
+            Value::Tagged(tagged) if is_omap(tagged) => match &tagged.value {
+                Value::Sequence(entries) => Some(
+                    entries
+                        .iter()
+                        .filter_map(|entry| entry.as_mapping()?.iter().next())
+                        .collect(),
+                ),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Whether `tagged` is an `!!omap` sequence whose elements are all mappings
+/// with a single entry.
+pub(crate) fn is_omap(tagged: &TaggedValue) -> bool {
+    tagged.tag == Tag::new(OMAP_TAG)
+        && match &tagged.value {
+            Value::Sequence(entries) => entries
+                .iter()
+                .all(|entry| matches!(entry, Value::Mapping(mapping) if mapping.len() == 1)),
+            _ => false,
+        }
+}
+
+/// The entries of the sequence of an `!!omap` accepted by [`is_omap`].
+pub(crate) fn into_entries(entries: Sequence) -> impl Iterator<Item = (Value, Value)> {
+    entries.into_iter().filter_map(|entry| match entry {
+        Value::Mapping(mapping) => mapping.into_iter().next(),
+        _ => None,
+    })
+}
@@ -28,6 +28,34 @@ impl PartialEq<&str> for Value {
     }
 }
 
+impl PartialEq<Value> for str {
+    /// Compare YAML value with `str`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// assert!(*"lorem" == Value::String("lorem".into()));
+    /// ```
+    fn eq(&self, other: &Value) -> bool {
+        other.as_str() == Some(self)
+    }
+}
+
+impl PartialEq<Value> for &str {
+    /// Compare YAML value with `&str`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// assert!("lorem" == Value::String("lorem".into()));
+    /// ```
+    fn eq(&self, other: &Value) -> bool {
+        other.as_str() == Some(*self)
+    }
+}
+
 impl PartialEq<String> for Value {
     /// Compare YAML value with String
     ///
@@ -42,6 +70,20 @@ impl PartialEq<String> for Value {
     }
 }
 
+impl PartialEq<Value> for String {
+    /// Compare String with YAML value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// assert!("lorem".to_string() == Value::String("lorem".into()));
+    /// ```
+    fn eq(&self, other: &Value) -> bool {
+        other.as_str().is_some_and(|s| s == self)
+    }
+}
+
 impl PartialEq<bool> for Value {
     /// Compare YAML value with bool
     ///
@@ -56,6 +98,20 @@ impl PartialEq<bool> for Value {
     }
 }
 
+impl PartialEq<Value> for bool {
+    /// Compare bool with YAML value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// assert!(true == Value::Bool(true));
+    /// ```
+    fn eq(&self, other: &Value) -> bool {
+        other.as_bool() == Some(*self)
+    }
+}
+
 macro_rules! partialeq_numeric {
     ($([$($ty:ty)*], $conversion:ident, $base:ty)*) => {
         $($(
@@ -76,6 +132,24 @@ macro_rules! partialeq_numeric {
                     self.$conversion().map_or(false, |i| i == (*other as $base))
                 }
             }
+
+            impl PartialEq<Value> for $ty {
+                fn eq(&self, other: &Value) -> bool {
+                    other.$conversion().map_or(false, |i| i == (*self as $base))
+                }
+            }
+
+            impl<'a> PartialEq<&'a Value> for $ty {
+                fn eq(&self, other: &&'a Value) -> bool {
+                    other.$conversion().map_or(false, |i| i == (*self as $base))
+                }
+            }
+
+            impl<'a> PartialEq<&'a mut Value> for $ty {
+                fn eq(&self, other: &&'a mut Value) -> bool {
+                    other.$conversion().map_or(false, |i| i == (*self as $base))
+                }
+            }
         )*)*
     }
 }
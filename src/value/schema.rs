@@ -0,0 +1,220 @@
+use crate::value::Value;
+use std::error;
+use std::fmt::{self, Display};
+
+/// The kinds of [`Value`] that a [`Schema`] can require. Tags are looked
+/// through, so a tagged mapping is a `Mapping`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    /// [`Value::Null`]
+    Null,
+    /// [`Value::Bool`]
+    Bool,
+    /// [`Value::Number`]
+    Number,
+    /// [`Value::String`]
+    String,
+    /// [`Value::Sequence`]
+    Sequence,
+    /// [`Value::Mapping`]
+    Mapping,
+}
+
+impl ValueKind {
+    fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value.untag_ref()),
+            (ValueKind::Null, Value::Null)
+                | (ValueKind::Bool, Value::Bool(_))
+                | (ValueKind::Number, Value::Number(_))
+                | (ValueKind::String, Value::String(_))
+                | (ValueKind::Sequence, Value::Sequence(_))
+                | (ValueKind::Mapping, Value::Mapping(_))
+        )
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ValueKind::Null => "null",
+            ValueKind::Bool => "bool",
+            ValueKind::Number => "number",
+            ValueKind::String => "string",
+            ValueKind::Sequence => "sequence",
+            ValueKind::Mapping => "mapping",
+        }
+    }
+}
+
+/// A structural template to check a [`Value`] against with
+/// [`Value::validate`].
+///
+/// A schema optionally requires a kind of value. Its keys apply when the
+/// value is a mapping, and its items schema applies to each element when the
+/// value is a sequence.
+///
+/// ```
+/// use serde_yaml::value::{Schema, ValueKind};
+/// use serde_yaml::Value;
+///
+/// let schema = Schema::new(ValueKind::Mapping)
+///     .required("name", Schema::new(ValueKind::String))
+///     .optional("port", Schema::new(ValueKind::Number))
+///     .required(
+///         "tags",
+///         Schema::new(ValueKind::Sequence).items(Schema::new(ValueKind::String)),
+///     );
+///
+/// let value: Value = serde_yaml::from_str("{name: web, tags: [a, b]}").unwrap();
+/// assert!(value.validate(&schema).is_ok());
+///
+/// let value: Value = serde_yaml::from_str("{port: http, tags: [a, [b]]}").unwrap();
+/// let errors = value.validate(&schema).unwrap_err();
+/// let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+/// assert_eq!(
+///     messages,
+///     [
+///         "missing required key `name`",
+///         "port: expected a number, found string",
+///         "tags[1]: expected a string, found sequence",
+///     ],
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    kind: Option<ValueKind>,
+    keys: Vec<(String, bool, Schema)>,
+    items: Option<Box<Schema>>,
+}
+
+impl Schema {
+    /// A schema that accepts a value of any kind.
+    pub fn any() -> Self {
+        Schema::default()
+    }
+
+    /// A schema that accepts only values of the given kind.
+    pub fn new(kind: ValueKind) -> Self {
+        Schema {
+            kind: Some(kind),
+            ..Schema::default()
+        }
+    }
+
+    /// Requires a mapping to contain `key`, with a value matching `schema`.
+    pub fn required(mut self, key: impl Into<String>, schema: Schema) -> Self {
+        self.keys.push((key.into(), true, schema));
+        self
+    }
+
+    /// If a mapping contains `key`, requires its value to match `schema`.
+    pub fn optional(mut self, key: impl Into<String>, schema: Schema) -> Self {
+        self.keys.push((key.into(), false, schema));
+        self
+    }
+
+    /// Requires every element of a sequence to match `schema`.
+    pub fn items(mut self, schema: Schema) -> Self {
+        self.items = Some(Box::new(schema));
+        self
+    }
+}
+
+/// A mismatch between a [`Value`] and a [`Schema`], found by
+/// [`Value::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    path: Vec<Value>,
+    message: String,
+}
+
+impl ValidationError {
+    /// The mapping keys and sequence indices leading to the mismatched value,
+    /// empty for the root.
+    pub fn path(&self) -> &[Value] {
+        &self.path
+    }
+
+    /// The description of the mismatch, without the path.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                Value::Number(index) => write!(formatter, "[{}]", index)?,
+                Value::String(key) if i == 0 => formatter.write_str(key)?,
+                Value::String(key) => write!(formatter, ".{}", key)?,
+                _ => unreachable!(),
+            }
+        }
+        if !self.path.is_empty() {
+            formatter.write_str(": ")?;
+        }
+        formatter.write_str(&self.message)
+    }
+}
+
+impl error::Error for ValidationError {}
+
+impl Value {
+    /// Checks this value against `schema`, returning every mismatch found
+    /// rather than only the first.
+    ///
+    /// Refer to [`Schema`] for an example.
+    pub fn validate(&self, schema: &Schema) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        validate(self, schema, &mut Vec::new(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn validate(
+    value: &Value,
+    schema: &Schema,
+    path: &mut Vec<Value>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let value = value.untag_ref();
+    if let Some(kind) = schema.kind.filter(|kind| !kind.matches(value)) {
+        errors.push(ValidationError {
+            path: path.clone(),
+            message: format!("expected a {}, found {}", kind.name(), value.type_name()),
+        });
+        return;
+    }
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, required, schema) in &schema.keys {
+                match mapping.get(key.as_str()) {
+                    Some(value) => {
+                        path.push(Value::String(key.clone()));
+                        validate(value, schema, path, errors);
+                        path.pop();
+                    }
+                    None if *required => errors.push(ValidationError {
+                        path: path.clone(),
+                        message: format!("missing required key `{}`", key),
+                    }),
+                    None => {}
+                }
+            }
+        }
+        Value::Sequence(sequence) => {
+            if let Some(items) = &schema.items {
+                for (i, element) in sequence.iter().enumerate() {
+                    path.push(Value::Number(i.into()));
+                    validate(element, items, path, errors);
+                    path.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -51,6 +51,34 @@ impl Serialize for Value {
 ///     input.serialize(serde_yaml::value::Serializer)
 /// }
 /// ```
+///
+/// It can also be used on its own to build part of a document as a `Value`,
+/// inspect or adjust it, and only then write it out or embed it in something
+/// larger. Map keys that are not strings are kept as they are.
+///
+/// ```
+/// use serde::Serialize;
+/// # use serde_derive::Serialize;
+/// use serde_yaml::value::Serializer;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize)]
+/// struct Server {
+///     host: String,
+///     ports: BTreeMap<u16, String>,
+/// }
+///
+/// let server = Server {
+///     host: "localhost".to_owned(),
+///     ports: BTreeMap::from([(80, "http".to_owned())]),
+/// };
+/// let mut value = server.serialize(Serializer).unwrap();
+/// assert_eq!(value["ports"][80], "http");
+///
+/// value["ports"][443] = "https".into();
+/// let yaml = serde_yaml::to_string(&value).unwrap();
+/// assert_eq!(yaml, "host: localhost\nports:\n  80: http\n  443: https\n");
+/// ```
 pub struct Serializer;
 
 impl ser::Serializer for Serializer {
@@ -234,15 +262,15 @@ impl ser::Serializer for Serializer {
             Ok(SerializeMap::CheckForTag)
         } else {
             Ok(SerializeMap::Untagged {
-                mapping: Mapping::new(),
+                mapping: Mapping::with_capacity(len.unwrap_or(0)),
                 next_key: None,
             })
         }
     }
 
-    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<SerializeStruct> {
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeStruct> {
         Ok(SerializeStruct {
-            mapping: Mapping::new(),
+            mapping: Mapping::with_capacity(len),
         })
     }
 
@@ -251,14 +279,14 @@ impl ser::Serializer for Serializer {
         _enum: &'static str,
         _idx: u32,
         variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<SerializeStructVariant> {
         if variant.is_empty() {
             return Err(error::new(ErrorImpl::EmptyTag));
         }
         Ok(SerializeStructVariant {
             tag: variant,
-            mapping: Mapping::new(),
+            mapping: Mapping::with_capacity(len),
         })
     }
 }
@@ -752,10 +780,17 @@ impl ser::SerializeMap for SerializeMap {
                 let key = key.serialize(CheckForTag)?;
                 let mut mapping = Mapping::new();
                 *self = match key {
-                    MaybeTag::Tag(string) => SerializeMap::Tagged(TaggedValue {
-                        tag: Tag::new(string),
-                        value: to_value(value)?,
-                    }),
+                    MaybeTag::Tag(mut string) => {
+                        // The leading `!` has been stripped, which for a
+                        // `!!name` core tag leaves a single one to restore.
+                        if string.starts_with('!') {
+                            string.insert(0, '!');
+                        }
+                        SerializeMap::Tagged(TaggedValue {
+                            tag: Tag::new(string),
+                            value: to_value(value)?,
+                        })
+                    }
                     MaybeTag::NotTag(key) => {
                         mapping.insert(key, to_value(value)?);
                         SerializeMap::Untagged {
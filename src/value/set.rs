@@ -0,0 +1,41 @@
+use crate::value::tagged::{Tag, TaggedValue};
+use crate::value::Value;
+
+/// The tag of a `!!set` mapping, as stored in a [`Tag`].
+pub(crate) const SET_TAG: &str = "!!set";
+
+impl Value {
+    /// If the `Value` is a `!!set`, meaning a mapping tagged `!!set` whose
+    /// values are all null, returns its members in order. Returns None
+    /// otherwise.
+    ///
+    /// Deserializing a `!!set` into a sequence type such as `HashSet<T>` or
+    /// `BTreeSet<T>` yields the same members.
+    ///
+    /// ```
+    /// # use serde_yaml::Value;
+    /// let v: Value = serde_yaml::from_str("!!set {a, b}").unwrap();
+    /// assert_eq!(v.as_set(), Some(vec![&Value::from("a"), &Value::from("b")]));
+    ///
+    /// let v: Value = serde_yaml::from_str("{a, b}").unwrap();
+    /// assert_eq!(v.as_set(), None);
+    /// ```
+    pub fn as_set(&self) -> Option<Vec<&Value>> {
+        match self {
+            Value::Tagged(tagged) if is_set(tagged) => match &tagged.value {
+                Value::Mapping(mapping) => Some(mapping.keys().collect()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Whether `tagged` is a `!!set` mapping whose values are all null.
+pub(crate) fn is_set(tagged: &TaggedValue) -> bool {
+    tagged.tag == Tag::new(SET_TAG)
+        && match &tagged.value {
+            Value::Mapping(mapping) => mapping.values().all(Value::is_null),
+            _ => false,
+        }
+}
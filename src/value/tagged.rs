@@ -171,6 +171,17 @@ impl Debug for Tag {
     }
 }
 
+/// A tag on its own serializes as the string `"!Thing"`. Use [`TaggedValue`]
+/// to write a tagged node.
+impl Serialize for Tag {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 impl Serialize for TaggedValue {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -2122,3 +2122,661 @@ pub mod singleton_map_recursive {
         }
     }
 }
+
+/// Serialize a sequence or mapping in flow style, like `[1, 2]` or `{a: 1}`,
+/// inside an otherwise block-style document.
+///
+/// Everything nested inside the value is written in flow style too. Only the
+/// YAML serializer honors this; other serializers, including
+/// [`to_value`][crate::to_value], see the value unchanged. Deserialization
+/// accepts either style.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Shape {
+///     name: String,
+///     #[serde(with = "serde_yaml::with::flow")]
+///     origin: Point,
+///     #[serde(with = "serde_yaml::with::flow")]
+///     sizes: Vec<u32>,
+///     labels: BTreeMap<String, String>,
+/// }
+///
+/// fn main() {
+///     let object = Shape {
+///         name: "square".to_owned(),
+///         origin: Point { x: 1, y: 2 },
+///         sizes: vec![3, 4],
+///         labels: BTreeMap::from([("color".to_owned(), "red".to_owned())]),
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "\
+/// name: square
+/// origin: {x: 1, y: 2}
+/// sizes: [3, 4]
+/// labels:
+///   color: red
+/// ");
+///
+///     let deserialized: Shape = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+/// }
+/// ```
+pub mod flow {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    pub(crate) const NAME: &str = "$serde_yaml::flow";
+
+    /// Serializes `value` as usual, except that it and everything inside it is
+    /// written in flow style.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(NAME, value)
+    }
+
+    /// Deserializes the value as usual. Flow style reads back the same as
+    /// block style.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Serialize/deserialize an `Option<Option<T>>` field so that an explicit
+/// `null` can be told apart from a missing field.
+///
+/// | YAML             | Rust                |
+/// |------------------|---------------------|
+/// | field absent     | `None`              |
+/// | `field: null`    | `Some(None)`        |
+/// | `field: <value>` | `Some(Some(value))` |
+///
+/// The field also needs `#[serde(default)]` so that it may be absent, and
+/// `skip_serializing_if = "Option::is_none"` so that `None` is written by
+/// leaving the field out.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Patch {
+///     #[serde(
+///         default,
+///         skip_serializing_if = "Option::is_none",
+///         with = "serde_yaml::with::double_option",
+///     )]
+///     timeout: Option<Option<u32>>,
+/// }
+///
+/// fn main() {
+///     let unchanged: Patch = serde_yaml::from_str("{}").unwrap();
+///     assert_eq!(unchanged.timeout, None);
+///
+///     let cleared: Patch = serde_yaml::from_str("timeout: null").unwrap();
+///     assert_eq!(cleared.timeout, Some(None));
+///
+///     let set: Patch = serde_yaml::from_str("timeout: 30").unwrap();
+///     assert_eq!(set.timeout, Some(Some(30)));
+///
+///     assert_eq!(serde_yaml::to_string(&unchanged).unwrap(), "{}\n");
+///     assert_eq!(serde_yaml::to_string(&cleared).unwrap(), "timeout: null\n");
+/// }
+/// ```
+pub mod double_option {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    /// Serializes both `None` and `Some(None)` as null, and `Some(Some(value))`
+    /// as `value`. Skip the field when it is `None` to tell the two apart.
+    pub fn serialize<T, S>(value: &Option<Option<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            None | Some(None) => serializer.serialize_none(),
+            Some(Some(value)) => serializer.serialize_some(value),
+        }
+    }
+
+    /// Deserializes a present field as `Some`, holding `None` if the field is
+    /// null. A missing field is left to `#[serde(default)]`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Serialize/deserialize a `std::time::Duration` as a scalar with a unit,
+/// like `2s`, `1.5s` or `500ms`.
+///
+/// The units `s`, `ms`, `us` and `ns` are accepted, and all but `ns` may be
+/// given a decimal fraction. Durations are written with the largest unit that
+/// represents them exactly, or as fractional seconds when longer than a second.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+/// use std::time::Duration;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Timeouts {
+///     #[serde(with = "serde_yaml::with::duration")]
+///     connect: Duration,
+///     #[serde(with = "serde_yaml::with::duration")]
+///     read: Duration,
+/// }
+///
+/// fn main() {
+///     let object = Timeouts {
+///         connect: Duration::from_millis(1500),
+///         read: Duration::from_millis(500),
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "connect: 1.5s\nread: 500ms\n");
+///
+///     let deserialized: Timeouts = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+///
+///     let error = serde_yaml::from_str::<Timeouts>("connect: 1s\nread: 5m\n").unwrap_err();
+///     assert_eq!(
+///         error.to_string(),
+///         "read: unknown duration unit `m`, expected one of `s`, `ms`, `us`, `ns` at line 2 column 7",
+///     );
+/// }
+/// ```
+pub mod duration {
+    use serde::de::{self, Deserializer, Unexpected, Visitor};
+    use serde::ser::Serializer;
+    use std::fmt;
+    use std::time::Duration;
+
+    const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+    /// Serializes `duration` as a scalar like `1.5s` or `500ms`.
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format(*duration))
+    }
+
+    /// Deserializes a duration from a scalar like `1.5s` with one of the units
+    /// `s`, `ms`, `us` or `ns`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(DurationVisitor)
+    }
+
+    fn format(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let nanos = duration.subsec_nanos();
+        if nanos == 0 {
+            format!("{}s", secs)
+        } else if secs > 0 {
+            let fraction = format!("{:09}", nanos);
+            format!("{}.{}s", secs, fraction.trim_end_matches('0'))
+        } else if nanos.is_multiple_of(1_000_000) {
+            format!("{}ms", nanos / 1_000_000)
+        } else if nanos.is_multiple_of(1_000) {
+            format!("{}us", nanos / 1_000)
+        } else {
+            format!("{}ns", nanos)
+        }
+    }
+
+    struct DurationVisitor;
+
+    impl Visitor<'_> for DurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a duration like `1.5s` or `500ms`")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let split = v
+                .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+                .unwrap_or(v.len());
+            let (number, unit) = v.split_at(split);
+            let nanos_per_unit: u128 = match unit {
+                "s" => u128::from(NANOS_PER_SEC),
+                "ms" => 1_000_000,
+                "us" => 1_000,
+                "ns" => 1,
+                "" => return Err(E::invalid_value(Unexpected::Str(v), &self)),
+                _ => {
+                    return Err(E::custom(format_args!(
+                        "unknown duration unit `{}`, expected one of `s`, `ms`, `us`, `ns`",
+                        unit,
+                    )));
+                }
+            };
+            parse_nanos(number, nanos_per_unit)
+                .and_then(|total| {
+                    let secs = u64::try_from(total / u128::from(NANOS_PER_SEC)).ok()?;
+                    let nanos = (total % u128::from(NANOS_PER_SEC)) as u32;
+                    Some(Duration::new(secs, nanos))
+                })
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+
+    // Parses a decimal number of units exactly, returning None if it is
+    // malformed, overflows, or is more precise than a nanosecond.
+    fn parse_nanos(number: &str, nanos_per_unit: u128) -> Option<u128> {
+        let (whole, fraction) = match number.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (number, ""),
+        };
+        if whole.is_empty() || fraction.contains('.') || number.ends_with('.') {
+            return None;
+        }
+        let mut total = whole.parse::<u128>().ok()?.checked_mul(nanos_per_unit)?;
+        let mut scale = nanos_per_unit;
+        for digit in fraction.bytes() {
+            if !scale.is_multiple_of(10) {
+                return None;
+            }
+            scale /= 10;
+            total = total.checked_add(u128::from(digit - b'0') * scale)?;
+        }
+        Some(total)
+    }
+}
+
+/// Serialize/deserialize a set, such as a `HashSet<T>` or `BTreeSet<T>`, as a
+/// YAML `!!set`: a mapping tagged `!!set` whose keys are the members and whose
+/// values are null.
+///
+/// Deserializing accepts both a `!!set` and a plain sequence of members.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::BTreeSet;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Config {
+///     #[serde(with = "serde_yaml::with::set")]
+///     features: BTreeSet<String>,
+/// }
+///
+/// fn main() {
+///     let object = Config {
+///         features: BTreeSet::from(["json".to_owned(), "tls".to_owned()]),
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "features: !!set\n  json: null\n  tls: null\n");
+///
+///     let deserialized: Config = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+///
+///     let deserialized: Config = serde_yaml::from_str("features: [json, tls]").unwrap();
+///     assert_eq!(object, deserialized);
+/// }
+/// ```
+pub mod set {
+    use crate::value::set::SET_TAG;
+    use crate::value::Tag;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeMap, Serializer};
+
+    /// Serializes the members of `set`, in its iteration order, as the keys
+    /// of a mapping tagged `!!set`.
+    pub fn serialize<'a, T, S>(set: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized,
+        &'a T: IntoIterator,
+        <&'a T as IntoIterator>::Item: Serialize,
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&SetTag, &Members(set))?;
+        map.end()
+    }
+
+    /// Deserializes a set from either a `!!set` or a sequence of members.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+
+    struct SetTag;
+
+    impl Serialize for SetTag {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(&Tag::new(SET_TAG))
+        }
+    }
+
+    struct Members<'a, T: ?Sized>(&'a T);
+
+    impl<'a, T> Serialize for Members<'a, T>
+    where
+        T: ?Sized,
+        &'a T: IntoIterator,
+        <&'a T as IntoIterator>::Item: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_map(self.0.into_iter().map(|member| (member, ())))
+        }
+    }
+}
+
+/// Serialize/deserialize a sequence of key-value pairs, such as a
+/// `Vec<(K, V)>`, as a YAML `!!omap`: a sequence tagged `!!omap` whose elements
+/// are mappings with a single entry.
+///
+/// Deserializing accepts an `!!omap` as well as a plain sequence of pairs. An
+/// `!!omap` can also be deserialized into a map type without this module.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Config {
+///     #[serde(with = "serde_yaml::with::omap")]
+///     steps: Vec<(String, u32)>,
+/// }
+///
+/// fn main() {
+///     let object = Config {
+///         steps: vec![("build".to_owned(), 2), ("test".to_owned(), 1)],
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "steps: !!omap\n- build: 2\n- test: 1\n");
+///
+///     let deserialized: Config = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+/// }
+/// ```
+pub mod omap {
+    use crate::value::omap::OMAP_TAG;
+    use crate::value::Tag;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeMap, Serializer};
+
+    /// Serializes `entries`, in order, as a sequence tagged `!!omap` of mappings
+    /// with a single entry.
+    pub fn serialize<K, V, S>(entries: &[(K, V)], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&OmapTag, &Entries(entries))?;
+        map.end()
+    }
+
+    /// Deserializes the entries from either an `!!omap` or a sequence of pairs.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+
+    struct OmapTag;
+
+    impl Serialize for OmapTag {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(&Tag::new(OMAP_TAG))
+        }
+    }
+
+    struct Entries<'a, K, V>(&'a [(K, V)]);
+
+    impl<K, V> Serialize for Entries<'_, K, V>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self.0.iter().map(|(key, value)| Entry(key, value)))
+        }
+    }
+
+    struct Entry<'a, K, V>(&'a K, &'a V);
+
+    impl<K, V> Serialize for Entry<'_, K, V>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(self.0, self.1)?;
+            map.end()
+        }
+    }
+}
+
+/// Serialize/deserialize a `Path` or `PathBuf` as a plain YAML string that is
+/// the same on every platform.
+///
+/// Paths are written with `/` as the separator, also on Windows, and a path
+/// that is not valid UTF-8 is an error. Use [`path::lossy`](self::path::lossy)
+/// to write such paths with invalid sequences replaced by `U+FFFD` instead.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+/// use std::path::PathBuf;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Config {
+///     #[serde(with = "serde_yaml::with::path")]
+///     cache: PathBuf,
+/// }
+///
+/// fn main() {
+///     let object = Config {
+///         cache: PathBuf::from("target/cache"),
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "cache: target/cache\n");
+///
+///     let deserialized: Config = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+/// }
+/// ```
+pub mod path {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Error, Serializer};
+    use std::borrow::Cow;
+    use std::path::{Path, PathBuf};
+
+    /// Serializes `path` as a string with `/` as the separator. A path that is
+    /// not valid UTF-8 is an error.
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: ?Sized + AsRef<Path>,
+        S: Serializer,
+    {
+        match path.as_ref().to_str() {
+            Some(string) => serializer.serialize_str(&normalize(string)),
+            None => Err(S::Error::custom("path contains invalid UTF-8 characters")),
+        }
+    }
+
+    /// Deserializes a path from a string.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(PathBuf::from)
+    }
+
+    #[cfg(windows)]
+    fn normalize(path: &str) -> Cow<'_, str> {
+        Cow::Owned(path.replace('\\', "/"))
+    }
+
+    #[cfg(not(windows))]
+    fn normalize(path: &str) -> Cow<'_, str> {
+        Cow::Borrowed(path)
+    }
+
+    /// Like [`path`](super::path), but a path that is not valid UTF-8 is
+    /// written in lossy form instead of being an error.
+    ///
+    /// ```
+    /// # use serde_derive::Serialize;
+    /// use serde::Serialize;
+    /// use std::path::PathBuf;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     #[serde(with = "serde_yaml::with::path::lossy")]
+    ///     cache: PathBuf,
+    /// }
+    ///
+    /// let object = Config {
+    ///     cache: PathBuf::from("target/cache"),
+    /// };
+    /// assert_eq!(serde_yaml::to_string(&object).unwrap(), "cache: target/cache\n");
+    /// ```
+    pub mod lossy {
+        use serde::ser::Serializer;
+        use std::path::Path;
+
+        pub use super::deserialize;
+
+        /// Serializes `path` as a string with `/` as the separator, replacing
+        /// invalid UTF-8 sequences with `U+FFFD`.
+        pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            P: ?Sized + AsRef<Path>,
+            S: Serializer,
+        {
+            let string = path.as_ref().to_string_lossy();
+            serializer.serialize_str(&super::normalize(&string))
+        }
+    }
+}
+
+/// Serialize/deserialize a `Result<T, E>` as a YAML map containing one entry,
+/// `Ok` or `Err`, whose value is the content.
+///
+/// This is the externally tagged form that [`singleton_map`] produces for any
+/// enum, pinned to `Result`. Unlike the default `!Ok`/`!Err` tags, it is
+/// ordinary YAML data that consumers in other languages can read without
+/// handling tags, and it does not depend on the serializer's
+/// [`EnumStyle`](crate::EnumStyle).
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Report {
+///     #[serde(with = "serde_yaml::with::result")]
+///     build: Result<u32, String>,
+///     #[serde(with = "serde_yaml::with::result")]
+///     test: Result<u32, String>,
+/// }
+///
+/// fn main() {
+///     let object = Report {
+///         build: Ok(12),
+///         test: Err("2 failed".to_owned()),
+///     };
+///
+///     let yaml = serde_yaml::to_string(&object).unwrap();
+///     assert_eq!(yaml, "build:\n  Ok: 12\ntest:\n  Err: 2 failed\n");
+///
+///     let deserialized: Report = serde_yaml::from_str(&yaml).unwrap();
+///     assert_eq!(object, deserialized);
+/// }
+/// ```
+pub mod result {
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    /// Serializes `result` as a map with the single key `Ok` or `Err`.
+    pub fn serialize<T, E, S>(result: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        E: Serialize,
+        S: Serializer,
+    {
+        super::singleton_map::serialize(result, serializer)
+    }
+
+    /// Deserializes a result from a map with the single key `Ok` or `Err`.
+    pub fn deserialize<'de, T, E, D>(deserializer: D) -> Result<Result<T, E>, D::Error>
+    where
+        T: Deserialize<'de>,
+        E: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::singleton_map::deserialize(deserializer)
+    }
+}
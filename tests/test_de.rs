@@ -7,9 +7,11 @@
 )]
 
 use indoc::indoc;
+use serde::{Deserialize as _, Serialize as _};
 use serde_derive::Deserialize;
-use serde_yaml::{Deserializer, Number, Value};
-use std::collections::BTreeMap;
+use serde_yaml::{Deserializer, Number, Serializer, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Debug;
 
 fn test_de<T>(yaml: &str, expected: &T)
@@ -69,6 +71,37 @@ fn test_borrowed() {
     test_de_no_value(yaml, &expected);
 }
 
+#[test]
+fn test_borrowed_keys() {
+    #[derive(Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct Key<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    let yaml = indoc! {r#"
+        plain: 1
+        'quoted': 2
+        "esc\taped": 3
+        'it''s': 4
+    "#};
+
+    let map: BTreeMap<&str, u8> = serde_yaml::from_str("{plain: 1, 'quoted': 2}").unwrap();
+    assert_eq!(map["plain"], 1);
+
+    let map: BTreeMap<Key, u8> = serde_yaml::from_str(yaml).unwrap();
+    let keys: Vec<(&str, bool)> = map
+        .keys()
+        .map(|key| (&*key.0, matches!(key.0, Cow::Borrowed(_))))
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            ("esc\taped", false),
+            ("it's", false),
+            ("plain", true),
+            ("quoted", true),
+        ],
+    );
+}
+
 #[test]
 fn test_alias() {
     let yaml = indoc! {"
@@ -356,6 +389,63 @@ fn test_byte_order_mark() {
     test_de(yaml, &expected);
 }
 
+#[test]
+fn test_byte_order_mark_utf16() {
+    let mut expected = BTreeMap::new();
+    expected.insert("key".to_owned(), "välue".to_owned());
+
+    let utf8 = "\u{feff}key: välue\n";
+    let deserialized: BTreeMap<String, String> = serde_yaml::from_slice(utf8.as_bytes()).unwrap();
+    assert_eq!(expected, deserialized);
+
+    let utf16le: Vec<u8> = utf8.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let deserialized: BTreeMap<String, String> = serde_yaml::from_slice(&utf16le).unwrap();
+    assert_eq!(expected, deserialized);
+
+    let utf16be: Vec<u8> = utf8.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let deserialized: BTreeMap<String, String> =
+        serde_yaml::from_reader(utf16be.as_slice()).unwrap();
+    assert_eq!(expected, deserialized);
+}
+
+#[test]
+fn test_from_reader_one() {
+    let input = b"a: 1\n...\n# comment\n---\nb: [2, 3]\n--- {not yaml\n";
+
+    // The count runs through the line that ends the document, and the rest
+    // is left unread.
+    let mut rdr = &input[..];
+    let (first, len): (BTreeMap<String, i32>, usize) =
+        serde_yaml::from_reader_one(&mut rdr).unwrap();
+    assert_eq!(first, BTreeMap::from([("a".to_owned(), 1)]));
+    assert_eq!(len, 9);
+    assert_eq!(rdr, &input[len..]);
+
+    // A `---` line only ends a document that has started.
+    let (second, len): (Value, usize) = serde_yaml::from_reader_one(&mut rdr).unwrap();
+    assert_eq!(second, serde_yaml::from_str::<Value>("b: [2, 3]").unwrap());
+    assert_eq!(len, 38);
+    assert_eq!(rdr, b"");
+
+    // The whole input when there is a single document.
+    let input = b"- x\n- y\n";
+    let (seq, len): (Vec<String>, usize) = serde_yaml::from_reader_one(&input[..]).unwrap();
+    assert_eq!(seq, ["x", "y"]);
+    assert_eq!(len, input.len());
+
+    let result = serde_yaml::from_reader_one::<_, Vec<String>>(&b"[x, "[..]);
+    assert!(result.is_err());
+
+    // The count is of bytes of UTF-16 input, not of its UTF-8 transcoding.
+    let utf16le: Vec<u8> = "\u{feff}\u{e9}: 1\n...\nrest\n"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let (first, len): (Value, usize) = serde_yaml::from_reader_one(&utf16le[..]).unwrap();
+    assert_eq!(first, serde_yaml::from_str::<Value>("\u{e9}: 1").unwrap());
+    assert_eq!(&utf16le[len..len + 2], b"r\0");
+}
+
 #[test]
 fn test_bomb() {
     #[derive(Debug, Deserialize, PartialEq)]
@@ -414,6 +504,11 @@ fn test_numbers() {
         ("0b10", "2"),
         ("+0b10", "2"),
         ("-0b10", "-2"),
+        ("0xFF_FF", "65535"),
+        ("0o7_7", "63"),
+        ("-0b1010_0101", "-165"),
+        ("1_000_000", "1000000"),
+        ("-1_000", "-1000"),
         ("127", "127"),
         ("+127", "127"),
         ("-127", "-127"),
@@ -439,7 +534,8 @@ fn test_numbers() {
     // NOT numbers.
     let cases = [
         "0127", "+0127", "-0127", "++.inf", "+-.inf", "++1", "+-1", "-+1", "--1", "0x+1", "0x-1",
-        "-0x+1", "-0x-1", "++0x1", "+-0x1", "-+0x1", "--0x1",
+        "-0x+1", "-0x-1", "++0x1", "+-0x1", "-+0x1", "--0x1", "0xGG", "0o8", "0b2", "_1", "1_",
+        "1__0", "0_127", "0x_",
     ];
     for yaml in &cases {
         let value = serde_yaml::from_str::<Value>(yaml).unwrap();
@@ -450,6 +546,55 @@ fn test_numbers() {
     }
 }
 
+#[test]
+fn test_float_special_values() {
+    let cases = [
+        (".inf", f64::INFINITY),
+        (".Inf", f64::INFINITY),
+        (".INF", f64::INFINITY),
+        (".iNf", f64::INFINITY),
+        ("+.inf", f64::INFINITY),
+        ("+.Inf", f64::INFINITY),
+        ("+.INF", f64::INFINITY),
+        ("-.inf", f64::NEG_INFINITY),
+        ("-.Inf", f64::NEG_INFINITY),
+        ("-.INF", f64::NEG_INFINITY),
+        ("-.InF", f64::NEG_INFINITY),
+        (".nan", f64::NAN),
+        (".NaN", f64::NAN),
+        (".NAN", f64::NAN),
+        (".nAn", f64::NAN),
+        ("+.nan", f64::NAN),
+        ("+.NaN", f64::NAN),
+    ];
+    for &(yaml, expected) in &cases {
+        let value = serde_yaml::from_str::<Value>(yaml).unwrap();
+        let float = value
+            .as_f64()
+            .unwrap_or_else(|| panic!("expected float. input={:?}", yaml));
+        assert!(
+            float == expected || (float.is_nan() && expected.is_nan()),
+            "input={:?}, result={:?}",
+            yaml,
+            float,
+        );
+        assert_eq!(
+            serde_yaml::from_str::<f64>(yaml).unwrap().is_nan(),
+            expected.is_nan()
+        );
+
+        // Strings that read as these floats are quoted on output.
+        let string = serde_yaml::to_string(yaml).unwrap();
+        assert_eq!(string, format!("'{}'\n", yaml));
+    }
+
+    // NOT floats.
+    for yaml in ["-.nan", "inf", "nan", ".infinity", "+-.nan"] {
+        let value = serde_yaml::from_str::<Value>(yaml).unwrap();
+        assert_eq!(value, Value::String(yaml.to_owned()));
+    }
+}
+
 #[test]
 fn test_nan() {
     // There is no negative NaN in YAML.
@@ -498,6 +643,16 @@ fn test_stateful() {
     }
 }
 
+#[test]
+fn test_char() {
+    test_de("a", &'a');
+    test_de("'5'", &'5');
+    test_de("5", &'5');
+    test_de("'\u{e9}'", &'\u{e9}');
+    test_de("\"\\U0001F389\"", &'\u{1f389}');
+    test_de("[x, 'y', \"\u{1f389}\"]", &vec!['x', 'y', '\u{1f389}']);
+}
+
 #[test]
 fn test_ignore_tag() {
     #[derive(Deserialize, Debug, PartialEq)]
@@ -622,6 +777,42 @@ fn test_python_safe_dump() {
     test_de(yaml, &expected);
 }
 
+#[test]
+fn test_explicit_core_tags() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Data {
+        string: String,
+        int: u32,
+        float: f64,
+        boolean: bool,
+        nothing: Option<String>,
+    }
+
+    let yaml = indoc! {r#"
+        string: !!str 42
+        int: !!int "0x10"
+        float: !!float '1.5'
+        boolean: !!bool "true"
+        nothing: !!null "~"
+    "#};
+
+    let expected = Data {
+        string: "42".to_owned(),
+        int: 16,
+        float: 1.5,
+        boolean: true,
+        nothing: None,
+    };
+    test_de(yaml, &expected);
+
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(value["string"], Value::String("42".to_owned()));
+    assert_eq!(value["int"], Value::Number(16.into()));
+    assert_eq!(value["nothing"], Value::Null);
+
+    assert!(serde_yaml::from_str::<u32>("!!str 42").is_err());
+}
+
 #[test]
 fn test_tag_resolution() {
     // https://yaml.org/spec/1.2.2/#1032-tag-resolution
@@ -688,6 +879,139 @@ fn test_tag_resolution() {
     test_de(yaml, &expected);
 }
 
+#[test]
+fn test_yaml_1_1_bools() {
+    let yaml = indoc! {"
+        - yes
+        - Yes
+        - yEs
+        - on
+        - ON
+        - no
+        - NO
+        - off
+        - Off
+        - true
+        - 'yes'
+        - y
+        - n
+        - !!bool on
+    "};
+    let de = Deserializer::from_str(yaml).yaml_1_1_bools();
+    let value = Value::deserialize(de).unwrap();
+    let expected = Value::Sequence(vec![
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(true),
+        Value::Bool(false),
+        Value::Bool(false),
+        Value::Bool(false),
+        Value::Bool(false),
+        Value::Bool(true),
+        Value::String("yes".to_owned()),
+        Value::String("y".to_owned()),
+        Value::String("n".to_owned()),
+        Value::Bool(true),
+    ]);
+    assert_eq!(value, expected);
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Flags {
+        enabled: bool,
+        label: String,
+    }
+    let yaml = "enabled: off\nlabel: on\n";
+    let de = Deserializer::from_str(yaml).yaml_1_1_bools();
+    let expected = Flags {
+        enabled: false,
+        label: "on".to_owned(),
+    };
+    assert_eq!(Flags::deserialize(de).unwrap(), expected);
+    assert!(serde_yaml::from_str::<Flags>(yaml).is_err());
+
+    let strings = vec!["yes", "On", "OFF", "no", "y"];
+    let yaml = serde_yaml::to_string(&strings).unwrap();
+    assert_eq!(yaml, "- yes\n- On\n- OFF\n- no\n- y\n");
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_yaml_1_1_bools(true);
+    strings.serialize(&mut ser).unwrap();
+    let yaml = String::from_utf8(buffer).unwrap();
+    assert_eq!(yaml, "- 'yes'\n- 'On'\n- 'OFF'\n- 'no'\n- y\n");
+    let de = Deserializer::from_str(&yaml).yaml_1_1_bools();
+    assert_eq!(Vec::<String>::deserialize(de).unwrap(), strings);
+}
+
+#[test]
+fn test_untagged_enum() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    #[serde(untagged)]
+    enum Node {
+        Leaf(u32),
+        Named { name: String, children: Vec<Node> },
+        List(Vec<Node>),
+    }
+    let yaml = indoc! {"
+        - 1
+        - name: root
+          children:
+          - 2
+          - [3, 4]
+          - name: inner
+            children: []
+    "};
+    let expected = Node::List(vec![
+        Node::Leaf(1),
+        Node::Named {
+            name: "root".to_owned(),
+            children: vec![
+                Node::Leaf(2),
+                Node::List(vec![Node::Leaf(3), Node::Leaf(4)]),
+                Node::Named {
+                    name: "inner".to_owned(),
+                    children: Vec::new(),
+                },
+            ],
+        },
+    ]);
+    test_de(yaml, &expected);
+}
+
+#[test]
+fn test_tag_resolver() {
+    let yaml = indoc! {"
+        plain: !upper abc
+        nested: !wrap
+          inner: !upper def
+        seq: !wrap [1, 2]
+        other: !other ghi
+    "};
+    let de = Deserializer::from_str(yaml).with_tag_resolver(|tag, value| match tag {
+        "upper" => Ok(Value::String(value.as_str().unwrap().to_uppercase())),
+        "wrap" => Ok(Value::Sequence(vec![value])),
+        _ => Ok(value),
+    });
+    let value = Value::deserialize(de).unwrap();
+    let expected = indoc! {"
+        plain: ABC
+        nested:
+        - inner: DEF
+        seq:
+        - - 1
+          - 2
+        other: ghi
+    "};
+    assert_eq!(serde_yaml::to_string(&value).unwrap(), expected);
+
+    let de = Deserializer::from_str(yaml)
+        .with_tag_resolver(|tag, _value| Err(serde::de::Error::custom(tag)));
+    let err = Value::deserialize(de).unwrap_err();
+    assert_eq!(err.to_string(), "plain: upper at line 1 column 8");
+}
+
 #[test]
 fn test_parse_number() {
     let n = "111".parse::<Number>().unwrap();
@@ -715,3 +1039,376 @@ fn test_parse_number() {
     let err = " 1 ".parse::<Number>().unwrap_err();
     assert_eq!(err.to_string(), "failed to parse YAML number");
 }
+
+#[test]
+fn test_parser_events() {
+    use serde_yaml::parser::{Event, Events, ScalarStyle};
+
+    let yaml = "k: &a 'v'\nl: [*a]\n";
+    let events: Vec<(Event, usize, usize)> = Events::from_str(yaml)
+        .map(|result| {
+            let (event, location) = result.unwrap();
+            (event, location.line(), location.column())
+        })
+        .collect();
+
+    let scalar = |value: &str, anchor: Option<&str>, style| Event::Scalar {
+        anchor: anchor.map(str::to_owned),
+        tag: None,
+        value: value.to_owned(),
+        style,
+    };
+    let expected = vec![
+        (Event::StreamStart, 1, 1),
+        (Event::DocumentStart, 1, 1),
+        (
+            Event::MappingStart {
+                anchor: None,
+                tag: None,
+            },
+            1,
+            1,
+        ),
+        (scalar("k", None, ScalarStyle::Plain), 1, 1),
+        (scalar("v", Some("a"), ScalarStyle::SingleQuoted), 1, 4),
+        (scalar("l", None, ScalarStyle::Plain), 2, 1),
+        (
+            Event::SequenceStart {
+                anchor: None,
+                tag: None,
+            },
+            2,
+            4,
+        ),
+        (
+            Event::Alias {
+                anchor: "a".to_owned(),
+            },
+            2,
+            5,
+        ),
+        (Event::SequenceEnd, 2, 7),
+        (Event::MappingEnd, 3, 1),
+        (Event::DocumentEnd, 3, 1),
+        (Event::StreamEnd, 3, 1),
+    ];
+    assert_eq!(events, expected);
+
+    let mut events = Events::from_str("[1, 2");
+    let mut error = None;
+    for result in &mut events {
+        if let Err(err) = result {
+            error = Some(err);
+        }
+    }
+    assert!(error.unwrap().is_eof());
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn test_scalar_locations() {
+    let yaml = indoc! {"
+        name: demo
+        servers:
+          - host: a
+            port: 80
+          - host: b
+        ? [complex]
+        : value
+        alias: &x 1
+        copy: *x
+    "};
+    let (value, locations) = serde_yaml::from_str_with_locations(yaml).unwrap();
+    assert_eq!(value["servers"][1]["host"], "b");
+
+    let locations: Vec<(&str, usize, usize)> = locations
+        .iter()
+        .map(|(path, location)| (path.as_str(), location.line(), location.column()))
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            ("name", 1, 7),
+            ("servers[0].host", 3, 11),
+            ("servers[0].port", 4, 11),
+            ("servers[1].host", 5, 11),
+            ("?", 7, 3),
+            ("alias", 8, 8),
+        ],
+    );
+
+    let (_, locations) = serde_yaml::from_str_with_locations("42").unwrap();
+    assert_eq!(locations[0].0, ".");
+}
+
+#[test]
+fn test_expand_tabs() {
+    let yaml = "jobs:\n\tbuild:\n\t\tname: \"a\tb\"\n\t\tscript: |\n\t\t  \techo\n\t\t  done\n\ttest: {}\n";
+    assert!(Value::deserialize(Deserializer::from_str(yaml)).is_err());
+
+    let value = Value::deserialize(Deserializer::from_str(yaml).expand_tabs(2)).unwrap();
+    assert_eq!(value["jobs"]["build"]["name"], "a\tb");
+    assert_eq!(value["jobs"]["build"]["script"], "\techo\ndone\n");
+    assert_eq!(value["jobs"]["test"], Value::Mapping(Default::default()));
+
+    let value = Value::deserialize(Deserializer::from_str("a: 1\n").expand_tabs(4)).unwrap();
+    assert_eq!(value["a"], 1);
+}
+
+#[test]
+fn test_set() {
+    let yaml = indoc! {"
+        ? b
+        ? a
+        ? c
+    "};
+    let yaml = format!("!!set\n{}", yaml);
+    let expected = BTreeSet::from(["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+    test_de(&yaml, &expected);
+    test_de(
+        &yaml,
+        &HashSet::from(["a".to_owned(), "b".to_owned(), "c".to_owned()]),
+    );
+    test_de(
+        "!!set {x: ~, y: null}",
+        &vec!["x".to_owned(), "y".to_owned()],
+    );
+
+    let value: Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(value.as_set().unwrap(), ["b", "a", "c"]);
+
+    let error = serde_yaml::from_str::<BTreeSet<String>>("!!set {a: 1}").unwrap_err();
+    let message = error.to_string();
+    assert!(
+        message.contains("invalid value: string \"1\", expected null"),
+        "{}",
+        message
+    );
+    let location = error.location().unwrap();
+    assert_eq!((location.line(), location.column()), (1, 11));
+}
+
+#[test]
+fn test_omap() {
+    let yaml = indoc! {"
+        !!omap
+        - b: 1
+        - a: 2
+    "};
+    let expected = vec![("b".to_owned(), 1), ("a".to_owned(), 2)];
+    test_de(yaml, &expected);
+    test_de(
+        yaml,
+        &BTreeMap::from([("a".to_owned(), 2), ("b".to_owned(), 1)]),
+    );
+
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let entries = value.as_omap().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(
+        (entries[0].0, entries[0].1),
+        (&Value::from("b"), &Value::from(1))
+    );
+
+    let error = serde_yaml::from_str::<Vec<(String, i32)>>("!!omap [{a: 1, b: 2}]").unwrap_err();
+    let message = error.to_string();
+    assert!(
+        message.contains("mapping with a single entry"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn test_byte_buf() {
+    #[derive(Debug, PartialEq)]
+    struct Bytes(Vec<u8>);
+
+    impl<'de> serde::Deserialize<'de> for Bytes {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct BytesVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+                type Value = Bytes;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("bytes")
+                }
+
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                    Ok(Bytes(v))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Data {
+        data: Bytes,
+    }
+
+    let yaml = "data: [0, 255, 0x0d, 7]\n";
+    let data: Data = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(data.data, Bytes(vec![0, 255, 13, 7]));
+
+    let bytes: Bytes = serde_yaml::from_str("a: &a [1, 2]\nb: *a\n")
+        .map(|mut map: BTreeMap<String, Bytes>| map.remove("b").unwrap())
+        .unwrap();
+    assert_eq!(bytes, Bytes(vec![1, 2]));
+
+    let error = serde_yaml::from_str::<Data>("data: [1, 256]\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "data: byte at index 1 is out of range 0..=255: 256 at line 1 column 7",
+    );
+    let error = serde_yaml::from_str::<Data>("data: [-1]\n").unwrap_err();
+    assert!(error.to_string().contains("index 0"), "{}", error);
+
+    // The base64 content of !!binary, from text and from a Value.
+    let yaml = "data: !!binary aGVs\n  bG8=\n";
+    let data: Data = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(data.data, Bytes(b"hello".to_vec()));
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let bytes: Bytes = serde::Deserialize::deserialize(&value["data"]).unwrap();
+    assert_eq!(bytes, Bytes(b"hello".to_vec()));
+    let data: Data = serde_yaml::from_value(value).unwrap();
+    assert_eq!(data.data, Bytes(b"hello".to_vec()));
+
+    let error = serde_yaml::from_str::<Data>("data: !!binary not*base64\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "data: invalid value: string \"not*base64\", expected base64 encoded !!binary data at line 1 column 7",
+    );
+}
+
+#[test]
+fn test_deny_unknown_tags() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Shape {
+        Circle { radius: u32 },
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Data {
+        name: String,
+        count: u32,
+        shape: Shape,
+    }
+
+    let yaml = indoc! {"
+        name: !!str demo
+        count: !!int 3
+        shape: !Circle
+          radius: 1
+    "};
+    let de = Deserializer::from_str(yaml).deny_unknown_tags();
+    let expected = Data {
+        name: "demo".to_owned(),
+        count: 3,
+        shape: Shape::Circle { radius: 1 },
+    };
+    assert_eq!(Data::deserialize(de).unwrap(), expected);
+
+    let yaml = "name: !env USER\ncount: 3\nshape: !Circle {radius: 1}\n";
+    let de = Deserializer::from_str(yaml).deny_unknown_tags();
+    let error = Data::deserialize(de).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "name: unknown tag `!env` at line 1 column 7"
+    );
+
+    let yaml = "items:\n- [1, !!set {a}]\n- !<tag:example.com,2024:x> {}\n";
+    assert!(Value::deserialize(Deserializer::from_str(yaml)).is_ok());
+    let de = Deserializer::from_str(yaml).deny_unknown_tags();
+    let error = Value::deserialize(de).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "items[1]: unknown tag `tag:example.com,2024:x` at line 3 column 3",
+    );
+}
+
+#[test]
+fn test_scalar_transform() {
+    fn interpolate(scalar: &str) -> Cow<'_, str> {
+        if scalar.contains("${") {
+            Cow::Owned(scalar.replace("${PORT}", "8080").replace("${NAME}", "web"))
+        } else {
+            Cow::Borrowed(scalar)
+        }
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Server {
+        name: String,
+        port: u16,
+        quoted: String,
+        block: String,
+    }
+
+    let yaml = indoc! {"
+        name: ${NAME}-1
+        port: ${PORT}
+        quoted: '${PORT}'
+        block: |
+          ${PORT}
+    "};
+    let de = Deserializer::from_str(yaml).with_scalar_transform(interpolate);
+    let expected = Server {
+        name: "web-1".to_owned(),
+        port: 8080,
+        quoted: "${PORT}".to_owned(),
+        block: "${PORT}\n".to_owned(),
+    };
+    assert_eq!(Server::deserialize(de).unwrap(), expected);
+
+    let yaml = "${NAME}: ${PORT}\n---\n- ${PORT}\n";
+    let de = Deserializer::from_str(yaml).with_scalar_transform(interpolate);
+    let values: Vec<Value> = de.map(|de| Value::deserialize(de).unwrap()).collect();
+    let expected: [Value; 2] = [
+        serde_yaml::from_str("web: 8080").unwrap(),
+        serde_yaml::from_str("[8080]").unwrap(),
+    ];
+    assert_eq!(values, expected);
+}
+
+#[test]
+fn test_all_scalars_as_strings() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Config {
+        version: String,
+        enabled: String,
+        missing: Option<String>,
+        empty: String,
+        count: u8,
+    }
+
+    let yaml = indoc! {"
+        version: 1.10
+        enabled: yes
+        missing: null
+        empty:
+        count: !!int 3
+    "};
+    let de = Deserializer::from_str(yaml).all_scalars_as_strings();
+    let expected = Config {
+        version: "1.10".to_owned(),
+        enabled: "yes".to_owned(),
+        missing: Some("null".to_owned()),
+        empty: String::new(),
+        count: 3,
+    };
+    assert_eq!(Config::deserialize(de).unwrap(), expected);
+
+    let de = Deserializer::from_str("{1: ~, true: [0x10, .inf]}").all_scalars_as_strings();
+    let value = Value::deserialize(de).unwrap();
+    let expected: Value = serde_yaml::from_str("{'1': '~', 'true': ['0x10', '.inf']}").unwrap();
+    assert_eq!(value, expected);
+
+    let de = Deserializer::from_str("42").all_scalars_as_strings();
+    assert!(u8::deserialize(de).is_err());
+}
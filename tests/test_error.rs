@@ -73,10 +73,30 @@ fn test_incorrect_nested_type() {
 
 #[test]
 fn test_empty() {
-    let expected = "EOF while parsing a value";
+    let expected = "empty document, expected a string";
     test_error::<String>("", expected);
 }
 
+#[test]
+fn test_empty_document() {
+    #[derive(Deserialize, Debug)]
+    pub enum E {
+        V,
+    }
+    for document in ["", "# comment", "\n\n"] {
+        let value: Value = serde_yaml::from_str(document).unwrap();
+        assert_eq!(value, Value::Null);
+
+        let error = serde_yaml::from_str::<u8>(document).unwrap_err();
+        assert!(error.is_eof());
+        assert_eq!(error.to_string(), "empty document, expected u8");
+
+        let error = serde_yaml::from_str::<E>(document).unwrap_err();
+        assert!(error.is_eof());
+        assert_eq!(error.to_string(), "empty document, expected enum E");
+    }
+}
+
 #[test]
 fn test_missing_field() {
     #[derive(Deserialize, Debug)]
@@ -122,7 +142,7 @@ fn test_ignored_unknown_anchor() {
 #[test]
 fn test_bytes() {
     let expected = "serialization and deserialization of bytes in YAML is not implemented";
-    test_error::<&[u8]>("...", expected);
+    test_error::<&[u8]>("a", expected);
 }
 
 #[test]
@@ -158,6 +178,61 @@ fn test_second_document_syntax_error() {
     assert_eq!(expected, result.unwrap_err().to_string());
 }
 
+#[test]
+fn test_best_effort() {
+    let yaml = indoc! {"
+        ---
+        0
+        ---
+        [1, 2]
+        ---
+        {a: [3,
+    "};
+    let (values, error) = serde_yaml::from_str_best_effort(yaml);
+    assert_eq!(values, [Value::from(0), Value::from(vec![1, 2])]);
+    let error = error.unwrap();
+    assert!(error.location().unwrap().line() >= 6);
+
+    let (values, error) = serde_yaml::from_str_best_effort("a: 1\n---\nb: 2\n");
+    assert_eq!(values.len(), 2);
+    assert!(error.is_none());
+
+    let (values, error) = serde_yaml::from_str_best_effort("]");
+    assert!(values.is_empty());
+    assert!(error.is_some());
+}
+
+#[test]
+fn test_char_length() {
+    let expected = "invalid length 2, expected a single character";
+    test_error::<char>("'\u{e9}\u{e9}'", expected);
+
+    #[derive(Deserialize, Debug)]
+    pub struct Data {
+        #[allow(dead_code)]
+        c: char,
+    }
+    let expected = "c: invalid length 0, expected a single character at line 1 column 4";
+    test_error::<Data>("c: ''", expected);
+}
+
+#[test]
+fn test_from_str_mapping() {
+    let mapping = serde_yaml::from_str_mapping("!Config {a: 1}").unwrap();
+    assert_eq!(mapping["a"], 1);
+    assert!(serde_yaml::from_str_mapping("").unwrap().is_empty());
+
+    for (yaml, found) in [
+        ("[a]", "sequence"),
+        ("text", "string"),
+        ("!Tag 1", "number"),
+    ] {
+        let error = serde_yaml::from_str_mapping(yaml).unwrap_err();
+        let expected = format!("expected a YAML mapping at the document root, found {found}");
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
 #[test]
 fn test_missing_enum_tag() {
     #[derive(Deserialize, Debug)]
@@ -168,7 +243,7 @@ fn test_missing_enum_tag() {
         "V": 16
         "other": 32
     "#};
-    let expected = "invalid type: map, expected a YAML tag starting with '!'";
+    let expected = "invalid length 2, expected map containing 1 entry";
     test_error::<E>(yaml, expected);
 }
 
@@ -320,7 +395,7 @@ fn test_short_tuple() {
         ---
         [0, 0]
     "};
-    let expected = "invalid length 2, expected a tuple of size 3 at line 2 column 1";
+    let expected = "expected 3 elements, found 2 at line 2 column 1";
     test_error::<(u8, u8, u8)>(yaml, expected);
 }
 
@@ -330,10 +405,37 @@ fn test_long_tuple() {
         ---
         [0, 0, 0]
     "};
-    let expected = "invalid length 3, expected sequence of 2 elements at line 2 column 1";
+    let expected = "expected 2 elements, found 3 at line 2 column 1";
     test_error::<(u8, u8)>(yaml, expected);
 }
 
+#[test]
+fn test_array_length() {
+    #[derive(Deserialize, Debug)]
+    pub struct S {
+        #[allow(dead_code)]
+        pub a: [i32; 2],
+        #[allow(dead_code)]
+        pub b: [i32; 3],
+    }
+
+    let yaml = "[1, 2]";
+    let expected = "expected 3 elements, found 2";
+    test_error::<[i32; 3]>(yaml, expected);
+
+    let yaml = indoc! {"
+        a: [1, 2]
+        b:
+        - 1
+        - [2, 3]
+        - {x: 4}
+        - &five 5
+        - *five
+    "};
+    let expected = "b: expected 3 elements, found 5 at line 3 column 1";
+    test_error::<S>(yaml, expected);
+}
+
 #[test]
 fn test_invalid_scalar_type() {
     #[derive(Deserialize, Debug)]
@@ -464,6 +566,57 @@ fn test_billion_laughs() {
     test_error::<BTreeMap<String, X>>(yaml, expected);
 }
 
+#[test]
+fn test_max_aliases() {
+    let yaml = indoc! {"
+        a: &a [lol, lol, lol, lol, lol, lol, lol, lol, lol]
+        b: &b [*a,*a,*a,*a,*a,*a,*a,*a,*a]
+        c: &c [*b,*b,*b,*b,*b,*b,*b,*b,*b]
+        d: &d [*c,*c,*c,*c,*c,*c,*c,*c,*c]
+        e: &e [*d,*d,*d,*d,*d,*d,*d,*d,*d]
+        f: &f [*e,*e,*e,*e,*e,*e,*e,*e,*e]
+        g: &g [*f,*f,*f,*f,*f,*f,*f,*f,*f]
+        h: &h [*g,*g,*g,*g,*g,*g,*g,*g,*g]
+        i: &i [*h,*h,*h,*h,*h,*h,*h,*h,*h]
+    "};
+    let error = Value::deserialize(Deserializer::from_str(yaml)).unwrap_err();
+    assert_eq!(error.to_string(), "repetition limit exceeded");
+
+    let yaml = indoc! {"
+        a: &a [lol, lol]
+        b: &b [*a, *a]
+        c: [*b, *b]
+    "};
+    let de = Deserializer::from_str(yaml).max_aliases(8);
+    assert!(Value::deserialize(de).is_ok());
+    let de = Deserializer::from_str(yaml).max_aliases(7);
+    let error = Value::deserialize(de).unwrap_err();
+    assert_eq!(error.to_string(), "repetition limit exceeded");
+}
+
+#[test]
+fn test_max_anchor_name_len() {
+    let long = "x".repeat(1025);
+    let yaml = format!("a: &{long} 1\n");
+    let error = Value::deserialize(Deserializer::from_str(&yaml)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "anchor name is longer than 1024 bytes at line 1 column 4",
+    );
+
+    let yaml = indoc! {"
+        a: &ok 1
+        b: *ok
+        c: [*ok, *toolong]
+    "};
+    let de = Deserializer::from_str(yaml).max_anchor_name_len(6);
+    let error = Value::deserialize(de).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "anchor name is longer than 6 bytes at line 3 column 10",
+    );
+}
+
 #[test]
 fn test_duplicate_keys() {
     let yaml = indoc! {"
@@ -498,3 +651,88 @@ fn test_duplicate_keys() {
     let expected = "duplicate entry in YAML map at line 2 column 1";
     test_error::<Value>(yaml, expected);
 }
+
+#[test]
+fn test_invalid_utf16() {
+    let expected = "input starts with a UTF-16 byte order mark but is not valid UTF-16";
+
+    // Unpaired surrogate.
+    let yaml = b"\xFF\xFEk\x00:\x00 \x00\x00\xD8";
+    let result = serde_yaml::from_slice::<Value>(yaml);
+    assert_eq!(expected, result.unwrap_err().to_string());
+
+    // Odd number of bytes.
+    let yaml = b"\xFE\xFF\x00k\x00";
+    let result = serde_yaml::from_slice::<Value>(yaml);
+    assert_eq!(expected, result.unwrap_err().to_string());
+}
+
+#[test]
+fn test_error_category() {
+    let truncated = ["[1, 2", "k: 'unterminated", "{a: 1,\n"];
+    for yaml in truncated {
+        let err = serde_yaml::from_str::<Value>(yaml).unwrap_err();
+        assert!(err.is_eof(), "{:?}", yaml);
+        assert!(!err.is_syntax() && !err.is_data() && !err.is_io());
+    }
+
+    let err = serde_yaml::from_str::<u8>("").unwrap_err();
+    assert!(err.is_eof());
+
+    let err = serde_yaml::from_str::<Value>("@invalid").unwrap_err();
+    assert!(err.is_syntax());
+    assert!(!err.is_eof());
+
+    let err = serde_yaml::from_str::<Value>("a\n---\nb").unwrap_err();
+    assert!(err.is_syntax());
+
+    let err = serde_yaml::from_str::<u8>("[1, 2]").unwrap_err();
+    assert!(err.is_data());
+
+    struct Fail;
+    impl std::io::Read for Fail {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("fail"))
+        }
+    }
+    let err = serde_yaml::from_reader::<_, Value>(Fail).unwrap_err();
+    assert!(err.is_io());
+}
+
+#[test]
+fn test_io_error_source() {
+    struct WouldBlock;
+    impl std::io::Read for WouldBlock {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WouldBlock.into())
+        }
+    }
+    let err = serde_yaml::from_reader::<_, Value>(WouldBlock).unwrap_err();
+    assert_eq!(
+        err.io_error().unwrap().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+
+    let source = std::error::Error::source(&err).unwrap();
+    let io_error = source.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io_error.kind(), std::io::ErrorKind::WouldBlock);
+
+    let err = serde_yaml::from_str::<u8>("[1, 2]").unwrap_err();
+    assert!(err.io_error().is_none());
+}
+
+#[test]
+fn test_source_snippet() {
+    let yaml = ">\n@";
+    let err = serde_yaml::from_str::<Value>(yaml).unwrap_err();
+    let expected = format!("{err}\n  |\n2 | @\n  | ^");
+    assert_eq!(err.with_source_snippet(yaml), expected);
+
+    let yaml = "a: [1,\t@]";
+    let err = serde_yaml::from_str::<Value>(yaml).unwrap_err();
+    let expected = format!("{err}\n  |\n1 | a: [1,\t@]\n  |       \t^");
+    assert_eq!(err.with_source_snippet(yaml), expected);
+
+    let err = serde_yaml::from_str::<u8>("").unwrap_err();
+    assert_eq!(err.with_source_snippet(""), err.to_string());
+}
@@ -8,10 +8,19 @@
 use indoc::indoc;
 use serde::ser::SerializeMap;
 use serde_derive::{Deserialize, Serialize};
-use serde_yaml::{Mapping, Number, Value};
-use std::collections::BTreeMap;
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::{
+    BoolStyle, EmptyStyle, EnumStyle, KeyCase, Mapping, NullStyle, Number, QuoteStyle, Serializer,
+    Value,
+};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
+use std::io;
 use std::iter;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
 
 fn test_serde<T>(thing: &T, yaml: &str)
 where
@@ -296,18 +305,21 @@ fn test_strings_needing_quote() {
         integer: String,
         void: String,
         leading_zeros: String,
+        grouped_digits: String,
     }
     let thing = Struct {
         boolean: "true".to_owned(),
         integer: "1".to_owned(),
         void: "null".to_owned(),
         leading_zeros: "007".to_owned(),
+        grouped_digits: "1_000".to_owned(),
     };
     let yaml = indoc! {"
         boolean: 'true'
         integer: '1'
         void: 'null'
         leading_zeros: '007'
+        grouped_digits: '1_000'
     "};
     test_serde(&thing, yaml);
 }
@@ -481,6 +493,39 @@ fn test_tagged_map_value() {
     test_serde(&thing, yaml);
 }
 
+#[test]
+fn test_tagged_value() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Shape {
+        origin: TaggedValue,
+    }
+    let mut point = Mapping::new();
+    point.insert("x".into(), 1.into());
+    point.insert("y".into(), 2.into());
+    let thing = Shape {
+        origin: TaggedValue {
+            tag: Tag::new("!Point"),
+            value: Value::Mapping(point),
+        },
+    };
+    let yaml = indoc! {"
+        origin: !Point
+          x: 1
+          y: 2
+    "};
+    test_serde(&thing, yaml);
+
+    let thing = TaggedValue {
+        tag: Tag::new("Point"),
+        value: Value::Sequence(vec![1.into(), 2.into()]),
+    };
+    assert_eq!(serde_yaml::to_string(&thing).unwrap(), "!Point\n- 1\n- 2\n");
+
+    // A bare tag is a string.
+    let yaml = serde_yaml::to_string(&Tag::new("Point")).unwrap();
+    assert_eq!(yaml, "'!Point'\n");
+}
+
 #[test]
 fn test_value() {
     #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -541,6 +586,52 @@ fn test_mapping() {
     test_serde(&thing, yaml);
 }
 
+#[test]
+fn test_complex_keys() {
+    let mut thing = Mapping::new();
+    thing.insert(Value::Sequence(vec!["a".into(), "b".into()]), "c".into());
+    let yaml = indoc! {"
+        ? - a
+          - b
+        : c
+    "};
+    test_serde(&thing, yaml);
+
+    let mut key = Mapping::new();
+    key.insert("x".into(), 1.into());
+    thing.insert(Value::Mapping(key), Value::Null);
+    let yaml = indoc! {"
+        ? - a
+          - b
+        : c
+        ? x: 1
+        : null
+    "};
+    test_serde(&thing, yaml);
+
+    let mut thing = BTreeMap::new();
+    thing.insert(vec![1, 2], 3);
+    let yaml = indoc! {"
+        ? - 1
+          - 2
+        : 3
+    "};
+    test_serde(&thing, yaml);
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    enum Key {
+        Unit,
+        Pair(u8, u8),
+        Struct { x: u8 },
+    }
+    let mut thing = BTreeMap::new();
+    thing.insert(Key::Unit, 0);
+    thing.insert(Key::Pair(1, 2), 1);
+    thing.insert(Key::Struct { x: 3 }, 2);
+    let yaml = serde_yaml::to_string(&thing).unwrap();
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+}
+
 #[test]
 fn test_long_string() {
     #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -558,3 +649,899 @@ fn test_long_string() {
 
     test_serde(&thing, yaml);
 }
+
+#[test]
+fn test_null_style() {
+    fn to_string<T: serde::Serialize>(thing: &T, style: NullStyle) -> String {
+        let mut buffer = Vec::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_null_style(style);
+        thing.serialize(&mut ser).unwrap();
+        drop(ser);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    let mut thing = BTreeMap::new();
+    thing.insert(None, vec![None, Some(1)]);
+    thing.insert(Some(2), vec![]);
+    thing.insert(Some(3), vec![None]);
+
+    let yaml = to_string(&thing, NullStyle::Keyword);
+    assert_eq!(yaml, "null:\n- null\n- 1\n2: []\n3:\n- null\n");
+
+    let yaml = to_string(&thing, NullStyle::Tilde);
+    assert_eq!(yaml, "~:\n- ~\n- 1\n2: []\n3:\n- ~\n");
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+
+    let yaml = to_string(&thing, NullStyle::Empty);
+    assert_eq!(yaml, "~:\n-\n- 1\n2: []\n3:\n-\n");
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+
+    let mut value = Mapping::new();
+    value.insert("key".into(), Value::Null);
+    let yaml = to_string(&value, NullStyle::Empty);
+    assert_eq!(yaml, "key:\n");
+    assert_eq!(
+        Value::Mapping(value),
+        serde_yaml::from_str::<Value>(&yaml).unwrap()
+    );
+}
+
+#[test]
+fn test_bool_style() {
+    fn to_string<T: serde::Serialize>(thing: &T, style: BoolStyle) -> String {
+        let mut buffer = Vec::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_bool_style(style);
+        thing.serialize(&mut ser).unwrap();
+        drop(ser);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    let thing = BTreeMap::from([("a", true), ("b", false)]);
+    let cases = [
+        (BoolStyle::TrueFalse, "a: true\nb: false\n"),
+        (BoolStyle::Capitalized, "a: True\nb: False\n"),
+        (BoolStyle::YesNo, "a: yes\nb: no\n"),
+        (BoolStyle::OnOff, "a: on\nb: off\n"),
+    ];
+    for (style, expected) in cases {
+        let yaml = to_string(&thing, style);
+        assert_eq!(yaml, expected);
+
+        let de = serde_yaml::Deserializer::from_str(&yaml).yaml_1_1_bools();
+        let deserialized: BTreeMap<&str, bool> = serde::Deserialize::deserialize(de).unwrap();
+        assert_eq!(deserialized, thing);
+    }
+
+    let yaml = to_string(&thing, BoolStyle::Capitalized);
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+}
+
+#[test]
+fn test_string_quoting() {
+    fn to_string<T: serde::Serialize>(thing: &T, quoting: QuoteStyle) -> String {
+        let mut buffer = Vec::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_string_quoting(quoting);
+        thing.serialize(&mut ser).unwrap();
+        drop(ser);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    let mut thing = BTreeMap::new();
+    thing.insert("a", "plain");
+    thing.insert("b", "true");
+
+    let yaml = to_string(&thing, QuoteStyle::Auto);
+    assert_eq!(yaml, "a: plain\nb: 'true'\n");
+
+    let yaml = to_string(&thing, QuoteStyle::Single);
+    assert_eq!(yaml, "'a': 'plain'\n'b': 'true'\n");
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+
+    let yaml = to_string(&thing, QuoteStyle::Double);
+    assert_eq!(yaml, "\"a\": \"plain\"\n\"b\": \"true\"\n");
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+
+    let yaml = to_string(&thing, QuoteStyle::Plain);
+    assert_eq!(yaml, "a: plain\nb: 'true'\n");
+
+    let multiline = vec!["x\ny".to_owned()];
+    let yaml = to_string(&multiline, QuoteStyle::Auto);
+    assert_eq!(yaml, "- |-\n  x\n  y\n");
+    let yaml = to_string(&multiline, QuoteStyle::Double);
+    assert_eq!(yaml, "- \"x\\ny\"\n");
+    let yaml = to_string(&multiline, QuoteStyle::Plain);
+    assert!(!yaml.contains('|'));
+    assert_eq!(
+        multiline,
+        serde_yaml::from_str::<Vec<String>>(&yaml).unwrap()
+    );
+}
+
+#[test]
+fn test_explicit_core_tags() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Data {
+        name: String,
+        enabled: bool,
+        count: u32,
+        ratio: f64,
+        parent: Option<u32>,
+        tags: Vec<i8>,
+    }
+
+    let thing = Data {
+        name: "demo".to_owned(),
+        enabled: true,
+        count: 3,
+        ratio: 0.5,
+        parent: None,
+        tags: vec![-1],
+    };
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_explicit_core_tags(true);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let yaml = indoc! {"
+        name: demo
+        enabled: !!bool true
+        count: !!int 3
+        ratio: !!float 0.5
+        parent: !!null null
+        tags:
+        - !!int -1
+    "};
+    let output = String::from_utf8(buffer).unwrap();
+    assert_eq!(output, yaml);
+    assert_eq!(thing, serde_yaml::from_str::<Data>(&output).unwrap());
+
+    let value = serde_yaml::to_value(&thing).unwrap();
+    assert_eq!(value, serde_yaml::from_str::<Value>(&output).unwrap());
+}
+
+#[test]
+fn test_sorted_keys() {
+    #[derive(Serialize)]
+    struct Inner {
+        z: u8,
+        y: u8,
+    }
+
+    #[derive(Serialize)]
+    enum Enum {
+        Variant { d: u8, c: u8 },
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        name: &'static str,
+        inner: Inner,
+        map: HashMap<&'static str, u8>,
+        single: BTreeMap<&'static str, u8>,
+        variant: Enum,
+    }
+
+    let thing = Outer {
+        name: "x",
+        inner: Inner { z: 1, y: 2 },
+        map: HashMap::from([("b", 1), ("a", 2), ("c", 3)]),
+        single: BTreeMap::from([("k", 1)]),
+        variant: Enum::Variant { d: 1, c: 2 },
+    };
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_sorted_keys(true);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let expected = indoc! {"
+        inner:
+          y: 2
+          z: 1
+        map:
+          a: 2
+          b: 1
+          c: 3
+        name: x
+        single:
+          k: 1
+        variant: !Variant
+          c: 2
+          d: 1
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+    // Other settings still apply to the sorted entries.
+    #[derive(Serialize)]
+    enum Unsorted {
+        A(u8),
+    }
+
+    #[derive(Serialize)]
+    struct Settings {
+        z: Unsorted,
+        #[serde(with = "serde_yaml::with::flow")]
+        y: BTreeMap<u16, u8>,
+    }
+
+    let thing = Settings {
+        z: Unsorted::A(1),
+        y: BTreeMap::from([(10, 1), (9, 2)]),
+    };
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_sorted_keys(true);
+    ser.set_enum_style(EnumStyle::Map);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let expected = indoc! {"
+        y: {9: 2, 10: 1}
+        z:
+          A: 1
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+    // Keys of different types sort in a fixed order, with NaN last.
+    let thing: Value = serde_yaml::from_str("{.nan: a, b: b, 1.5: c, 2: d, ~: e}").unwrap();
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_sorted_keys(true);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let expected = indoc! {"
+        null: e
+        2: d
+        1.5: c
+        .nan: a
+        b: b
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+}
+
+#[test]
+fn test_spaced() {
+    let mut thing = BTreeMap::new();
+    thing.insert("a", vec!["x".to_owned(), "y\n".to_owned()]);
+    thing.insert("b", vec!["z\n\n".to_owned()]);
+    thing.insert("c", vec![]);
+
+    let yaml = serde_yaml::to_string_spaced(&thing).unwrap();
+    let expected = indoc! {"
+        a:
+        - x
+        - |
+          y
+
+        b:
+        - |+
+          z
+
+        c: []
+    "};
+    assert_eq!(yaml, expected);
+    assert_eq!(thing, serde_yaml::from_str(&yaml).unwrap());
+
+    let thing = vec![vec![1, 2], vec![3]];
+    let yaml = serde_yaml::to_string_spaced(&thing).unwrap();
+    assert_eq!(yaml, "- - 1\n  - 2\n\n- - 3\n");
+    assert_eq!(thing, serde_yaml::from_str::<Vec<Vec<u8>>>(&yaml).unwrap());
+}
+
+#[test]
+fn test_flow_field() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Inner {
+        a: Vec<u8>,
+        b: BTreeMap<String, u8>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Outer {
+        #[serde(with = "serde_yaml::with::flow")]
+        inner: Inner,
+        #[serde(with = "serde_yaml::with::flow")]
+        scalar: u8,
+        after: Vec<u8>,
+    }
+
+    let thing = Outer {
+        inner: Inner {
+            a: vec![1, 2],
+            b: BTreeMap::from([("k".to_owned(), 3)]),
+        },
+        scalar: 4,
+        after: vec![5],
+    };
+    let yaml = indoc! {"
+        inner: {a: [1, 2], b: {k: 3}}
+        scalar: 4
+        after:
+        - 5
+    "};
+    assert_eq!(serde_yaml::to_string(&thing).unwrap(), yaml);
+    assert_eq!(thing, serde_yaml::from_str(yaml).unwrap());
+}
+
+#[test]
+fn test_double_option() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Patch {
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_yaml::with::double_option"
+        )]
+        name: Option<Option<String>>,
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_yaml::with::double_option"
+        )]
+        port: Option<Option<u16>>,
+    }
+
+    let thing = Patch {
+        name: Some(None),
+        port: None,
+    };
+    test_serde(&thing, "name: null\n");
+
+    let thing = Patch {
+        name: Some(Some("demo".to_owned())),
+        port: Some(Some(80)),
+    };
+    test_serde(&thing, "name: demo\nport: 80\n");
+
+    let thing: Patch = serde_yaml::from_str("port: ~\n").unwrap();
+    assert_eq!(
+        thing,
+        Patch {
+            name: None,
+            port: Some(None),
+        },
+    );
+}
+
+#[test]
+fn test_duration() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Timeout {
+        #[serde(with = "serde_yaml::with::duration")]
+        timeout: Duration,
+    }
+
+    let cases = [
+        (Duration::from_secs(2), "timeout: 2s\n"),
+        (Duration::from_millis(1500), "timeout: 1.5s\n"),
+        (Duration::from_millis(500), "timeout: 500ms\n"),
+        (Duration::from_micros(250), "timeout: 250us\n"),
+        (Duration::from_nanos(7), "timeout: 7ns\n"),
+        (Duration::ZERO, "timeout: 0s\n"),
+    ];
+    for (timeout, yaml) in cases {
+        test_serde(&Timeout { timeout }, yaml);
+    }
+
+    let thing: Timeout = serde_yaml::from_str("timeout: 0.25ms\n").unwrap();
+    assert_eq!(thing.timeout, Duration::from_micros(250));
+
+    for yaml in [
+        "timeout: 5m\n",
+        "timeout: 5\n",
+        "timeout: 1.5ns\n",
+        "timeout: .5s\n",
+    ] {
+        serde_yaml::from_str::<Timeout>(yaml).unwrap_err();
+    }
+}
+
+#[test]
+fn test_omap_field() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Pipeline {
+        #[serde(with = "serde_yaml::with::omap")]
+        steps: Vec<(String, u32)>,
+    }
+
+    let thing = Pipeline {
+        steps: vec![("test".to_owned(), 1), ("build".to_owned(), 2)],
+    };
+    let yaml = indoc! {"
+        steps: !!omap
+        - test: 1
+        - build: 2
+    "};
+    test_serde(&thing, yaml);
+}
+
+#[test]
+fn test_result_field() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Outcome {
+        #[serde(with = "serde_yaml::with::result")]
+        ok: Result<Vec<u8>, String>,
+        #[serde(with = "serde_yaml::with::result")]
+        err: Result<Vec<u8>, String>,
+    }
+
+    let thing = Outcome {
+        ok: Ok(vec![1, 2]),
+        err: Err("failed".to_owned()),
+    };
+    let yaml = indoc! {"
+        ok:
+          Ok:
+          - 1
+          - 2
+        err:
+          Err: failed
+    "};
+    test_serde(&thing, yaml);
+}
+
+#[test]
+fn test_path() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        #[serde(with = "serde_yaml::with::path")]
+        cache: PathBuf,
+        #[serde(with = "serde_yaml::with::path::lossy")]
+        log: PathBuf,
+    }
+
+    let thing = Config {
+        cache: PathBuf::from("target/cache"),
+        log: PathBuf::from("/var/log/app.log"),
+    };
+    let yaml = indoc! {"
+        cache: target/cache
+        log: /var/log/app.log
+    "};
+    test_serde(&thing, yaml);
+}
+
+#[test]
+fn test_set_field() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        #[serde(with = "serde_yaml::with::set")]
+        features: BTreeSet<String>,
+        #[serde(with = "serde_yaml::with::set")]
+        ports: BTreeSet<u16>,
+    }
+
+    let thing = Config {
+        features: BTreeSet::from(["tls".to_owned()]),
+        ports: BTreeSet::from([443, 80]),
+    };
+    let yaml = indoc! {"
+        features: !!set
+          tls: null
+        ports: !!set
+          80: null
+          443: null
+    "};
+    test_serde(&thing, yaml);
+}
+
+#[test]
+fn test_enum_style_map() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Enum {
+        Unit,
+        Newtype(Box<Enum>),
+        Tuple(u8, u8),
+        Struct { x: u8 },
+    }
+
+    let thing = vec![
+        Enum::Unit,
+        Enum::Newtype(Box::new(Enum::Tuple(1, 2))),
+        Enum::Struct { x: 3 },
+    ];
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_enum_style(EnumStyle::Map);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let yaml = indoc! {"
+        - Unit
+        - Newtype:
+            Tuple:
+            - 1
+            - 2
+        - Struct:
+            x: 3
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), yaml);
+    assert_eq!(thing, serde_yaml::from_str::<Vec<Enum>>(yaml).unwrap());
+
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(thing, serde_yaml::from_value::<Vec<Enum>>(value).unwrap());
+}
+
+#[test]
+fn test_number_format() {
+    let thing: [Value; 8] = [
+        0.into(),
+        123.into(),
+        1234.into(),
+        1_234_567.into(),
+        (-1000).into(),
+        u64::MAX.into(),
+        i64::MIN.into(),
+        1234.5.into(),
+    ];
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_digit_grouping(true);
+    ser.set_plus_sign(true);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let yaml = indoc! {"
+        - +0
+        - +123
+        - +1_234
+        - +1_234_567
+        - -1_000
+        - +18_446_744_073_709_551_615
+        - -9_223_372_036_854_775_808
+        - +1234.5
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), yaml);
+    assert_eq!(serde_yaml::from_str::<Vec<Value>>(yaml).unwrap(), thing);
+    assert_eq!(
+        serde_yaml::from_str::<u64>("+18_446_744_073_709_551_615").unwrap(),
+        u64::MAX
+    );
+}
+
+#[test]
+fn test_trailing_newline() {
+    let things = [
+        serde_yaml::to_string(&1).unwrap(),
+        serde_yaml::to_string("multi\nline").unwrap(),
+        serde_yaml::to_string(&vec!["a", "b"]).unwrap(),
+        serde_yaml::to_string(&BTreeMap::from([("k", Vec::<u8>::new())])).unwrap(),
+    ];
+    for yaml in things {
+        assert!(
+            yaml.ends_with('\n') && !yaml.ends_with("\n\n"),
+            "{:?}",
+            yaml
+        );
+    }
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_trailing_newline(false);
+    serde::Serialize::serialize(&BTreeMap::from([("k", 107)]), &mut ser).unwrap();
+    serde::Serialize::serialize(&BTreeMap::from([("j", 74)]), &mut ser).unwrap();
+    drop(ser);
+    assert_eq!(buffer, b"k: 107\n---\nj: 74");
+}
+
+#[test]
+fn test_directives() {
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_yaml_version_directive(true);
+    ser.add_tag_directive("!y!", "tag:yaml.org,2002:").unwrap();
+    let widget = TaggedValue {
+        tag: Tag::new("!!widget"),
+        value: Value::from(1),
+    };
+    let thing = BTreeMap::from([("a", Value::Tagged(Box::new(widget)))]);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+    let yaml = String::from_utf8(buffer).unwrap();
+    let expected = indoc! {"
+        %YAML 1.2
+        %TAG !y! tag:yaml.org,2002:
+        ---
+        a: !y!widget 1
+    "};
+    assert_eq!(yaml, expected);
+
+    let value: Value = serde_yaml::from_str(&yaml).unwrap();
+    let shorthand: Value = serde_yaml::from_str("a: !!widget 1").unwrap();
+    assert_eq!(value, shorthand);
+
+    let mut ser = Serializer::new(Vec::new());
+    ser.add_tag_directive("y", "tag:yaml.org,2002:").unwrap();
+    serde::Serialize::serialize(&thing, &mut ser).unwrap_err();
+
+    // libyaml would write the `!` of a local prefix as `%21`, making it global.
+    let mut ser = Serializer::new(Vec::new());
+    let error = ser.add_tag_directive("!e!", "!example.com/").unwrap_err();
+    let expected = "%TAG prefix \"!example.com/\" would be percent-escaped";
+    assert_eq!(error.to_string(), expected);
+}
+
+#[test]
+fn test_unicode_escapes() {
+    let thing = [
+        "caf\u{e9}",
+        "5 \u{20ac}",
+        "\u{1f600}",
+        "multi\nline \u{fc}ber",
+        "ascii",
+    ];
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_unicode_escapes(true);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+    let yaml = String::from_utf8(buffer).unwrap();
+    assert!(yaml.is_ascii(), "{}", yaml);
+    assert!(yaml.contains("\"5 \\u20AC\""), "{}", yaml);
+
+    let deserialized: Vec<String> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(deserialized, thing);
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_unicode_escapes(false);
+    serde::Serialize::serialize(&thing[0], &mut ser).unwrap();
+    drop(ser);
+    assert_eq!(buffer, "caf\u{e9}\n".as_bytes());
+}
+
+#[test]
+fn test_to_vec() {
+    let mut thing = BTreeMap::new();
+    thing.insert("name".to_owned(), vec!["caf\u{e9}".to_owned()]);
+    thing.insert("empty".to_owned(), Vec::new());
+
+    let bytes = serde_yaml::to_vec(&thing).unwrap();
+    assert_eq!(bytes, serde_yaml::to_string(&thing).unwrap().into_bytes());
+
+    let deserialized: BTreeMap<String, Vec<String>> = serde_yaml::from_slice(&bytes).unwrap();
+    assert_eq!(deserialized, thing);
+}
+
+#[test]
+fn test_empty_style() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Empty {}
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Enum {
+        Variant {},
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        list: Vec<u8>,
+        map: BTreeMap<String, u8>,
+        nested: Empty,
+        variant: Enum,
+    }
+
+    let thing = Config {
+        list: Vec::new(),
+        map: BTreeMap::new(),
+        nested: Empty {},
+        variant: Enum::Variant {},
+    };
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_empty_style(EmptyStyle::Explicit);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let yaml = indoc! {"
+        list: !!seq []
+        map: !!map {}
+        nested: !!map {}
+        variant: !Variant {}
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), yaml);
+    assert_eq!(thing, serde_yaml::from_str::<Config>(yaml).unwrap());
+
+    let flow = serde_yaml::to_string(&thing).unwrap();
+    assert_eq!(flow, yaml.replace("!!seq ", "").replace("!!map ", ""));
+    let explicit: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(explicit, serde_yaml::from_str::<Value>(&flow).unwrap());
+}
+
+#[test]
+fn test_key_case() {
+    #[derive(Serialize)]
+    struct Inner {
+        retry_count: u8,
+    }
+
+    #[derive(Serialize)]
+    struct Config {
+        log_level: &'static str,
+        inner: Inner,
+        labels: BTreeMap<&'static str, &'static str>,
+        ports: BTreeMap<u16, &'static str>,
+    }
+
+    let thing = Config {
+        log_level: "info",
+        inner: Inner { retry_count: 3 },
+        labels: BTreeMap::from([("HTTPServer", "keep_as_is"), ("app-name", "demo")]),
+        ports: BTreeMap::from([(80, "http")]),
+    };
+
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_key_case(KeyCase::Camel);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+
+    let yaml = indoc! {"
+        logLevel: info
+        inner:
+          retryCount: 3
+        labels:
+          httpServer: keep_as_is
+          appName: demo
+        ports:
+          80: http
+    "};
+    assert_eq!(String::from_utf8(buffer).unwrap(), yaml);
+
+    // Sorting uses the keys as written.
+    let thing = BTreeMap::from([("zB", 1), ("z_a", 2)]);
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_sorted_keys(true);
+    ser.set_key_case(KeyCase::Kebab);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+    assert_eq!(buffer, b"z-a: 2\nz-b: 1\n");
+
+    // Keys that convert to the same key are an error rather than a duplicate.
+    let thing = BTreeMap::from([("fooBar", 1), ("foo_bar", 2)]);
+    let mut ser = Serializer::new(io::sink());
+    ser.set_key_case(KeyCase::Snake);
+    let error = serde::Serialize::serialize(&thing, &mut ser).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "more than one key of a map is written as \"foo_bar\""
+    );
+
+    // The same key in different maps is fine.
+    let inner = BTreeMap::from([("fooBar", 1)]);
+    let thing = BTreeMap::from([("fooBar", inner.clone()), ("x", inner)]);
+    let mut buffer = Vec::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_key_case(KeyCase::Snake);
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    drop(ser);
+    assert_eq!(buffer, b"foo_bar:\n  foo_bar: 1\nx:\n  foo_bar: 1\n");
+}
+
+#[test]
+fn test_streaming_sequence() {
+    struct CountingWriter(Rc<Cell<usize>>);
+
+    impl io::Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.set(self.0.get() + buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Numbers {
+        len: usize,
+        written: Rc<Cell<usize>>,
+    }
+
+    impl serde::Serialize for Numbers {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.collect_seq((0..self.len).inspect(|i| {
+                // Each element takes at least 4 bytes, like `- 0\n`. All but
+                // a bounded amount of what came before must have been written.
+                assert!(self.written.get() + 20_000 >= 4 * i);
+            }))
+        }
+    }
+
+    let written = Rc::new(Cell::new(0));
+    let thing = Numbers {
+        len: 200_000,
+        written: Rc::clone(&written),
+    };
+    let mut ser = Serializer::new(CountingWriter(Rc::clone(&written)));
+    serde::Serialize::serialize(&thing, &mut ser).unwrap();
+    ser.into_inner().unwrap();
+    assert!(written.get() > 4 * thing.len);
+}
+
+#[test]
+fn test_anchor_dedup() {
+    fn to_string<T: serde::Serialize>(thing: &T, dedup: bool) -> String {
+        let mut buffer = Vec::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_anchor_dedup(dedup);
+        thing.serialize(&mut ser).unwrap();
+        drop(ser);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    let value: Value = serde_yaml::from_str(indoc! {"
+        a: !T {x: [1, 2], y: []}
+        b: {x: [1, 2], y: []}
+        c: [[1, 2], !T {x: [1, 2], y: []}, [1]]
+        d: [1]
+    "})
+    .unwrap();
+    let expected = indoc! {"
+        a: &id001 !T
+          x: &id002
+          - 1
+          - 2
+          y: []
+        b:
+          x: *id002
+          y: []
+        c:
+        - *id002
+        - *id001
+        - &id003
+          - 1
+        d: *id003
+    "};
+    let yaml = to_string(&value, true);
+    assert_eq!(yaml, expected);
+    assert_eq!(value, serde_yaml::from_str::<Value>(&yaml).unwrap());
+    assert_eq!(
+        to_string(&value, false),
+        serde_yaml::to_string(&value).unwrap()
+    );
+
+    #[derive(Serialize)]
+    struct Service {
+        ports: Vec<u16>,
+    }
+    let services = BTreeMap::from([
+        (
+            "api",
+            Service {
+                ports: vec![80, 443],
+            },
+        ),
+        (
+            "web",
+            Service {
+                ports: vec![80, 443],
+            },
+        ),
+    ]);
+    let expected = indoc! {"
+        api: &id001
+          ports:
+          - 80
+          - 443
+        web: *id001
+    "};
+    assert_eq!(to_string(&services, true), expected);
+}
@@ -8,7 +8,10 @@ use indoc::indoc;
 use serde::de::IntoDeserializer;
 use serde::Deserialize;
 use serde_derive::{Deserialize, Serialize};
-use serde_yaml::{Number, Value};
+use serde_yaml::mapping::Entry;
+use serde_yaml::value::{Binary, MergeOrder, Schema, Tag, TaggedValue, ValueKind};
+use serde_yaml::{yaml, Mapping, Number, Sequence, Value};
+use std::collections::BTreeMap;
 
 #[test]
 fn test_nan() {
@@ -25,6 +28,75 @@ fn test_nan() {
     assert_eq!(pos_nan, different_pos_nan);
 }
 
+#[test]
+fn test_non_finite() {
+    let value: Value = serde_yaml::from_str("[.nan, .inf, -.inf, 1.5, 1]").unwrap();
+    let checks: Vec<_> = value
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(|v| match v {
+            Value::Number(n) => (n.is_nan(), n.is_infinite(), n.is_finite()),
+            _ => panic!("expected number, got {:?}", v),
+        })
+        .collect();
+    assert_eq!(
+        checks,
+        [
+            (true, false, false),
+            (false, true, false),
+            (false, true, false),
+            (false, false, true),
+            (false, false, true),
+        ],
+    );
+}
+
+#[test]
+fn test_number_from_f64() {
+    assert_eq!(Number::from_f64(1.5), Some(Number::from(1.5)));
+    assert_eq!(Number::from_f64(-0.0), Some(Number::from(-0.0)));
+    assert_eq!(
+        Number::from_f64(f64::NEG_INFINITY),
+        Some(Number::from(f64::NEG_INFINITY))
+    );
+    assert_eq!(Number::from_f64(f64::NAN), None);
+    assert!(Number::from(f64::NAN).is_nan());
+}
+
+#[test]
+fn test_number_numeric_eq() {
+    let one = Value::Number(Number::from(1));
+    let one_float = Value::Number(Number::from(1.0));
+    assert_ne!(one, one_float);
+
+    let mut mapping = Mapping::new();
+    mapping.insert(one.clone(), Value::from("int"));
+    mapping.insert(one_float.clone(), Value::from("float"));
+    assert_eq!(mapping.len(), 2);
+    assert_eq!(mapping[&one], "int");
+    assert_eq!(mapping[&one_float], "float");
+
+    let cases = [
+        (Number::from(1), Number::from(1.0), true),
+        (Number::from(0), Number::from(-0.0), true),
+        (Number::from(-5), Number::from(-5.0), true),
+        (Number::from(i64::MIN), Number::from(i64::MIN as f64), true),
+        (Number::from(1), Number::from(1.5), false),
+        (Number::from(u64::MAX), Number::from(u64::MAX as f64), false),
+        (Number::from(-1), Number::from(1.0), false),
+        (Number::from(1), Number::from(f64::INFINITY), false),
+        (Number::from(1), Number::from(f64::NAN), false),
+        (Number::from(f64::NAN), Number::from(f64::NAN), true),
+        (Number::from(2), Number::from(2), true),
+        (Number::from(2.5), Number::from(2.5), true),
+    ];
+    for (a, b, expected) in cases {
+        assert_eq!(a.numeric_eq(&b), expected, "{} vs {}", a, b);
+        assert_eq!(b.numeric_eq(&a), expected, "{} vs {}", b, a);
+    }
+}
+
 #[test]
 fn test_digits() {
     let num_string = serde_yaml::from_str::<Value>("01").unwrap();
@@ -98,6 +170,73 @@ fn test_merge() {
     }
 }
 
+#[test]
+fn test_merge_depth() {
+    let yaml = indoc! {"
+        base: &base
+          retries: 3
+        <<: *base
+        jobs:
+          <<: *base
+          deploy:
+            - <<: *base
+    "};
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.apply_merge_depth(0).unwrap();
+    assert_eq!(value["retries"], 3);
+    assert!(value["jobs"].contains_key("<<"));
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.apply_merge_depth(1).unwrap();
+    assert_eq!(value["jobs"]["retries"], 3);
+    assert!(value["jobs"]["deploy"][0].contains_key("<<"));
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.apply_merge_depth(3).unwrap();
+    assert_eq!(value["jobs"]["deploy"][0]["retries"], 3);
+
+    let mut all: Value = serde_yaml::from_str(yaml).unwrap();
+    all.apply_merge().unwrap();
+    assert_eq!(value, all);
+}
+
+#[test]
+fn test_merge_order() {
+    let yaml = indoc! {"
+        a: &a {x: 1, y: 2}
+        b: &b {z: 3, x: 4}
+        c:
+          w: 0
+          y: 5
+          <<: [*a, *b]
+        d:
+          w: 0
+    "};
+
+    let keys = |value: &Value| -> Vec<String> {
+        let mapping = value.as_mapping().unwrap();
+        mapping
+            .keys()
+            .map(|k| k.as_str().unwrap().to_owned())
+            .collect()
+    };
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.apply_merge_with(MergeOrder::Append).unwrap();
+    assert_eq!(keys(&value["c"]), ["w", "y", "x", "z"]);
+    let mut appended: Value = serde_yaml::from_str(yaml).unwrap();
+    appended.apply_merge().unwrap();
+    assert_eq!(value, appended);
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.apply_merge_with(MergeOrder::Prepend).unwrap();
+    assert_eq!(keys(&value["c"]), ["x", "y", "z", "w"]);
+    assert_eq!(keys(&value["d"]), ["w"]);
+    assert_eq!(value["c"]["x"], 1);
+    assert_eq!(value["c"]["y"], 5);
+}
+
 #[test]
 fn test_debug() {
     let yaml = indoc! {"
@@ -151,3 +290,1162 @@ fn test_tagged() {
     let serialized = serde_yaml::to_value(&value).unwrap();
     assert_eq!(value, serialized);
 }
+
+#[test]
+fn test_tagged_into_enum() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Enum {
+        Unit,
+        Newtype(u8),
+        NewtypeString(String),
+        Tuple(u8, u8),
+        Struct { x: u8 },
+    }
+
+    fn tagged(tag: &str, value: Value) -> Value {
+        Value::Tagged(Box::new(TaggedValue {
+            tag: Tag::new(tag),
+            value,
+        }))
+    }
+
+    let cases = [
+        (tagged("!Unit", Value::Null), Enum::Unit),
+        (tagged("!Newtype", Value::from(1)), Enum::Newtype(1)),
+        (tagged("Newtype", Value::from(1)), Enum::Newtype(1)),
+        (
+            tagged("!NewtypeString", Value::from("s")),
+            Enum::NewtypeString("s".to_owned()),
+        ),
+        (
+            tagged(
+                "!Tuple",
+                Value::Sequence(vec![Value::from(1), Value::from(2)]),
+            ),
+            Enum::Tuple(1, 2),
+        ),
+        (
+            tagged("!Struct", serde_yaml::from_str("x: 1").unwrap()),
+            Enum::Struct { x: 1 },
+        ),
+    ];
+
+    for (value, expected) in cases {
+        assert_eq!(Enum::deserialize(&value).unwrap(), expected);
+        assert_eq!(serde_yaml::from_value::<Enum>(value).unwrap(), expected);
+    }
+
+    let unknown = tagged("!Other", Value::Null);
+    assert!(serde_yaml::from_value::<Enum>(unknown).is_err());
+}
+
+#[test]
+fn test_from_value_ref() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Server<'a> {
+        host: &'a str,
+        port: u16,
+    }
+
+    let value: Value = serde_yaml::from_str("host: localhost\nport: 8080\n").unwrap();
+
+    assert!(serde_yaml::from_value_ref::<Vec<u16>>(&value).is_err());
+    let server: Server = serde_yaml::from_value_ref(&value).unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost",
+            port: 8080,
+        },
+    );
+    assert_eq!(value["port"], 8080);
+}
+
+#[test]
+fn test_value_serializer() {
+    #[derive(Serialize)]
+    struct Unit;
+
+    #[derive(Serialize)]
+    struct Newtype(u8);
+
+    #[derive(Serialize)]
+    struct Tuple(u8, char);
+
+    #[derive(Serialize)]
+    enum Enum {
+        Unit,
+        Newtype(u8),
+        Tuple(u8, u8),
+        Struct { a: u8 },
+    }
+
+    #[derive(Serialize)]
+    struct Everything {
+        unit: (),
+        unit_struct: Unit,
+        boolean: bool,
+        int: i8,
+        big: i128,
+        small: u128,
+        float: f32,
+        ch: char,
+        string: &'static str,
+        none: Option<u8>,
+        some: Option<u8>,
+        newtype: Newtype,
+        tuple: (u8, bool),
+        tuple_struct: Tuple,
+        seq: Vec<u8>,
+        int_keys: BTreeMap<i32, bool>,
+        seq_keys: BTreeMap<Vec<u8>, u8>,
+        unit_variant: Enum,
+        newtype_variant: Enum,
+        tuple_variant: Enum,
+        struct_variant: Enum,
+    }
+
+    let thing = Everything {
+        unit: (),
+        unit_struct: Unit,
+        boolean: true,
+        int: -1,
+        big: i128::MIN,
+        small: 1,
+        float: 1.5,
+        ch: 'x',
+        string: "s",
+        none: None,
+        some: Some(1),
+        newtype: Newtype(2),
+        tuple: (3, false),
+        tuple_struct: Tuple(4, 'y'),
+        seq: vec![5],
+        int_keys: BTreeMap::from([(-1, true), (2, false)]),
+        seq_keys: BTreeMap::from([(vec![1, 2], 3)]),
+        unit_variant: Enum::Unit,
+        newtype_variant: Enum::Newtype(6),
+        tuple_variant: Enum::Tuple(7, 8),
+        struct_variant: Enum::Struct { a: 9 },
+    };
+
+    let yaml = indoc! {"
+        unit: null
+        unit_struct: null
+        boolean: true
+        int: -1
+        small: 1
+        float: 1.5
+        ch: x
+        string: s
+        none: null
+        some: 1
+        newtype: 2
+        tuple: [3, false]
+        tuple_struct: [4, y]
+        seq: [5]
+        int_keys: {-1: true, 2: false}
+        seq_keys:
+          ? [1, 2]
+          : 3
+        unit_variant: Unit
+        newtype_variant: !Newtype 6
+        tuple_variant: !Tuple [7, 8]
+        struct_variant: !Struct {a: 9}
+    "};
+    let mut expected: Value = serde_yaml::from_str(yaml).unwrap();
+    // Too big for a YAML integer.
+    expected["big"] = Value::String(i128::MIN.to_string());
+
+    let value = serde::Serialize::serialize(&thing, serde_yaml::value::Serializer).unwrap();
+    assert_eq!(value, expected);
+
+    let bytes = serde::Serializer::serialize_bytes(serde_yaml::value::Serializer, b"ab").unwrap();
+    assert_eq!(bytes, Value::Sequence(vec![97.into(), 98.into()]));
+}
+
+#[test]
+fn test_partialeq_symmetric() {
+    let string = Value::String("lorem".to_owned());
+    assert!(string == *"lorem");
+    assert!(*"lorem" == string);
+    assert!(string == "lorem");
+    assert!("lorem" == string);
+    let owned = "lorem".to_owned();
+    assert!(string == owned);
+    assert!(owned == string);
+    assert!(string != "ipsum");
+    assert!("ipsum" != string);
+
+    let boolean = Value::Bool(true);
+    assert!(boolean == true);
+    assert!(true == boolean);
+    assert!(false != boolean);
+
+    let int = Value::Number(Number::from(42));
+    assert!(int == 42i32);
+    assert!(42i32 == int);
+    assert!(int == 42u64);
+    assert!(42u64 == int);
+    assert!(42i32 == &int);
+    assert!(43i32 != int);
+
+    let float = Value::Number(Number::from(1.5));
+    assert!(float == 1.5f64);
+    assert!(1.5f64 == float);
+    assert!(2.5f64 != float);
+
+    assert!("42" != int);
+    assert!(42i32 != string);
+}
+
+#[test]
+fn test_flatten_keys() {
+    let yaml = indoc! {"
+        server:
+          host: localhost
+          ports:
+          - 80
+          - 443
+        empty: {}
+        enabled: true
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let flat = value.flatten_keys("__");
+
+    let expected = indoc! {"
+        server__host: localhost
+        server__ports__0: 80
+        server__ports__1: 443
+        empty: {}
+        enabled: true
+    "};
+    assert_eq!(serde_yaml::to_string(&flat).unwrap(), expected);
+    assert_eq!(Value::unflatten_keys(flat, "__"), value);
+}
+
+#[test]
+fn test_visit() {
+    let yaml = indoc! {"
+        name: demo
+        items:
+        - !Tag 1
+        - [2]
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+
+    let mut paths = Vec::new();
+    value.visit(|path, _node| paths.push(serde_yaml::to_string(path).unwrap()));
+    let expected = [
+        "[]\n",
+        "- name\n",
+        "- items\n",
+        "- items\n- 0\n",
+        "- items\n- 1\n",
+        "- items\n- 1\n- 0\n",
+    ];
+    assert_eq!(paths, expected);
+
+    let mut value = value;
+    value.visit_mut(|path, node| {
+        if path.len() == 2 {
+            *node = Value::Sequence(vec![node.clone(), Value::Null]);
+        } else if let Some(n) = node.as_u64() {
+            *node = Value::from(n * 10);
+        }
+    });
+    let expected = indoc! {"
+        name: demo
+        items:
+        - - 10
+          - null
+        - - - 20
+          - null
+    "};
+    assert_eq!(serde_yaml::to_string(&value).unwrap(), expected);
+}
+
+#[test]
+fn test_eq_unordered() {
+    let a: Value = serde_yaml::from_str("[[1, 2], {k: [x, y]}, !T [3, 4], 1]").unwrap();
+    let b: Value = serde_yaml::from_str("[1, {k: [y, x]}, [2, 1], !T [4, 3]]").unwrap();
+    assert_ne!(a, b);
+    assert!(a.eq_unordered(&b));
+    assert!(b.eq_unordered(&a));
+
+    let cases = [
+        ("[1, 1, 2]", "[1, 2, 2]"),
+        ("[1, 2]", "[1, 2, 3]"),
+        ("!T [1, 2]", "!U [2, 1]"),
+        ("{[1, 2]: x}", "{[2, 1]: x}"),
+        ("{a: [1]}", "{a: [1], b: [2]}"),
+    ];
+    for (a, b) in cases {
+        let a: Value = serde_yaml::from_str(a).unwrap();
+        let b: Value = serde_yaml::from_str(b).unwrap();
+        assert!(!a.eq_unordered(&b), "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_find_all() {
+    let yaml = indoc! {"
+        token: abc
+        nested:
+        - !Secret abd
+        - {key: xyz, other: abe}
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+
+    let found = value.find_all(|node| node.as_str().is_some_and(|s| s.starts_with("ab")));
+    let paths: Vec<String> = found
+        .iter()
+        .map(|(path, _node)| serde_yaml::to_string(path).unwrap())
+        .collect();
+    let expected = ["- token\n", "- nested\n- 0\n", "- nested\n- 1\n- other\n"];
+    assert_eq!(paths, expected);
+    assert_eq!(found[2].1, "abe");
+
+    assert!(value.find_all(|node| node.is_bool()).is_empty());
+}
+
+#[test]
+fn test_leaves() {
+    let yaml = indoc! {"
+        name: !Name demo
+        ports: [80, 443]
+        empty: {}
+        nested: {list: [[], [x]]}
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+
+    let leaves: Vec<(String, &Value)> = value
+        .leaves()
+        .map(|(path, leaf)| (serde_yaml::to_string(&path).unwrap(), leaf))
+        .collect();
+    let expected = [
+        (
+            "- name\n",
+            serde_yaml::from_str::<Value>("!Name demo").unwrap(),
+        ),
+        ("- ports\n- 0\n", Value::from(80)),
+        ("- ports\n- 1\n", Value::from(443)),
+        ("- nested\n- list\n- 1\n- 0\n", Value::from("x")),
+    ];
+    assert_eq!(leaves.len(), expected.len());
+    for ((path, leaf), (expected_path, expected_leaf)) in leaves.into_iter().zip(&expected) {
+        assert_eq!(path, *expected_path);
+        assert_eq!(leaf, expected_leaf);
+    }
+
+    let mut value = Value::Null;
+    for _ in 0..10_000 {
+        value = Value::Sequence(vec![value]);
+    }
+    let (path, leaf) = value.leaves().next().unwrap();
+    assert_eq!(path.len(), 10_000);
+    assert_eq!(*leaf, Value::Null);
+    while let Value::Sequence(mut sequence) = value {
+        value = sequence.pop().unwrap();
+    }
+}
+
+#[test]
+fn test_mapping_into_iter() {
+    let yaml = indoc! {"
+        c: 1
+        a: 2
+        b: 3
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let mapping = value.as_mapping().unwrap();
+
+    let keys: Vec<Value> = mapping.clone().into_keys().collect();
+    assert_eq!(keys, ["c", "a", "b"]);
+
+    let values: Vec<Value> = mapping.clone().into_values().collect();
+    assert_eq!(values, [1, 2, 3]);
+
+    let entries: Vec<(Value, Value)> = mapping.clone().into_iter().collect();
+    let expected: Vec<(Value, Value)> = mapping
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn test_mapping_iter_len_rev() {
+    let value: Value = serde_yaml::from_str("{c: 1, a: 2, b: 3}").unwrap();
+    let mut mapping = value.as_mapping().unwrap().clone();
+
+    assert_eq!(mapping.iter().len(), 3);
+    assert_eq!(mapping.keys().len(), 3);
+    assert_eq!(mapping.values().len(), 3);
+
+    let keys: Vec<&Value> = mapping.keys().rev().collect();
+    assert_eq!(keys, ["b", "a", "c"]);
+    let values: Vec<&Value> = mapping.values().rev().collect();
+    assert_eq!(values, [&3, &2, &1]);
+    assert_eq!(mapping.iter().next_back().unwrap().0, "b");
+    assert_eq!(mapping.iter_mut().rev().len(), 3);
+
+    let entries: Vec<(Value, Value)> = mapping.into_iter().rev().collect();
+    assert_eq!(entries[0], (Value::from("b"), Value::from(3)));
+}
+
+#[test]
+fn test_contains() {
+    let object: Value = serde_yaml::from_str("{ a: 1, 2: b }").unwrap();
+    assert!(object.contains_key("a"));
+    assert!(object.contains_key(2));
+    assert!(!object.contains_key("b"));
+    assert!(!object.contains(&Value::from(1)));
+
+    let sequence: Value = serde_yaml::from_str("[a, 1]").unwrap();
+    assert!(sequence.contains(&Value::from("a")));
+    assert!(sequence.contains(&Value::from(1)));
+    assert!(!sequence.contains(&Value::from("b")));
+    assert!(!sequence.contains_key(0));
+
+    let scalar = Value::from("a");
+    assert!(!scalar.contains_key("a"));
+    assert!(!scalar.contains(&Value::from("a")));
+}
+
+#[test]
+fn test_comments() {
+    let yaml = indoc! {"
+        # Top-level heading
+
+        # Listen addresses
+        # for the server
+        servers:
+          # Primary
+          - host: a
+            port: 1
+          - host: b
+            #
+            port: 2
+        limit: 10 # trailing
+    "};
+    let (value, comments) = serde_yaml::from_str_with_comments(yaml).unwrap();
+    assert_eq!(value["servers"][1]["port"], 2);
+    assert_eq!(comments.len(), 3);
+    assert_eq!(
+        comments.get("servers").unwrap(),
+        ["Listen addresses", "for the server"],
+    );
+    assert_eq!(comments.get("servers[0].host").unwrap(), ["Primary"]);
+    assert_eq!(comments.get("servers[1].port").unwrap(), [""]);
+    assert_eq!(comments.get("limit"), None);
+
+    let expected = indoc! {"
+        # Listen addresses
+        # for the server
+        servers:
+        # Primary
+        - host: a
+          port: 1
+        - host: b
+          #
+          port: 2
+        limit: 10
+    "};
+    let output = serde_yaml::to_string_with_comments(&value, &comments).unwrap();
+    assert_eq!(output, expected);
+
+    let (_, reread) = serde_yaml::from_str_with_comments(&output).unwrap();
+    assert_eq!(reread, comments);
+}
+
+#[test]
+fn test_untag_all() {
+    let yaml = indoc! {"
+        !Outer
+        a: !Inner
+          - !Int 1
+          - [!Int 2]
+        !Key b: !Str c
+        !One k: first
+        !Two k: second
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+
+    let expected: Value = serde_yaml::from_str(indoc! {"
+        a:
+          - 1
+          - [2]
+        b: c
+        k: second
+    "})
+    .unwrap();
+    assert_eq!(value.untagged(), expected);
+
+    let mut mutated = value.clone();
+    mutated.untag_all();
+    assert_eq!(mutated, expected);
+    assert_ne!(value, expected);
+}
+
+#[test]
+fn test_node_count_and_depth() {
+    let value = Value::Null;
+    assert_eq!(value.node_count(), 1);
+    assert_eq!(value.depth(), 0);
+
+    let value: Value = serde_yaml::from_str("!Tag [[], {}, {[k]: v}]").unwrap();
+    assert_eq!(value.node_count(), 7);
+    assert_eq!(value.depth(), 3);
+
+    let mut value = Value::Null;
+    for _ in 0..100_000 {
+        value = Value::Sequence(vec![value]);
+    }
+    assert_eq!(value.node_count(), 100_001);
+    assert_eq!(value.depth(), 100_000);
+    // Dismantle without recursing so that the drop does not overflow.
+    while let Value::Sequence(mut sequence) = value {
+        value = sequence.pop().unwrap();
+    }
+}
+
+#[test]
+fn test_mapping_remove_order() {
+    let value: Value = serde_yaml::from_str("{a: 1, b: 2, c: 3, d: 4}").unwrap();
+    let mapping = value.as_mapping().unwrap();
+
+    let mut shifted = mapping.clone();
+    assert_eq!(shifted.shift_remove("a"), Some(Value::from(1)));
+    assert_eq!(shifted.remove("b"), Some(Value::from(2)));
+    assert_eq!(shifted.remove_entry("z"), None);
+    let keys: Vec<&Value> = shifted.keys().collect();
+    assert_eq!(keys, ["c", "d"]);
+
+    let mut swapped = mapping.clone();
+    assert_eq!(swapped.swap_remove("a"), Some(Value::from(1)));
+    let keys: Vec<&Value> = swapped.keys().collect();
+    assert_eq!(keys, ["d", "b", "c"]);
+}
+
+#[test]
+fn test_coerce_strings_to_numbers() {
+    let yaml = indoc! {"
+        '1': '0x1F'
+        list: ['1_000', '-2', '1e3', '.inf', '007', 'null', 'yes', ' 1', '']
+        tagged: !Tag '5'
+    "};
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.coerce_strings_to_numbers();
+
+    let expected: Value = serde_yaml::from_str(indoc! {"
+        '1': 31
+        list: [1000, -2, 1000.0, .inf, '007', 'null', 'yes', ' 1', '']
+        tagged: !Tag '5'
+    "})
+    .unwrap();
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_binary() {
+    let yaml = indoc! {"
+        a: !!binary aGVsbG8=
+        b: !!binary |
+          aGVs
+          bG8h
+        c: !!binary '1234'
+        d: !!binary not*base64
+        e: aGVsbG8=
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(value["a"].as_bytes().unwrap(), b"hello");
+    assert_eq!(value["b"].as_bytes().unwrap(), b"hello!");
+    assert_eq!(value["c"].as_bytes().unwrap(), [0xd7, 0x6d, 0xf8]);
+    assert_eq!(value["d"].as_bytes(), None);
+    assert_eq!(value["e"].as_bytes(), None);
+
+    for bytes in [&b""[..], b"a", b"ab", b"abc", b"abcd", &[0, 0xff, 0x10]] {
+        let binary = Binary(bytes.to_vec());
+        let yaml = serde_yaml::to_string(&binary).unwrap();
+        assert!(yaml.starts_with("!!binary "), "{}", yaml);
+        assert_eq!(binary, serde_yaml::from_str(&yaml).unwrap());
+
+        let value = serde_yaml::to_value(&binary).unwrap();
+        assert_eq!(value, Value::from(binary.clone()));
+        assert_eq!(value.as_bytes().unwrap(), bytes);
+        assert_eq!(value, serde_yaml::from_str::<Value>(&yaml).unwrap());
+    }
+}
+
+#[test]
+fn test_prune_empty() {
+    let yaml = indoc! {"
+        keep: 1
+        nested:
+          empty: {}
+          list:
+            - []
+            - {a: []}
+            - null
+        tagged: !Tag {}
+        null: ~
+    "};
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.prune_empty(false);
+    let expected: Value = serde_yaml::from_str(indoc! {"
+        keep: 1
+        nested:
+          list:
+            - null
+        tagged: !Tag {}
+        null: ~
+    "})
+    .unwrap();
+    assert_eq!(value, expected);
+
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.prune_empty(true);
+    let expected: Value = serde_yaml::from_str("{keep: 1, tagged: !Tag {}}").unwrap();
+    assert_eq!(value, expected);
+
+    let mut value = Value::Sequence(vec![Value::Sequence(vec![])]);
+    value.prune_empty(false);
+    assert_eq!(value, Value::Sequence(vec![]));
+}
+
+#[test]
+fn test_into_accessors() {
+    let value: Value = serde_yaml::from_str("!Tag [1, 2]").unwrap();
+
+    let value = value.into_string().unwrap_err();
+    assert!(matches!(value, Value::Tagged(_)));
+    let value = value.into_mapping().unwrap_err();
+    assert!(matches!(value, Value::Tagged(_)));
+    assert_eq!(value.into_sequence().unwrap(), [1, 2]);
+
+    let value: Value = serde_yaml::from_str("{a: b}").unwrap();
+    let mapping = value.into_mapping().unwrap();
+    let string = mapping.into_iter().next().unwrap().1.into_string();
+    assert_eq!(string.unwrap(), "b");
+}
+
+#[test]
+fn test_type_name() {
+    let yaml = "[~, true, 1, 1.5, a, [], {}, !Tag x]";
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let names: Vec<&str> = value
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(Value::type_name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "null", "bool", "number", "number", "string", "sequence", "mapping", "tagged",
+        ],
+    );
+}
+
+#[test]
+fn test_is_empty() {
+    let yaml = "[~, '', [], {}, !Tag [], false, 0, ' ', [~], {a: ~}, !Tag x]";
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let empty: Vec<bool> = value
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(Value::is_empty)
+        .collect();
+    assert_eq!(
+        empty,
+        [
+            true, true, true, true, true, false, false, false, false, false, false
+        ],
+    );
+}
+
+#[test]
+fn test_structure_hash() {
+    let hash = |yaml| {
+        serde_yaml::from_str::<Value>(yaml)
+            .unwrap()
+            .structure_hash()
+    };
+
+    let base = hash("{a: 1, b: [x, y], c: !T {d: true}}");
+    assert_eq!(base, hash("{c: !T {d: false}, b: [z, w], a: -2.5}"));
+    assert_ne!(base, hash("{a: '1', b: [x, y], c: !T {d: true}}"));
+    assert_ne!(base, hash("{a: 1, b: [x], c: !T {d: true}}"));
+    assert_ne!(base, hash("{a: 1, b: [x, y], c: !U {d: true}}"));
+    assert_ne!(base, hash("{a: 1, b: [x, y], c: !T {e: true}}"));
+    assert_ne!(base, hash("{a: 1, b: [x, y]}"));
+}
+
+#[test]
+fn test_stable_hash() {
+    let hash = |yaml| serde_yaml::from_str::<Value>(yaml).unwrap().stable_hash();
+
+    // Pinned: these must not change between releases.
+    assert_eq!(
+        hash("{a: [1, -2, 2.5, true, ~], b: !T x}"),
+        0x3d631ed5bafacfa6
+    );
+    assert_eq!(hash("x"), 0x73d406607f1b2c5e);
+
+    let base = hash("{a: [1, -2, 2.5, true, ~], b: !T x}");
+    assert_eq!(base, hash("{b: !T x, a: [1, -2, 2.5, true, null]}"));
+    assert_ne!(base, hash("{a: [-2, 1, 2.5, true, ~], b: !T x}"));
+    assert_ne!(base, hash("{a: [1, -2, 2.5, true, ~], b: x}"));
+    assert_ne!(base, hash("{a: [1, -2, 2.5, true, ~], b: !T y}"));
+    assert_ne!(base, hash("{a: [1, -2, 2.5, true], b: !T x}"));
+    assert_ne!(hash("1"), hash("1.0"));
+    assert_ne!(hash("1"), hash("'1'"));
+    assert_eq!(hash("0.0"), hash("-0.0"));
+    assert_eq!(hash(".nan"), Value::from(-f64::NAN).stable_hash());
+}
+
+#[test]
+fn test_accessors_or() {
+    let value: Value = serde_yaml::from_str("{a: true, b: -7, c: text, d: !Tag 5}").unwrap();
+
+    assert!(value["a"].as_bool_or(false));
+    assert_eq!(value["b"].as_i64_or(0), -7);
+    assert_eq!(value["c"].as_str_or(""), "text");
+    assert_eq!(value["d"].as_i64_or(0), 5);
+
+    assert!(!value["c"].as_bool_or(false));
+    assert_eq!(value["a"].as_i64_or(1), 1);
+    assert_eq!(value["b"].as_str_or("none"), "none");
+    assert_eq!(value["x"]["y"].as_i64_or(2), 2);
+}
+
+#[test]
+fn test_ensure() {
+    let mut value = Value::Null;
+    value
+        .ensure_mapping()
+        .unwrap()
+        .insert("a".into(), Value::Null);
+    value["a"].ensure_sequence().unwrap().push(1.into());
+    value["a"].ensure_sequence().unwrap().push(2.into());
+    assert_eq!(value, serde_yaml::from_str::<Value>("a: [1, 2]").unwrap());
+
+    let mut value: Value = serde_yaml::from_str("!Tag {b: 1}").unwrap();
+    value.ensure_mapping().unwrap().insert("c".into(), 2.into());
+    assert_eq!(
+        value,
+        serde_yaml::from_str::<Value>("!Tag {b: 1, c: 2}").unwrap()
+    );
+
+    let mut value = Value::from(1);
+    let error = value.ensure_mapping().unwrap_err();
+    assert_eq!(error.to_string(), "expected a mapping, found number");
+    let error = value.ensure_sequence().unwrap_err();
+    assert_eq!(error.to_string(), "expected a sequence, found number");
+    assert_eq!(value, 1);
+}
+
+#[test]
+fn test_try_from() {
+    let value: Value = serde_yaml::from_str("!Tag {a: [1, 2]}").unwrap();
+    let mut mapping = Mapping::try_from(value).unwrap();
+    let sequence = Sequence::try_from(mapping.remove("a").unwrap()).unwrap();
+    assert_eq!(sequence, [1, 2]);
+    let number = Number::try_from(sequence[0].clone()).unwrap();
+    assert_eq!(Value::from(number), 1);
+    assert_eq!(Value::from(mapping), Value::Mapping(Mapping::new()));
+
+    let error = Mapping::try_from(Value::Null).unwrap_err();
+    assert_eq!(error.to_string(), "expected a mapping, found null");
+    let error = Sequence::try_from(Value::from(true)).unwrap_err();
+    assert_eq!(error.to_string(), "expected a sequence, found bool");
+    let error = Number::try_from(Value::from("1")).unwrap_err();
+    assert_eq!(error.to_string(), "expected a number, found string");
+}
+
+#[test]
+fn test_try_from_scalar() {
+    let value: Value = serde_yaml::from_str("{s: !T text, b: true, i: -7, u: 7, f: 0.5}").unwrap();
+    let mapping = Mapping::try_from(value).unwrap();
+    let get = |key: &str| mapping[key].clone();
+    assert_eq!(String::try_from(get("s")).unwrap(), "text");
+    assert!(bool::try_from(get("b")).unwrap());
+    assert_eq!(i64::try_from(get("i")).unwrap(), -7);
+    assert_eq!(u64::try_from(get("u")).unwrap(), 7);
+    assert_eq!(f64::try_from(get("f")).unwrap(), 0.5);
+    assert_eq!(f64::try_from(get("i")).unwrap(), -7.0);
+
+    let error = String::try_from(get("i")).unwrap_err();
+    assert_eq!(error.to_string(), "expected a string, found number");
+    let error = bool::try_from(get("s")).unwrap_err();
+    assert_eq!(error.to_string(), "expected a bool, found string");
+    let error = i64::try_from(Value::from(u64::MAX)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a 64-bit signed integer, found number"
+    );
+    let error = u64::try_from(get("i")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a 64-bit unsigned integer, found number"
+    );
+    let error = f64::try_from(Value::Null).unwrap_err();
+    assert_eq!(error.to_string(), "expected a number, found null");
+
+    fn port(value: Value) -> serde_yaml::Result<u64> {
+        let port = u64::try_from(value)?;
+        Ok(port)
+    }
+    assert!(port(Value::from("http")).is_err());
+}
+
+#[test]
+fn test_as_single_entry() {
+    let value: Value = serde_yaml::from_str("!Shape {Square: 3}").unwrap();
+    assert_eq!(
+        value.as_single_entry(),
+        Some((&Value::from("Square"), &Value::from(3)))
+    );
+
+    for yaml in ["{}", "{a: 1, b: 2}", "[a]", "a"] {
+        let value: Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(value.as_single_entry(), None);
+    }
+}
+
+#[test]
+fn test_get_or_null_mut() {
+    let mut value = Value::Null;
+    *value.get_or_null_mut("a").unwrap() = 1.into();
+    assert_eq!(value.get_or_null_mut("a"), Some(&mut Value::from(1)));
+    assert_eq!(value.get_or_null_mut(2), Some(&mut Value::Null));
+    assert_eq!(
+        value,
+        serde_yaml::from_str::<Value>("{a: 1, 2: null}").unwrap()
+    );
+
+    let mut value: Value = serde_yaml::from_str("!Config {}").unwrap();
+    *value.get_or_null_mut("b").unwrap() = true.into();
+    assert_eq!(value["b"], true);
+
+    for yaml in ["[a]", "a", "1"] {
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(value.get_or_null_mut("a"), None);
+    }
+}
+
+#[test]
+fn test_validate() {
+    let schema = Schema::new(ValueKind::Mapping)
+        .required("name", Schema::new(ValueKind::String))
+        .optional("debug", Schema::new(ValueKind::Bool))
+        .required(
+            "servers",
+            Schema::new(ValueKind::Sequence).items(
+                Schema::new(ValueKind::Mapping)
+                    .required("host", Schema::new(ValueKind::String))
+                    .optional("port", Schema::new(ValueKind::Number)),
+            ),
+        )
+        .optional("extra", Schema::any());
+
+    let yaml = indoc! {"
+        name: web
+        servers:
+          - host: a
+          - !Backup {host: b, port: 81}
+        extra: [anything]
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(value.validate(&schema), Ok(()));
+
+    let yaml = indoc! {"
+        debug: 1
+        servers:
+          - port: 80
+          - host: b
+            port: '81'
+          - c
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    let errors = value.validate(&schema).unwrap_err();
+    assert_eq!(errors.len(), 5);
+    assert!(errors[0].path().is_empty());
+    assert_eq!(errors[0].message(), "missing required key `name`");
+    assert_eq!(errors[1].path(), [Value::from("debug")]);
+    assert_eq!(errors[2].path(), [Value::from("servers"), Value::from(0)]);
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "missing required key `name`",
+            "debug: expected a bool, found number",
+            "servers[0]: missing required key `host`",
+            "servers[1].port: expected a number, found string",
+            "servers[2]: expected a mapping, found string",
+        ],
+    );
+
+    let errors = Value::Null.validate(&schema).unwrap_err();
+    assert_eq!(errors[0].to_string(), "expected a mapping, found null");
+}
+
+#[test]
+fn test_entry_path() {
+    let mut value: Value = serde_yaml::from_str("{a: {b: ~}, t: !Tag {}}").unwrap();
+    value
+        .entry_path(["a", "b", "c"])
+        .clone_from(&Value::from(1));
+    *value.entry_path(["t", "d"]) = true.into();
+    *value.entry_path("x.y".split('.')) = "z".into();
+
+    let yaml = "{a: {b: {c: 1}}, t: !Tag {d: true}, x: {y: z}}";
+    assert_eq!(value, serde_yaml::from_str::<Value>(yaml).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "cannot access key \"b\" in YAML sequence")]
+fn test_entry_path_conflict() {
+    let mut value: Value = serde_yaml::from_str("{a: [1]}").unwrap();
+    value.entry_path(["a", "b"]);
+}
+
+#[test]
+fn test_mapping_entry() {
+    let mut mapping = Mapping::new();
+    mapping.insert("a".into(), 1.into());
+
+    match mapping.entry("a".into()) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), "a");
+            assert_eq!(entry.get(), 1);
+            *entry.get_mut() = 2.into();
+            assert_eq!(entry.insert(3.into()), 2);
+        }
+        Entry::Vacant(_) => panic!("expected occupied entry"),
+    }
+    match mapping.entry("b".into()) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), "b");
+            assert_eq!(*entry.insert(4.into()), 4);
+        }
+        Entry::Occupied(_) => panic!("expected vacant entry"),
+    }
+
+    let inserted = mapping
+        .entry("c".into())
+        .or_insert_with_key(|key| format!("{}!", key.as_str().unwrap()).into());
+    assert_eq!(*inserted, "c!");
+    assert!(mapping.entry("d".into()).or_default().is_null());
+    assert_eq!(*mapping.entry("a".into()).or_default(), 3);
+
+    match mapping.entry("b".into()) {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 4),
+        Entry::Vacant(_) => panic!("expected occupied entry"),
+    }
+    let keys: Vec<&Value> = mapping.keys().collect();
+    assert_eq!(keys, ["a", "d", "c"]);
+}
+
+#[test]
+fn test_mapping_order() {
+    let mut mapping: Mapping = ["a", "b", "c", "d", "e"]
+        .into_iter()
+        .map(|key| (Value::from(key), Value::Null))
+        .collect();
+    assert_eq!(mapping.insert("b".into(), 2.into()), Some(Value::Null));
+    assert_eq!(mapping.insert("f".into(), 6.into()), None);
+
+    assert_eq!(mapping.shift_remove("a"), Some(Value::Null));
+    assert_eq!(mapping.swap_remove("c"), Some(Value::Null));
+    assert_eq!(mapping.remove("x"), None);
+    let keys: Vec<&Value> = mapping.keys().collect();
+    assert_eq!(keys, ["b", "f", "d", "e"]);
+    let values: Vec<&Value> = mapping.values().rev().collect();
+    assert_eq!(
+        values,
+        [&Value::Null, &Value::Null, &Value::from(6), &Value::from(2)]
+    );
+
+    mapping.retain(|_, value| !value.is_null());
+    let mut reordered = Mapping::new();
+    reordered.insert("f".into(), 6.into());
+    reordered.insert("b".into(), 2.into());
+    assert_eq!(mapping, reordered);
+    assert_eq!(mapping.into_iter().len(), 2);
+}
+
+#[test]
+fn test_mapping_index() {
+    let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2, c: 3}").unwrap();
+    assert_eq!(
+        mapping.get_index(0),
+        Some((&Value::from("a"), &Value::from(1)))
+    );
+    assert_eq!(mapping.get_index(3), None);
+    assert!(mapping.get_index_mut(3).is_none());
+    *mapping.get_index_mut(2).unwrap().1 = Value::from(30);
+
+    assert_eq!(mapping.insert_index(1, "d".into(), 4.into()), None);
+    assert_eq!(
+        mapping.insert_index(0, "c".into(), 5.into()),
+        Some(Value::from(30))
+    );
+    mapping.move_index(1, 3);
+    let keys: Vec<&Value> = mapping.keys().collect();
+    assert_eq!(keys, ["c", "d", "b", "a"]);
+    assert_eq!(mapping.get("c"), Some(&Value::from(5)));
+}
+
+#[test]
+fn test_mapping_rename_key() {
+    let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2, c: 3, d: 4}").unwrap();
+    assert!(mapping.rename_key(&"b".into(), 20.into()));
+    assert!(mapping.rename_key(&"c".into(), "c".into()));
+    assert!(!mapping.rename_key(&"x".into(), "y".into()));
+    let keys: Vec<&Value> = mapping.keys().collect();
+    assert_eq!(
+        keys,
+        [
+            &Value::from("a"),
+            &Value::from(20),
+            &"c".into(),
+            &"d".into()
+        ]
+    );
+
+    // The existing entry for the new key is dropped, wherever it is.
+    assert!(mapping.rename_key(&"c".into(), "a".into()));
+    assert!(mapping.rename_key(&20.into(), "d".into()));
+    let entries: Vec<(&Value, &Value)> = mapping.iter().collect();
+    let expected = [("d", 2), ("a", 3)];
+    assert_eq!(entries.len(), expected.len());
+    for ((k, v), (key, value)) in entries.into_iter().zip(expected) {
+        assert_eq!((k, v), (&Value::from(key), &Value::from(value)));
+    }
+}
+
+#[test]
+fn test_mapping_append() {
+    let mut mapping: Mapping = serde_yaml::from_str("{a: 1, b: 2}").unwrap();
+    let mut other: Mapping = serde_yaml::from_str("{c: 3, a: 4, d: 5}").unwrap();
+    mapping.append(&mut other);
+    assert!(other.is_empty());
+
+    let entries: Vec<(&Value, &Value)> = mapping.iter().collect();
+    let expected = [("a", 4), ("b", 2), ("c", 3), ("d", 5)];
+    assert_eq!(entries.len(), expected.len());
+    for ((k, v), (key, value)) in entries.into_iter().zip(expected) {
+        assert_eq!((k, v), (&Value::from(key), &Value::from(value)));
+    }
+}
+
+#[test]
+fn test_yaml_macro() {
+    let name = "demo";
+    let ports = vec![80, 443];
+    let value = yaml!({
+        "name": name,
+        "ports": ports,
+        "replicas": 1 + 2,
+        "labels": {},
+        "env": [{ "key": "DEBUG", "value": false }, null],
+        ("ti".to_owned() + "meout"): 1.5,
+    });
+
+    let yaml = indoc! {"
+        name: demo
+        ports:
+        - 80
+        - 443
+        replicas: 3
+        labels: {}
+        env:
+        - key: DEBUG
+          value: false
+        - null
+        timeout: 1.5
+    "};
+    assert_eq!(value, serde_yaml::from_str::<Value>(yaml).unwrap());
+
+    assert_eq!(yaml!(null), Value::Null);
+    assert_eq!(yaml!([]), Value::Sequence(Vec::new()));
+    assert_eq!(
+        yaml!([true, "x", [1]]),
+        serde_yaml::from_str::<Value>("[true, x, [1]]").unwrap(),
+    );
+}
+
+#[test]
+fn test_get_ci() {
+    let value: Value = serde_yaml::from_str("{1: one, HOST: a, Host: b, ÉTÉ: c}").unwrap();
+    let mapping = value.as_mapping().unwrap();
+
+    assert_eq!(mapping.get_ci("host").unwrap(), "a");
+    assert_eq!(mapping.get_ci("été").unwrap(), "c");
+    assert_eq!(mapping.get_ci("1"), None);
+    assert_eq!(mapping.get_ci("port"), None);
+
+    assert_eq!(value.get_ci("hOsT").unwrap(), "a");
+    assert_eq!(Value::from("host").get_ci("host"), None);
+}
+
+#[test]
+fn test_map_strings() {
+    let yaml = indoc! {"
+        a: 1
+        b: x
+        B: !Tag y
+        c: [x, {d: [x]}]
+    "};
+    let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+    value.map_strings(str::to_uppercase);
+
+    let expected = indoc! {"
+        A: 1
+        B: !Tag Y
+        C: [X, {D: [X]}]
+    "};
+    assert_eq!(value, serde_yaml::from_str::<Value>(expected).unwrap());
+    let keys: Vec<&Value> = value.as_mapping().unwrap().keys().collect();
+    assert_eq!(keys, ["A", "B", "C"]);
+}
+
+#[test]
+fn test_core_tag_to_value() {
+    let binary = Binary(b"hello".to_vec());
+    let value = serde_yaml::to_value(&binary).unwrap();
+    assert_eq!(value, Value::from(binary));
+    assert_eq!(value.as_bytes(), Some(b"hello".to_vec()));
+}
+
+#[test]
+fn test_value_to_value() {
+    let yaml = indoc! {"
+        a: [1, -2.5, ~, true]
+        ? [k]
+        : !Tag {b: c}
+        d: !!binary aGVsbG8=
+    "};
+    let value: Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(serde_yaml::to_value(&value).unwrap(), value);
+}
+
+#[test]
+fn test_get_many_mut() {
+    let mut value: Value = serde_yaml::from_str("!Tag {a: 1, b: [2], 1: 3}").unwrap();
+
+    let [b, a] = value.get_many_mut(["b", "a"]).unwrap();
+    b.as_sequence_mut().unwrap().push(a.clone());
+    *a = Value::Null;
+    let expected: Value = serde_yaml::from_str("{a: null, b: [2, 1], 1: 3}").unwrap();
+    assert_eq!(value.untagged(), expected);
+
+    assert!(value.get_many_mut(["a", "1"]).is_none());
+    assert!(value.get_many_mut(["b", "b"]).is_none());
+    assert!(value.get_many_mut::<0>([]).is_some());
+    assert!(Value::Null.get_many_mut(["a"]).is_none());
+}